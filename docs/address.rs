@@ -0,0 +1,374 @@
+//! Classification of address bytes by their header (CIP-19).
+//!
+//! `Address` and `RewardAccount` only carry raw bytes.  The first byte of a
+//! Shelley address holds the address type in its high nibble and the network
+//! id in its low nibble; the rest are the payment and stake credentials, or a
+//! stake pointer.  Byron bootstrap addresses are instead a CBOR-wrapped
+//! payload with a CRC32 checksum, whose network is given by the optional
+//! network magic attribute.
+
+use std::fmt;
+
+use crate::error_parser::{decode_term, Term};
+use crate::rust_rule_errors::*;
+
+/// Error returned when address bytes cannot be classified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    /// The header names an address type that is not defined.
+    UnknownHeader(u8),
+    /// The bytes do not have the layout required by their header.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressError::UnknownHeader(header) => {
+                write!(f, "unknown address header 0x{header:02x}")
+            }
+            AddressError::Malformed(msg) => write!(f, "malformed address: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+/// Location of a stake registration certificate, referenced by pointer
+/// addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pointer {
+    pub slot: u64,
+    pub tx_index: u64,
+    pub cert_index: u64,
+}
+
+/// Contents of a Byron bootstrap address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootstrapAddress {
+    /// Hash of the address spending data and attributes.
+    pub root: Hash28,
+    /// Protocol magic of the test network, absent on mainnet.
+    pub network_magic: Option<u32>,
+    /// Byron address type: 0 for public keys, 2 for redemption keys.
+    pub address_type: u64,
+}
+
+/// An address classified by its header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedAddress {
+    Base {
+        network: NetworkId,
+        payment: Credential,
+        stake: StakeCredential,
+    },
+    Pointer {
+        network: NetworkId,
+        payment: Credential,
+        pointer: Pointer,
+    },
+    Enterprise {
+        network: NetworkId,
+        payment: Credential,
+    },
+    Reward {
+        network: NetworkId,
+        stake: StakeCredential,
+    },
+    Bootstrap(BootstrapAddress),
+}
+
+impl ParsedAddress {
+    /// The network the address belongs to.  Byron addresses without a
+    /// network magic are mainnet addresses.
+    pub fn network(&self) -> NetworkId {
+        match self {
+            ParsedAddress::Base { network, .. }
+            | ParsedAddress::Pointer { network, .. }
+            | ParsedAddress::Enterprise { network, .. }
+            | ParsedAddress::Reward { network, .. } => *network,
+            ParsedAddress::Bootstrap(address) => match address.network_magic {
+                Some(_) => NetworkId(0),
+                None => NetworkId(1),
+            },
+        }
+    }
+
+    /// Short name of the address type.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParsedAddress::Base { .. } => "base",
+            ParsedAddress::Pointer { .. } => "pointer",
+            ParsedAddress::Enterprise { .. } => "enterprise",
+            ParsedAddress::Reward { .. } => "reward",
+            ParsedAddress::Bootstrap(_) => "bootstrap",
+        }
+    }
+
+    pub fn payment(&self) -> Option<&Credential> {
+        match self {
+            ParsedAddress::Base { payment, .. }
+            | ParsedAddress::Pointer { payment, .. }
+            | ParsedAddress::Enterprise { payment, .. } => Some(payment),
+            ParsedAddress::Reward { .. } | ParsedAddress::Bootstrap(_) => None,
+        }
+    }
+
+    pub fn stake(&self) -> Option<&StakeCredential> {
+        match self {
+            ParsedAddress::Base { stake, .. } | ParsedAddress::Reward { stake, .. } => Some(stake),
+            _ => None,
+        }
+    }
+}
+
+impl Address {
+    pub fn parse(&self) -> Result<ParsedAddress, AddressError> {
+        parse_address(&self.0)
+    }
+}
+
+impl RewardAccount {
+    /// Parse the account, which must be a reward address.
+    pub fn parse(&self) -> Result<ParsedAddress, AddressError> {
+        match parse_address(&self.0)? {
+            reward @ ParsedAddress::Reward { .. } => Ok(reward),
+            _ => Err(AddressError::Malformed("not a reward address")),
+        }
+    }
+}
+
+/// Classify address bytes by their header.
+///
+/// # Errors
+///
+/// Returns [`AddressError::UnknownHeader`] for the undefined address types
+/// and [`AddressError::Malformed`] if the bytes do not match their header.
+pub fn parse_address(bytes: &[u8]) -> Result<ParsedAddress, AddressError> {
+    let (&header, body) = bytes
+        .split_first()
+        .ok_or(AddressError::Malformed("empty address"))?;
+    let network = NetworkId(header & 0x0f);
+    let kind = header >> 4;
+    let credential = |script: bool, bytes: &[u8]| -> Result<Credential, AddressError> {
+        let hash = Hash28(
+            bytes
+                .try_into()
+                .map_err(|_| AddressError::Malformed("unexpected address length"))?,
+        );
+        Ok(if script {
+            Credential::ScriptHash(ScriptHash(hash))
+        } else {
+            Credential::KeyHash(AddrKeyHash(hash))
+        })
+    };
+    match kind {
+        0..=3 if body.len() == 56 => Ok(ParsedAddress::Base {
+            network,
+            payment: credential(kind & 1 != 0, &body[..28])?,
+            stake: credential(kind & 2 != 0, &body[28..])?,
+        }),
+        4 | 5 if body.len() > 28 => Ok(ParsedAddress::Pointer {
+            network,
+            payment: credential(kind & 1 != 0, &body[..28])?,
+            pointer: pointer(&body[28..])?,
+        }),
+        6 | 7 => Ok(ParsedAddress::Enterprise {
+            network,
+            payment: credential(kind & 1 != 0, body)?,
+        }),
+        14 | 15 => Ok(ParsedAddress::Reward {
+            network,
+            stake: credential(kind & 1 != 0, body)?,
+        }),
+        8 => bootstrap(bytes).map(ParsedAddress::Bootstrap),
+        0..=5 => Err(AddressError::Malformed("unexpected address length")),
+        _ => Err(AddressError::UnknownHeader(header)),
+    }
+}
+
+/// Three variable-length naturals: seven bits per byte, most significant
+/// first, with the high bit set on every byte but the last.
+fn pointer(mut bytes: &[u8]) -> Result<Pointer, AddressError> {
+    let mut natural = || -> Result<u64, AddressError> {
+        let mut value: u64 = 0;
+        loop {
+            let (&byte, rest) = bytes
+                .split_first()
+                .ok_or(AddressError::Malformed("truncated pointer"))?;
+            bytes = rest;
+            value = value
+                .checked_mul(128)
+                .ok_or(AddressError::Malformed("pointer out of range"))?
+                | u64::from(byte & 0x7f);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    };
+    let pointer = Pointer {
+        slot: natural()?,
+        tx_index: natural()?,
+        cert_index: natural()?,
+    };
+    if bytes.is_empty() {
+        Ok(pointer)
+    } else {
+        Err(AddressError::Malformed("trailing bytes after pointer"))
+    }
+}
+
+/// `[24(payload), crc32(payload)]` with the payload `[root, attributes, type]`.
+fn bootstrap(bytes: &[u8]) -> Result<BootstrapAddress, AddressError> {
+    let malformed = || AddressError::Malformed("invalid bootstrap address");
+    let term = decode_term(bytes).map_err(|_| malformed())?;
+    let (payload, checksum) = match &term {
+        Term::Array(items) => match items.as_slice() {
+            [Term::Tagged(24, payload), Term::Unsigned(checksum)] => match payload.as_ref() {
+                Term::Bytes(payload) => (payload, *checksum),
+                _ => return Err(malformed()),
+            },
+            _ => return Err(malformed()),
+        },
+        _ => return Err(malformed()),
+    };
+    if u64::from(crc32(payload)) != checksum {
+        return Err(AddressError::Malformed(
+            "bootstrap address checksum mismatch",
+        ));
+    }
+    let payload = decode_term(payload.as_slice()).map_err(|_| malformed())?;
+    let Term::Array(items) = payload else {
+        return Err(malformed());
+    };
+    let [Term::Bytes(root), Term::Map(attributes), Term::Unsigned(address_type)] = items.as_slice()
+    else {
+        return Err(malformed());
+    };
+    let root = Hash28(root.as_slice().try_into().map_err(|_| malformed())?);
+    let network_magic = attributes
+        .iter()
+        .find(|(key, _)| *key == Term::Unsigned(2))
+        .map(|(_, value)| match value {
+            Term::Bytes(magic) => match decode_term(magic.as_slice()) {
+                Ok(Term::Unsigned(magic)) => u32::try_from(magic).map_err(|_| malformed()),
+                _ => Err(malformed()),
+            },
+            _ => Err(malformed()),
+        })
+        .transpose()?;
+    Ok(BootstrapAddress {
+        root,
+        network_magic,
+        address_type: *address_type,
+    })
+}
+
+/// CRC-32 (IEEE 802.3), as used by Byron address checksums.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ciborium::{ser::into_writer, value::Value};
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        into_writer(value, &mut buffer).expect("encode test value");
+        buffer
+    }
+
+    #[test]
+    fn classifies_shelley_headers() {
+        let mut base = vec![0x21];
+        base.extend([1; 28]);
+        base.extend([2; 28]);
+        assert_eq!(
+            Address(base).parse().unwrap(),
+            ParsedAddress::Base {
+                network: NetworkId(1),
+                payment: Credential::KeyHash(AddrKeyHash(Hash28([1; 28]))),
+                stake: Credential::ScriptHash(ScriptHash(Hash28([2; 28]))),
+            }
+        );
+
+        // Pointer (2498243, 27, 3), from the CIP-19 test vectors.
+        let mut pointer = vec![0x40];
+        pointer.extend([1; 28]);
+        pointer.extend([0x81, 0x98, 0xbd, 0x43, 0x1b, 0x03]);
+        let parsed = Address(pointer).parse().unwrap();
+        assert_eq!(parsed.kind(), "pointer");
+        assert_eq!(parsed.network(), NetworkId(0));
+        assert!(matches!(
+            parsed,
+            ParsedAddress::Pointer {
+                pointer: Pointer {
+                    slot: 2_498_243,
+                    tx_index: 27,
+                    cert_index: 3
+                },
+                ..
+            }
+        ));
+
+        let mut reward = vec![0xf0];
+        reward.extend([3; 28]);
+        assert_eq!(
+            RewardAccount(reward.clone()).parse().unwrap().kind(),
+            "reward"
+        );
+        reward[0] = 0x60;
+        assert_eq!(
+            RewardAccount(reward).parse(),
+            Err(AddressError::Malformed("not a reward address"))
+        );
+        assert_eq!(
+            parse_address(&[0x90; 29]),
+            Err(AddressError::UnknownHeader(0x90))
+        );
+    }
+
+    #[test]
+    fn reads_bootstrap_network_magic() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+
+        let magic = encode(&Value::Integer(1_097_911_063.into()));
+        let payload = encode(&Value::Array(vec![
+            Value::Bytes(vec![4; 28]),
+            Value::Map(vec![(Value::Integer(2.into()), Value::Bytes(magic))]),
+            Value::Integer(0.into()),
+        ]));
+        let checksum = crc32(&payload);
+        let address = encode(&Value::Array(vec![
+            Value::Tag(24, Box::new(Value::Bytes(payload))),
+            Value::Integer(checksum.into()),
+        ]));
+
+        let parsed = parse_address(&address).unwrap();
+        assert_eq!(parsed.kind(), "bootstrap");
+        assert_eq!(parsed.network(), NetworkId(0));
+        assert!(matches!(
+            parsed,
+            ParsedAddress::Bootstrap(BootstrapAddress {
+                network_magic: Some(1_097_911_063),
+                ..
+            })
+        ));
+
+        let mut corrupted = address;
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 1;
+        assert_eq!(
+            parse_address(&corrupted),
+            Err(AddressError::Malformed(
+                "bootstrap address checksum mismatch"
+            ))
+        );
+    }
+}
@@ -0,0 +1,453 @@
+//! Decoding of the hard-fork combinator envelope that wraps the predicate
+//! failures a node returns when it rejects a transaction.
+//!
+//! Over local transaction submission the node does not send a bare
+//! `ApplyTxError`: consensus first tags it with the index of the era whose
+//! ledger rejected the transaction (`encodeNS`), and, when the hard-fork
+//! combinator is enabled for the connection, wraps the result in a
+//! one-element list.  A transaction from the wrong era is reported instead as
+//! a pair of era descriptions.  In CDDL terms:
+//!
+//! ```text
+//! hfc_apply_tx_err = [era_apply_tx_err]          ; from the ledger's era
+//!                  / [era_info, era_info]        ; transaction from another era
+//!                  / era_apply_tx_err            ; combinator disabled
+//! era_apply_tx_err = [era_index, [+ ledger_failure]]
+//! era_info         = [era_index, era_name]
+//! ```
+//!
+//! The failures inside are the era's LEDGER predicate failures, decoded with
+//! the `FromTaggedTree` impls of `failure_decoder.rs`.  When the envelope is
+//! decoded from bytes, errors in a failure are reported at its byte range.
+
+use crate::era::*;
+use crate::error_parser::{
+    decode_spanned_term, ParseError, PathSegment, SpanTree, TaggedTree, Term,
+};
+use crate::failure_decoder::{FromTaggedTree, FromTerm};
+use crate::rust_rule_errors::*;
+
+/// Era indices assigned by the Cardano hard-fork combinator.
+pub const BYRON_ERA_INDEX: u64 = 0;
+pub const SHELLEY_ERA_INDEX: u64 = 1;
+pub const ALLEGRA_ERA_INDEX: u64 = 2;
+pub const MARY_ERA_INDEX: u64 = 3;
+pub const ALONZO_ERA_INDEX: u64 = 4;
+pub const BABBAGE_ERA_INDEX: u64 = 5;
+pub const CONWAY_ERA_INDEX: u64 = 6;
+pub const DIJKSTRA_ERA_INDEX: u64 = 7;
+
+/// A transaction rejection as reported by the hard-fork combinator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HardForkApplyTxErr {
+    /// The ledger of the current era rejected the transaction.
+    FromEra(EraApplyTxError),
+    /// The transaction was built for a different era than the ledger's.
+    WrongEra {
+        transaction_era: EraInfo,
+        ledger_era: EraInfo,
+    },
+}
+
+/// Era description carried by a wrong-era rejection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EraInfo {
+    pub index: u64,
+    pub name: String,
+}
+
+/// The `ApplyTxError` of one era: its non-empty list of LEDGER failures.
+///
+/// Allegra and Mary keep the Shelley LEDGER and UTXOW rules around their own
+/// UTXO rule; Dijkstra reuses the Conway LEDGER failures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EraApplyTxError {
+    Shelley(NonEmpty<shelley::LedgerPredicateFailure<shelley::UtxoPredicateFailure<Shelley>>>),
+    Allegra(NonEmpty<allegra::LedgerPredicateFailure<Allegra>>),
+    Mary(NonEmpty<mary::LedgerPredicateFailure<Mary>>),
+    Alonzo(NonEmpty<alonzo::LedgerPredicateFailure<Alonzo>>),
+    Babbage(NonEmpty<babbage::LedgerPredicateFailure<Babbage>>),
+    Conway(NonEmpty<conway::LedgerPredicateFailure<Conway>>),
+    Dijkstra(NonEmpty<dijkstra::LedgerPredicateFailure<Dijkstra>>),
+}
+
+impl EraApplyTxError {
+    /// Hard-fork combinator index of the era.
+    pub fn era_index(&self) -> u64 {
+        match self {
+            EraApplyTxError::Shelley(_) => SHELLEY_ERA_INDEX,
+            EraApplyTxError::Allegra(_) => ALLEGRA_ERA_INDEX,
+            EraApplyTxError::Mary(_) => MARY_ERA_INDEX,
+            EraApplyTxError::Alonzo(_) => ALONZO_ERA_INDEX,
+            EraApplyTxError::Babbage(_) => BABBAGE_ERA_INDEX,
+            EraApplyTxError::Conway(_) => CONWAY_ERA_INDEX,
+            EraApplyTxError::Dijkstra(_) => DIJKSTRA_ERA_INDEX,
+        }
+    }
+
+    /// Name of the era, as consensus reports it.
+    pub fn era_name(&self) -> &'static str {
+        match self {
+            EraApplyTxError::Shelley(_) => Shelley::NAME,
+            EraApplyTxError::Allegra(_) => Allegra::NAME,
+            EraApplyTxError::Mary(_) => Mary::NAME,
+            EraApplyTxError::Alonzo(_) => Alonzo::NAME,
+            EraApplyTxError::Babbage(_) => Babbage::NAME,
+            EraApplyTxError::Conway(_) => Conway::NAME,
+            EraApplyTxError::Dijkstra(_) => Dijkstra::NAME,
+        }
+    }
+}
+
+impl FromTerm for EraApplyTxError {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        EraApplyTxError::from_spanned_term(term, None)
+    }
+}
+
+impl EraApplyTxError {
+    /// Decode `[era_index, [+ ledger_failure]]` with the byte ranges of the
+    /// term, if known.
+    pub fn from_spanned_term(term: &Term, spans: Option<&SpanTree>) -> Result<Self, ParseError> {
+        let spans = spans.and_then(|spans| spans.child(1));
+        let (index, failures) = match term {
+            Term::Array(items) if items.len() == 2 => (u64::from_term(&items[0])?, &items[1]),
+            _ => {
+                return Err(ParseError::Malformed(
+                    "expected an [era index, failures] pair",
+                ))
+            }
+        };
+        match index {
+            BYRON_ERA_INDEX => Err(ParseError::Unsupported("Byron transaction errors")),
+            SHELLEY_ERA_INDEX => {
+                ledger_failures(failures, spans, Shelley::NAME).map(EraApplyTxError::Shelley)
+            }
+            ALLEGRA_ERA_INDEX => {
+                ledger_failures(failures, spans, Allegra::NAME).map(EraApplyTxError::Allegra)
+            }
+            MARY_ERA_INDEX => {
+                ledger_failures(failures, spans, Mary::NAME).map(EraApplyTxError::Mary)
+            }
+            ALONZO_ERA_INDEX => {
+                ledger_failures(failures, spans, Alonzo::NAME).map(EraApplyTxError::Alonzo)
+            }
+            BABBAGE_ERA_INDEX => {
+                ledger_failures(failures, spans, Babbage::NAME).map(EraApplyTxError::Babbage)
+            }
+            CONWAY_ERA_INDEX => {
+                ledger_failures(failures, spans, Conway::NAME).map(EraApplyTxError::Conway)
+            }
+            DIJKSTRA_ERA_INDEX => {
+                ledger_failures(failures, spans, Dijkstra::NAME).map(EraApplyTxError::Dijkstra)
+            }
+            tag => Err(ParseError::UnknownConstructor {
+                family: "HardForkApplyTxErr era",
+                tag,
+            }),
+        }
+    }
+}
+
+impl FromTerm for EraInfo {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (index, name) = <(u64, String)>::from_term(term)?;
+        Ok(EraInfo { index, name })
+    }
+}
+
+impl FromTerm for HardForkApplyTxErr {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        HardForkApplyTxErr::from_spanned_term(term, None)
+    }
+}
+
+impl HardForkApplyTxErr {
+    /// Decode the envelope with the byte ranges of the term, if known.
+    pub fn from_spanned_term(term: &Term, spans: Option<&SpanTree>) -> Result<Self, ParseError> {
+        match term {
+            Term::Array(items) => match items.as_slice() {
+                [Term::Unsigned(_), _] => {
+                    EraApplyTxError::from_spanned_term(term, spans).map(Self::FromEra)
+                }
+                [inner] => {
+                    let spans = spans.and_then(|spans| spans.child(0));
+                    EraApplyTxError::from_spanned_term(inner, spans).map(Self::FromEra)
+                }
+                [transaction_era, ledger_era] => Ok(HardForkApplyTxErr::WrongEra {
+                    transaction_era: EraInfo::from_term(transaction_era)?,
+                    ledger_era: EraInfo::from_term(ledger_era)?,
+                }),
+                _ => Err(ParseError::Malformed(
+                    "unexpected hard-fork envelope length",
+                )),
+            },
+            _ => Err(ParseError::Malformed("expected a hard-fork envelope")),
+        }
+    }
+}
+
+/// `ApplyTxError` is a `NonEmpty` list of LEDGER failures.  Errors in a
+/// failure are reported with its index in the list and the era.
+fn ledger_failures<T: FromTaggedTree>(
+    term: &Term,
+    spans: Option<&SpanTree>,
+    era: &'static str,
+) -> Result<NonEmpty<T>, ParseError> {
+    let items = match term {
+        Term::Array(items) => items,
+        _ => return Err(ParseError::Malformed("expected a list of ledger failures")),
+    };
+    let failures = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let spans = spans.and_then(|spans| spans.child(index));
+            let tree = TaggedTree::from_spanned_term(item.clone(), spans);
+            T::from_tagged_tree(&tree).map_err(|err| {
+                err.at(tree.span(), Some(item))
+                    .within(PathSegment::Index(index))
+                    .in_era(era)
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    NonEmpty::new(failures).ok_or(ParseError::Malformed("empty list of ledger failures"))
+}
+
+/// Decode the bytes of a node's transaction rejection.
+///
+/// # Errors
+///
+/// Returns [`ParseError::Cbor`] for invalid CBOR, [`ParseError::Malformed`]
+/// if the envelope does not have one of the shapes above, and the errors of
+/// the era's failure decoders for its payload.
+pub fn decode_apply_tx_err_bytes(bytes: &[u8]) -> Result<HardForkApplyTxErr, ParseError> {
+    let (term, spans) = decode_spanned_term(bytes)?;
+    HardForkApplyTxErr::from_spanned_term(&term, Some(&spans))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_parser::Span;
+    use ciborium::{ser::into_writer, value::Value};
+
+    fn uint(n: u64) -> Value {
+        Value::Integer(n.into())
+    }
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        into_writer(value, &mut buffer).expect("encode test value");
+        buffer
+    }
+
+    /// `ConwayUtxowFailure (UtxoFailure InputSetEmptyUTxO)`.
+    fn conway_failure() -> Value {
+        Value::Array(vec![
+            uint(1),
+            Value::Array(vec![uint(0), Value::Array(vec![uint(4)])]),
+        ])
+    }
+
+    #[test]
+    fn decodes_wrapped_conway_rejection() {
+        let envelope = Value::Array(vec![Value::Array(vec![
+            uint(CONWAY_ERA_INDEX),
+            Value::Array(vec![conway_failure()]),
+        ])]);
+
+        let err = decode_apply_tx_err_bytes(&encode(&envelope)).expect("decode envelope");
+        let expected = conway::LedgerPredicateFailure::ConwayUtxowFailure(
+            conway::UtxowPredicateFailure::UtxoFailure(
+                conway::UtxoPredicateFailure::InputSetEmptyUTxO,
+            ),
+        );
+        assert_eq!(
+            err,
+            HardForkApplyTxErr::FromEra(EraApplyTxError::Conway(NonEmpty::singleton(expected)))
+        );
+    }
+
+    #[test]
+    fn decodes_allegra_validity_interval_rejection() {
+        // UtxowFailure (UtxoFailure (OutsideValidityIntervalUTxO
+        // (ValidityInterval SNothing (SJust 100)) 250)), a tag the Shelley
+        // UTXO rule uses for `ExpiredUTxO` instead.
+        let interval = Value::Array(vec![Value::Array(vec![]), Value::Array(vec![uint(100)])]);
+        let failure = Value::Array(vec![
+            uint(0),
+            Value::Array(vec![
+                uint(4),
+                Value::Array(vec![uint(1), interval, uint(250)]),
+            ]),
+        ]);
+        let envelope = Value::Array(vec![uint(ALLEGRA_ERA_INDEX), Value::Array(vec![failure])]);
+
+        let err = decode_apply_tx_err_bytes(&encode(&envelope)).expect("decode envelope");
+        let expected = shelley::LedgerPredicateFailure::UtxowFailure(
+            shelley::UtxowPredicateFailure::UtxoFailure(
+                allegra::UtxoPredicateFailure::OutsideValidityIntervalUTxO {
+                    interval: ValidityInterval {
+                        invalid_before: None,
+                        invalid_hereafter: Some(SlotNo(100)),
+                    },
+                    current_slot: SlotNo(250),
+                },
+            ),
+        );
+        assert_eq!(
+            err,
+            HardForkApplyTxErr::FromEra(EraApplyTxError::Allegra(NonEmpty::singleton(expected)))
+        );
+    }
+
+    /// `[coin, {policy: {"BERRY": quantity}}]`, a Mary multi-asset value.
+    fn berries(coin: u64, quantity: u64) -> Value {
+        Value::Array(vec![
+            uint(coin),
+            Value::Map(vec![(
+                Value::Bytes(vec![0xd5; 28]),
+                Value::Map(vec![(Value::Bytes(b"BERRY".to_vec()), uint(quantity))]),
+            )]),
+        ])
+    }
+
+    /// A Mary rejection holding a single UTXO failure.
+    fn mary_rejection(era: u64, utxo_failure: Value) -> Vec<u8> {
+        let failure = Value::Array(vec![uint(0), Value::Array(vec![uint(4), utxo_failure])]);
+        encode(&Value::Array(vec![uint(era), Value::Array(vec![failure])]))
+    }
+
+    fn mary_utxo_failure(err: HardForkApplyTxErr) -> mary::UtxoPredicateFailure<Mary> {
+        match err {
+            HardForkApplyTxErr::FromEra(EraApplyTxError::Mary(failures)) => {
+                match failures.as_slice() {
+                    [shelley::LedgerPredicateFailure::UtxowFailure(
+                        shelley::UtxowPredicateFailure::UtxoFailure(failure),
+                    )] => failure.clone(),
+                    other => panic!("unexpected failures {other:?}"),
+                }
+            }
+            other => panic!("unexpected rejection {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decodes_mary_multi_asset_rejection() {
+        // UtxowFailure (UtxoFailure (ValueNotConservedUTxO (Mismatch consumed
+        // produced))), where 100 BERRY went missing.
+        let utxo_failure = Value::Array(vec![
+            uint(5),
+            Value::Array(vec![berries(3_000_000, 1000), berries(3_000_000, 900)]),
+        ]);
+
+        let err = decode_apply_tx_err_bytes(&mary_rejection(MARY_ERA_INDEX, utxo_failure.clone()))
+            .expect("decode envelope");
+        match mary_utxo_failure(err) {
+            mary::UtxoPredicateFailure::ValueNotConservedUTxO { balance_mismatch } => {
+                let [supplied, expected] = [&balance_mismatch.supplied, &balance_mismatch.expected]
+                    .map(|value| {
+                        value.assets().0[&ScriptHash(Hash28([0xd5; 28]))]
+                            [&AssetName(b"BERRY".to_vec())]
+                    });
+                assert_eq!((supplied, expected), (1000, 900));
+                assert_eq!(balance_mismatch.supplied.coin(), Coin(3_000_000));
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+        assert!(
+            decode_apply_tx_err_bytes(&mary_rejection(ALLEGRA_ERA_INDEX, utxo_failure)).is_err()
+        );
+    }
+
+    #[test]
+    fn decodes_mary_output_too_big_rejection() {
+        // UtxowFailure (UtxoFailure (OutputTooBigUTxO [TxOut addr value])).
+        let mut address = vec![0x61];
+        address.extend([0x8f; 28]);
+        let output = Value::Array(vec![Value::Bytes(address), berries(1_444_443, 1)]);
+        let utxo_failure = Value::Array(vec![uint(12), Value::Array(vec![output])]);
+
+        let err = decode_apply_tx_err_bytes(&mary_rejection(MARY_ERA_INDEX, utxo_failure))
+            .expect("decode envelope");
+        match mary_utxo_failure(err) {
+            mary::UtxoPredicateFailure::OutputTooBigUTxO { oversized_outputs } => {
+                match oversized_outputs.as_slice() {
+                    [TxOutStruct::Shelley { amount, .. }] => {
+                        assert_eq!(amount.coin(), Coin(1_444_443));
+                        assert_eq!(amount.assets().0.len(), 1);
+                    }
+                    other => panic!("unexpected outputs {other:?}"),
+                }
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+
+        // TriesToForgeADA is Mary's alone: Allegra cannot mint.
+        let forge = || Value::Array(vec![uint(11)]);
+        let err = decode_apply_tx_err_bytes(&mary_rejection(MARY_ERA_INDEX, forge()))
+            .expect("decode envelope");
+        assert_eq!(
+            mary_utxo_failure(err),
+            mary::UtxoPredicateFailure::TriesToForgeADA
+        );
+        assert!(decode_apply_tx_err_bytes(&mary_rejection(ALLEGRA_ERA_INDEX, forge())).is_err());
+    }
+
+    #[test]
+    fn decodes_wrong_era_rejection() {
+        let envelope = Value::Array(vec![
+            Value::Array(vec![uint(BABBAGE_ERA_INDEX), Value::Text("Babbage".into())]),
+            Value::Array(vec![uint(CONWAY_ERA_INDEX), Value::Text("Conway".into())]),
+        ]);
+
+        match decode_apply_tx_err_bytes(&encode(&envelope)).expect("decode envelope") {
+            HardForkApplyTxErr::WrongEra {
+                transaction_era,
+                ledger_era,
+            } => {
+                assert_eq!(transaction_era.name, "Babbage");
+                assert_eq!(ledger_era.index, CONWAY_ERA_INDEX);
+            }
+            other => panic!("unexpected rejection {other:?}"),
+        }
+    }
+
+    #[test]
+    fn locates_malformed_nested_field() {
+        // `ConwayUtxowFailure (UtxoFailure (BadInputsUTxO 5))`, where the
+        // inputs should be a set.
+        let failure = Value::Array(vec![
+            uint(1),
+            Value::Array(vec![uint(0), Value::Array(vec![uint(1), uint(5)])]),
+        ]);
+        let envelope = Value::Array(vec![
+            uint(CONWAY_ERA_INDEX),
+            Value::Array(vec![conway_failure(), failure]),
+        ]);
+
+        let err = decode_apply_tx_err_bytes(&encode(&envelope)).expect_err("inputs are not a set");
+        let context = err.context().expect("located error");
+        assert_eq!(context.era, Some("Conway"));
+        assert_eq!(
+            context.path_string(),
+            "[1].LedgerPredicateFailure(1)[0].UtxowPredicateFailure(0)[0].UtxoPredicateFailure(1)[0]"
+        );
+        assert_eq!(context.found, Some(Term::Unsigned(5)));
+        assert_eq!(context.span, Some(Span { start: 15, end: 16 }));
+        assert!(matches!(
+            err.cause(),
+            ParseError::Malformed("expected an array")
+        ));
+    }
+
+    #[test]
+    fn rejects_empty_failure_list() {
+        let envelope = Value::Array(vec![uint(CONWAY_ERA_INDEX), Value::Array(vec![])]);
+        assert!(matches!(
+            decode_apply_tx_err_bytes(&encode(&envelope)),
+            Err(ParseError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,481 @@
+//! Text forms of ledger identifiers: bech32 (BIP-173) with the prefixes of
+//! CIP-5 and the credential headers of CIP-129, and base58 for Byron
+//! addresses.
+//!
+//! Cardano uses the original bech32 checksum, not bech32m, and lifts the
+//! 90-character limit of BIP-173 since Shelley addresses exceed it.  Shelley
+//! addresses and reward accounts take their prefix from the network id in
+//! their header, so a parsed identifier is checked against its prefix.
+
+use std::fmt;
+
+use crate::address::{parse_address, AddressError, ParsedAddress};
+use crate::rust_rule_errors::*;
+
+/// Error returned when an identifier cannot be parsed from its text form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdError {
+    /// The text is not valid bech32 or base58.
+    Malformed(&'static str),
+    /// The bech32 checksum does not match.
+    Checksum,
+    /// The prefix does not name the expected kind of identifier.
+    UnexpectedPrefix(String),
+    /// The decoded bytes are not a valid address.
+    Address(AddressError),
+}
+
+impl fmt::Display for IdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdError::Malformed(msg) => write!(f, "malformed identifier: {msg}"),
+            IdError::Checksum => f.write_str("invalid bech32 checksum"),
+            IdError::UnexpectedPrefix(prefix) => write!(f, "unexpected prefix {prefix:?}"),
+            IdError::Address(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for IdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IdError::Address(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<AddressError> for IdError {
+    fn from(err: AddressError) -> Self {
+        IdError::Address(err)
+    }
+}
+
+/// Identifiers with a standard text form.
+pub trait Bech32: Sized {
+    fn to_bech32(&self) -> String;
+
+    /// # Errors
+    ///
+    /// Returns an [`IdError`] if the text is malformed or names another kind
+    /// of identifier.
+    fn from_bech32(text: &str) -> Result<Self, IdError>;
+}
+
+// ---------------------------------------------------------------------------
+// Bech32 and base58 codecs
+// ---------------------------------------------------------------------------
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATORS: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    values.fold(1, |checksum, value| {
+        let top = checksum >> 25;
+        let checksum = ((checksum & 0x01ff_ffff) << 5) ^ u32::from(value);
+        GENERATORS
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| (top >> bit) & 1 == 1)
+            .fold(checksum, |checksum, (_, generator)| checksum ^ generator)
+    })
+}
+
+fn expand_prefix(prefix: &str) -> impl Iterator<Item = u8> + '_ {
+    let bytes = prefix.bytes();
+    bytes
+        .clone()
+        .map(|byte| byte >> 5)
+        .chain([0])
+        .chain(bytes.map(|byte| byte & 31))
+}
+
+/// Regroup bits from `from`-bit to `to`-bit words, padding the last word
+/// when encoding and rejecting non-zero padding when decoding.
+fn regroup(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, IdError> {
+    let mut accumulator: u32 = 0;
+    let mut bits = 0;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for value in data {
+        accumulator = (accumulator << from) | u32::from(*value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((accumulator >> bits) & ((1 << to) - 1)) as u8);
+        }
+    }
+    if pad && bits > 0 {
+        out.push(((accumulator << (to - bits)) & ((1 << to) - 1)) as u8);
+    } else if !pad && (bits >= from || (accumulator << (to - bits)) & ((1 << to) - 1) != 0) {
+        return Err(IdError::Malformed("invalid bech32 padding"));
+    }
+    Ok(out)
+}
+
+/// Encode bytes under a human-readable prefix.
+pub fn encode_bech32(prefix: &str, bytes: &[u8]) -> String {
+    let data = regroup(bytes, 8, 5, true).expect("padding is allowed when encoding");
+    let checksum = polymod(
+        expand_prefix(prefix)
+            .chain(data.iter().copied())
+            .chain([0; 6]),
+    ) ^ 1;
+    let checksum = (0..6)
+        .rev()
+        .map(|group| ((checksum >> (5 * group)) & 31) as u8);
+    let mut text = String::with_capacity(prefix.len() + 1 + data.len() + 6);
+    text.push_str(prefix);
+    text.push('1');
+    text.extend(
+        data.iter()
+            .copied()
+            .chain(checksum)
+            .map(|value| char::from(CHARSET[usize::from(value)])),
+    );
+    text
+}
+
+/// Decode a bech32 string into its prefix and bytes.
+///
+/// # Errors
+///
+/// Returns [`IdError::Malformed`] for mixed case, a missing separator or an
+/// unknown character, and [`IdError::Checksum`] if the checksum is wrong.
+pub fn decode_bech32(text: &str) -> Result<(String, Vec<u8>), IdError> {
+    if text.bytes().any(|byte| byte.is_ascii_lowercase())
+        && text.bytes().any(|byte| byte.is_ascii_uppercase())
+    {
+        return Err(IdError::Malformed("mixed-case bech32"));
+    }
+    let text = text.to_ascii_lowercase();
+    let separator = text
+        .rfind('1')
+        .ok_or(IdError::Malformed("missing bech32 separator"))?;
+    let (prefix, data) = (&text[..separator], &text[separator + 1..]);
+    if prefix.is_empty() || data.len() < 6 || prefix.bytes().any(|byte| !(33..=126).contains(&byte))
+    {
+        return Err(IdError::Malformed("invalid bech32 layout"));
+    }
+    let data = data
+        .bytes()
+        .map(|byte| {
+            CHARSET
+                .iter()
+                .position(|symbol| *symbol == byte)
+                .map(|value| value as u8)
+                .ok_or(IdError::Malformed("invalid bech32 character"))
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    if polymod(expand_prefix(prefix).chain(data.iter().copied())) != 1 {
+        return Err(IdError::Checksum);
+    }
+    let bytes = regroup(&data[..data.len() - 6], 5, 8, false)?;
+    Ok((prefix.to_owned(), bytes))
+}
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encode bytes in the Bitcoin base58 alphabet used by Byron addresses.
+pub fn encode_base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    let mut digits: Vec<u8> = Vec::new();
+    for byte in &bytes[zeros..] {
+        let mut carry = u32::from(*byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|digit| char::from(BASE58_ALPHABET[usize::from(*digit)])),
+        )
+        .collect()
+}
+
+/// # Errors
+///
+/// Returns [`IdError::Malformed`] if the text has a character outside the
+/// base58 alphabet.
+pub fn decode_base58(text: &str) -> Result<Vec<u8>, IdError> {
+    let zeros = text.bytes().take_while(|byte| *byte == b'1').count();
+    let mut bytes: Vec<u8> = Vec::new();
+    for symbol in text.bytes().skip(zeros) {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|candidate| *candidate == symbol)
+            .ok_or(IdError::Malformed("invalid base58 character"))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.extend(std::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    Ok(bytes)
+}
+
+// ---------------------------------------------------------------------------
+// Identifiers
+// ---------------------------------------------------------------------------
+
+fn network_prefix(mainnet: &'static str, testnet: &'static str, network: NetworkId) -> String {
+    match network.0 {
+        1 => mainnet.to_owned(),
+        _ => testnet.to_owned(),
+    }
+}
+
+/// Shelley addresses are `addr1…` on mainnet and `addr_test1…` elsewhere;
+/// Byron bootstrap addresses are written in base58.  Bytes that are not a
+/// valid address are written under the mainnet prefix.
+impl Bech32 for Address {
+    fn to_bech32(&self) -> String {
+        match parse_address(&self.0) {
+            Ok(ParsedAddress::Bootstrap(_)) => encode_base58(&self.0),
+            Ok(parsed) => encode_bech32(
+                &network_prefix("addr", "addr_test", parsed.network()),
+                &self.0,
+            ),
+            Err(_) => encode_bech32("addr", &self.0),
+        }
+    }
+
+    fn from_bech32(text: &str) -> Result<Self, IdError> {
+        let (prefix, bytes) = match decode_bech32(text) {
+            Ok(decoded) => decoded,
+            // Base58 never yields a bech32 checksum to check: this is a
+            // mistyped bech32 address.
+            Err(IdError::Checksum) => return Err(IdError::Checksum),
+            Err(_) => {
+                let bytes = decode_base58(text)?;
+                return match parse_address(&bytes)? {
+                    ParsedAddress::Bootstrap(_) => Ok(Address(bytes)),
+                    _ => Err(IdError::Malformed("base58 is only used by Byron addresses")),
+                };
+            }
+        };
+        let parsed = parse_address(&bytes)?;
+        if matches!(
+            parsed,
+            ParsedAddress::Reward { .. } | ParsedAddress::Bootstrap(_)
+        ) || prefix != network_prefix("addr", "addr_test", parsed.network())
+        {
+            return Err(IdError::UnexpectedPrefix(prefix));
+        }
+        Ok(Address(bytes))
+    }
+}
+
+/// Reward accounts are `stake1…` on mainnet and `stake_test1…` elsewhere.
+impl Bech32 for RewardAccount {
+    fn to_bech32(&self) -> String {
+        let network = parse_address(&self.0).map_or(NetworkId(1), |parsed| parsed.network());
+        encode_bech32(&network_prefix("stake", "stake_test", network), &self.0)
+    }
+
+    fn from_bech32(text: &str) -> Result<Self, IdError> {
+        let (prefix, bytes) = decode_bech32(text)?;
+        let account = RewardAccount(bytes);
+        let network = account.parse()?.network();
+        if prefix != network_prefix("stake", "stake_test", network) {
+            return Err(IdError::UnexpectedPrefix(prefix));
+        }
+        Ok(account)
+    }
+}
+
+impl Bech32 for PoolKeyHash {
+    fn to_bech32(&self) -> String {
+        encode_bech32("pool", &self.0 .0)
+    }
+
+    fn from_bech32(text: &str) -> Result<Self, IdError> {
+        match decode_bech32(text)? {
+            (prefix, bytes) if prefix == "pool" => bytes
+                .try_into()
+                .map(|hash| PoolKeyHash(Hash28(hash)))
+                .map_err(|_| IdError::Malformed("unexpected pool id length")),
+            (prefix, _) => Err(IdError::UnexpectedPrefix(prefix)),
+        }
+    }
+}
+
+/// CIP-129: the transaction id followed by the action index as one byte, or
+/// two big-endian bytes for the indices that do not fit one.
+impl Bech32 for GovActionId {
+    fn to_bech32(&self) -> String {
+        let mut bytes = self.tx_id.0 .0.to_vec();
+        match u8::try_from(self.action_index.0) {
+            Ok(index) => bytes.push(index),
+            Err(_) => bytes.extend(self.action_index.0.to_be_bytes()),
+        }
+        encode_bech32("gov_action", &bytes)
+    }
+
+    fn from_bech32(text: &str) -> Result<Self, IdError> {
+        let (prefix, bytes) = decode_bech32(text)?;
+        if prefix != "gov_action" {
+            return Err(IdError::UnexpectedPrefix(prefix));
+        }
+        let action_index = match bytes.get(32..) {
+            Some([index]) => u16::from(*index),
+            Some([high, low]) => u16::from_be_bytes([*high, *low]),
+            _ => return Err(IdError::Malformed("unexpected governance action id length")),
+        };
+        let tx_id = bytes[..32].try_into().expect("length checked above");
+        Ok(GovActionId {
+            tx_id: TxId(Hash32(tx_id)),
+            action_index: GovActionIx(action_index),
+        })
+    }
+}
+
+/// CIP-129 header of DRep credentials: key type 2 in the high nibble and the
+/// credential type (2 for key hashes, 3 for scripts) in the low nibble.
+const DREP_KEY_HEADER: u8 = 0x22;
+const DREP_SCRIPT_HEADER: u8 = 0x23;
+
+/// `drep1…` form of a DRep credential, with its CIP-129 header.
+pub fn drep_to_bech32(credential: &DRepCredential) -> String {
+    let (header, hash) = match credential {
+        Credential::KeyHash(hash) => (DREP_KEY_HEADER, &hash.0),
+        Credential::ScriptHash(hash) => (DREP_SCRIPT_HEADER, &hash.0),
+    };
+    let mut bytes = vec![header];
+    bytes.extend_from_slice(&hash.0);
+    encode_bech32("drep", &bytes)
+}
+
+/// Parse a DRep credential from its CIP-129 form, or from the CIP-105 forms
+/// without a header (`drep1…` for key hashes, `drep_script1…` for scripts).
+///
+/// # Errors
+///
+/// Returns an [`IdError`] if the text is malformed or not a DRep id.
+pub fn drep_from_bech32(text: &str) -> Result<DRepCredential, IdError> {
+    let (prefix, bytes) = decode_bech32(text)?;
+    // The length tells the forms apart: a bare key hash may well start with
+    // a header byte.
+    let (script, hash) = match (prefix.as_str(), bytes.as_slice()) {
+        ("drep", hash) if hash.len() == 28 => (false, hash),
+        ("drep", [DREP_KEY_HEADER, hash @ ..]) if hash.len() == 28 => (false, hash),
+        ("drep", [DREP_SCRIPT_HEADER, hash @ ..]) if hash.len() == 28 => (true, hash),
+        ("drep", [_, hash @ ..]) if hash.len() == 28 => {
+            return Err(IdError::Malformed("unexpected DRep id header"))
+        }
+        ("drep", _) => return Err(IdError::Malformed("unexpected DRep id length")),
+        ("drep_script", hash) => (true, hash),
+        _ => return Err(IdError::UnexpectedPrefix(prefix)),
+    };
+    let hash = Hash28(
+        hash.try_into()
+            .map_err(|_| IdError::Malformed("unexpected DRep id length"))?,
+    );
+    Ok(if script {
+        Credential::ScriptHash(ScriptHash(hash))
+    } else {
+        Credential::KeyHash(AddrKeyHash(hash))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_reference_vectors() {
+        // BIP-173 valid strings.
+        assert_eq!(decode_bech32("A12UEL5L").unwrap(), ("a".to_owned(), vec![]));
+        let (prefix, bytes) =
+            decode_bech32("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw").unwrap();
+        assert_eq!(
+            encode_bech32(&prefix, &bytes),
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw"
+        );
+        assert_eq!(decode_bech32("a12uel5m"), Err(IdError::Checksum));
+        assert_eq!(
+            decode_bech32("A12uel5l"),
+            Err(IdError::Malformed("mixed-case bech32"))
+        );
+
+        assert_eq!(encode_base58(b"Hello World"), "JxF12TrwUP45BMd");
+        assert_eq!(encode_base58(&[0, 0, 1]), "112");
+        assert_eq!(decode_base58("112").unwrap(), vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn round_trips_ledger_identifiers() {
+        // Mainnet enterprise address from the CIP-19 test vectors.
+        let mut bytes = vec![0x61];
+        bytes.extend([
+            0x94, 0x93, 0x31, 0x5c, 0xd9, 0x2e, 0xb5, 0xd8, 0xc4, 0x30, 0x4e, 0x67, 0xb7, 0xe1,
+            0x6a, 0xe3, 0x6d, 0x61, 0xd3, 0x45, 0x02, 0x69, 0x46, 0x57, 0x81, 0x1a, 0x2c, 0x8e,
+        ]);
+        let address = Address(bytes);
+        let text = "addr1vx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzers66hrl8";
+        assert_eq!(address.to_bech32(), text);
+        assert_eq!(Address::from_bech32(text).unwrap(), address);
+        let mistyped = "addr1vx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzers66hrl9";
+        assert_eq!(Address::from_bech32(mistyped), Err(IdError::Checksum));
+
+        let mut bytes = vec![0xe0];
+        bytes.extend([8; 28]);
+        let account = RewardAccount(bytes);
+        let text = account.to_bech32();
+        assert!(text.starts_with("stake_test1"));
+        assert_eq!(RewardAccount::from_bech32(&text).unwrap(), account);
+        assert_eq!(
+            Address::from_bech32(&text),
+            Err(IdError::UnexpectedPrefix("stake_test".to_owned()))
+        );
+
+        let pool = PoolKeyHash(Hash28([9; 28]));
+        assert_eq!(PoolKeyHash::from_bech32(&pool.to_bech32()).unwrap(), pool);
+
+        let action = GovActionId {
+            tx_id: TxId(Hash32([0; 32])),
+            action_index: GovActionIx(17),
+        };
+        assert_eq!(
+            GovActionId::from_bech32(&action.to_bech32()).unwrap(),
+            action
+        );
+
+        let drep = Credential::ScriptHash(ScriptHash(Hash28([5; 28])));
+        let text = drep_to_bech32(&drep);
+        assert!(text.starts_with("drep1"));
+        assert_eq!(drep_from_bech32(&text).unwrap(), drep);
+        let legacy = encode_bech32("drep", &[6; 28]);
+        assert_eq!(
+            drep_from_bech32(&legacy).unwrap(),
+            Credential::KeyHash(AddrKeyHash(Hash28([6; 28])))
+        );
+        // A legacy key hash that happens to start with a CIP-129 header.
+        let mut hash = [0x22; 28];
+        hash[1] = 0x07;
+        assert_eq!(
+            drep_from_bech32(&encode_bech32("drep", &hash)).unwrap(),
+            Credential::KeyHash(AddrKeyHash(Hash28(hash)))
+        );
+    }
+}
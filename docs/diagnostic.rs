@@ -0,0 +1,2362 @@
+//! Human-readable rendering of the predicate failures defined in
+//! `rust_rule_errors.rs`.
+//!
+//! Every failure enum implements [`Diagnose`], which walks through the
+//! wrapper constructors (`ConwayUtxowFailure(UtxoFailure(..))`) and records
+//! them as a breadcrumb path before describing the innermost failure in plain
+//! words.  The enums also implement `Display` through the same rendering, so
+//! `failure.to_string()` reads like
+//!
+//! ```text
+//! ConwayUtxowFailure > UtxoFailure > FeeTooSmallUTxO: fee 170000 lovelace is below the required 172345 (short by 2345)
+//! ```
+//!
+//! A failure that could not be decoded, such as one with a constructor this
+//! decoder does not know, still gets a diagnostic from its `ParseError`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write as _};
+
+use crate::address::{AddressError, ParsedAddress};
+use crate::bech32::{drep_to_bech32, Bech32};
+use crate::era::*;
+use crate::error_parser::{ParseError, PathSegment};
+use crate::rust_rule_errors::*;
+use crate::value::{diff, AssetClass};
+
+/// A failure described for a human reader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Constructor names from the outermost wrapper down to the failure.
+    pub path: Vec<&'static str>,
+    /// Description of the innermost failure.
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(constructor: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic {
+            path: vec![constructor],
+            message: message.into(),
+        }
+    }
+
+    /// Record the wrapper constructor the failure was found in.
+    fn within(mut self, wrapper: &'static str) -> Self {
+        self.path.insert(0, wrapper);
+        self
+    }
+
+    /// The path rendered as `Outer > Inner > Failure`.
+    pub fn breadcrumb(&self) -> String {
+        self.path.join(" > ")
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.breadcrumb(), self.message)
+    }
+}
+
+/// Description of a predicate failure for a human reader.
+pub trait Diagnose {
+    fn diagnose(&self) -> Diagnostic;
+}
+
+/// Fallback for a failure that could not be decoded, most often because the
+/// node is newer than this decoder and added a constructor.  The breadcrumb
+/// names the failure families down to where decoding stopped.
+impl Diagnose for ParseError {
+    fn diagnose(&self) -> Diagnostic {
+        let short = |family: &'static str| family.rsplit("::").next().unwrap_or(family);
+        let mut path: Vec<&'static str> = self
+            .context()
+            .into_iter()
+            .flat_map(|context| &context.path)
+            .filter_map(|segment| match segment {
+                PathSegment::Field { family, .. } => Some(short(family)),
+                PathSegment::Tag { .. } | PathSegment::Index(_) => None,
+            })
+            .collect();
+        let message = match self.cause() {
+            ParseError::UnknownConstructor { family, tag } => {
+                path.push(short(family));
+                format!("unknown constructor tag {tag}, perhaps added by a newer node")
+            }
+            cause => cause.to_string(),
+        };
+        Diagnostic { path, message }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Payload rendering
+// ---------------------------------------------------------------------------
+
+/// Rendering of a payload value inside a diagnostic message.
+trait Render {
+    fn render(&self) -> String;
+
+    /// Numeric amount of the value, used to report how far a bound is missed.
+    fn amount(&self) -> Option<i128> {
+        None
+    }
+}
+
+macro_rules! render_number {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Render for $ty {
+                fn render(&self) -> String {
+                    self.to_string()
+                }
+
+                fn amount(&self) -> Option<i128> {
+                    Some(i128::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+render_number!(u16, u32, u64, i32, i64);
+
+impl Render for usize {
+    fn render(&self) -> String {
+        self.to_string()
+    }
+
+    fn amount(&self) -> Option<i128> {
+        i128::try_from(*self).ok()
+    }
+}
+
+macro_rules! render_number_newtype {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl Render for $ty {
+                fn render(&self) -> String {
+                    self.0.render()
+                }
+
+                fn amount(&self) -> Option<i128> {
+                    self.0.amount()
+                }
+            }
+        )*
+    };
+}
+
+render_number_newtype!(Coin, DeltaCoin, SlotNo, EpochNo);
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+impl Render for [u8; 32] {
+    fn render(&self) -> String {
+        hex(self)
+    }
+}
+
+impl Render for Vec<u8> {
+    fn render(&self) -> String {
+        hex(self)
+    }
+}
+
+impl Render for Hash28 {
+    fn render(&self) -> String {
+        hex(&self.0)
+    }
+}
+
+impl Render for Hash32 {
+    fn render(&self) -> String {
+        hex(&self.0)
+    }
+}
+
+macro_rules! render_hash_newtype {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl Render for $ty {
+                fn render(&self) -> String {
+                    self.0.render()
+                }
+            }
+        )*
+    };
+}
+
+render_hash_newtype!(
+    ScriptHash,
+    DataHash,
+    ScriptIntegrityHash,
+    TxAuxDataHash,
+    TxId,
+    AddrKeyHash,
+    KeyHash,
+    VRFKeyHash,
+);
+
+macro_rules! render_bech32 {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl Render for $ty {
+                fn render(&self) -> String {
+                    self.to_bech32()
+                }
+            }
+        )*
+    };
+}
+
+render_bech32!(Address, RewardAccount, PoolKeyHash, GovActionId);
+
+/// Asset names are shown as text when they are printable UTF-8.
+impl Render for AssetName {
+    fn render(&self) -> String {
+        match std::str::from_utf8(&self.0) {
+            Ok(text) if !text.is_empty() && !text.chars().any(char::is_control) => text.to_owned(),
+            _ => format!("#{}", hex(&self.0)),
+        }
+    }
+}
+
+impl Render for VerificationKey {
+    fn render(&self) -> String {
+        hex(&self.0)
+    }
+}
+
+impl Render for String {
+    fn render(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl Render for NetworkId {
+    fn render(&self) -> String {
+        match self.0 {
+            0 => "testnet".into(),
+            1 => "mainnet".into(),
+            other => format!("network {other}"),
+        }
+    }
+}
+
+impl Render for TxIn {
+    fn render(&self) -> String {
+        format!("{}#{}", self.transaction_id.render(), self.index.0)
+    }
+}
+
+impl Render for ProtVer {
+    fn render(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
+}
+
+impl Render for ExUnits {
+    fn render(&self) -> String {
+        format!("{} memory / {} steps", self.mem, self.steps)
+    }
+}
+
+impl Render for ValidityInterval {
+    fn render(&self) -> String {
+        let bound = |slot: &Option<SlotNo>, open: &str| match slot {
+            Some(slot) => format!("slot {}", slot.0),
+            None => open.to_string(),
+        };
+        format!(
+            "from {} until {}",
+            bound(&self.invalid_before, "the beginning"),
+            bound(&self.invalid_hereafter, "forever")
+        )
+    }
+}
+
+impl Render for MIRPot {
+    fn render(&self) -> String {
+        match self {
+            MIRPot::Reserves => "reserves".into(),
+            MIRPot::Treasury => "treasury".into(),
+        }
+    }
+}
+
+impl Render for Credential {
+    fn render(&self) -> String {
+        match self {
+            Credential::KeyHash(hash) => format!("key hash {}", hash.render()),
+            Credential::ScriptHash(hash) => format!("script hash {}", hash.render()),
+        }
+    }
+}
+
+impl Render for VoterEnum {
+    fn render(&self) -> String {
+        match self {
+            VoterEnum::CommitteeKey(hash) => format!("committee key {}", hash.render()),
+            VoterEnum::CommitteeScript(hash) => format!("committee script {}", hash.render()),
+            VoterEnum::DRepKey(hash) => {
+                format!(
+                    "DRep {}",
+                    drep_to_bech32(&Credential::KeyHash(hash.clone()))
+                )
+            }
+            VoterEnum::DRepScript(hash) => {
+                format!(
+                    "DRep {}",
+                    drep_to_bech32(&Credential::ScriptHash(hash.clone()))
+                )
+            }
+            VoterEnum::StakePool(hash) => format!("stake pool {}", hash.render()),
+        }
+    }
+}
+
+impl Render for ValueStruct {
+    fn render(&self) -> String {
+        match self {
+            ValueStruct::Coin(coin) => format!("{} lovelace", coin.0),
+            ValueStruct::MultiAsset { coin, assets } => {
+                let count: usize = assets.0.values().map(BTreeMap::len).sum();
+                format!("{} lovelace and {count} native assets", coin.0)
+            }
+        }
+    }
+}
+
+impl<E: Era> Render for TxOutStruct<E> {
+    fn render(&self) -> String {
+        let (address, amount, datum, script_ref) = match self {
+            TxOutStruct::Shelley {
+                address,
+                amount,
+                datum_hash,
+            } => (
+                address,
+                amount,
+                datum_hash
+                    .as_ref()
+                    .map(|hash| format!("datum hash {}", hash.render())),
+                None,
+            ),
+            TxOutStruct::Babbage {
+                address,
+                amount,
+                datum_option,
+                script_ref,
+            } => (
+                address,
+                amount,
+                datum_option.as_ref().map(DatumOption::render),
+                script_ref.as_ref(),
+            ),
+        };
+        let extras: Vec<String> = datum
+            .into_iter()
+            .chain(script_ref.map(|_| "a reference script".to_owned()))
+            .collect();
+        let amount: ValueStruct = amount.clone().into();
+        let mut rendered = format!("{} to {}", amount.render(), address.render());
+        if !extras.is_empty() {
+            let _ = write!(rendered, " with {}", extras.join(" and "));
+        }
+        rendered
+    }
+}
+
+impl Render for DatumOption {
+    fn render(&self) -> String {
+        match self {
+            DatumOption::Hash(hash) => format!("datum hash {}", hash.render()),
+            DatumOption::Inline(data) => format!("inline datum {}", data.render()),
+        }
+    }
+}
+
+/// Plutus data in the notation of the Plutus pretty-printer, for example
+/// `Constr 0 [I 5, B #00ff]`.
+impl Render for PlutusData {
+    fn render(&self) -> String {
+        let items = |items: &[PlutusData]| -> String {
+            let items: Vec<String> = items.iter().map(Render::render).collect();
+            items.join(", ")
+        };
+        match self {
+            PlutusData::Constr { tag, fields } => format!("Constr {tag} [{}]", items(fields)),
+            PlutusData::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| format!("({}, {})", key.render(), value.render()))
+                    .collect();
+                format!("Map [{}]", entries.join(", "))
+            }
+            PlutusData::List(list) => format!("List [{}]", items(list)),
+            PlutusData::Integer(BigInt::Int(value)) => format!("I {value}"),
+            PlutusData::Integer(BigInt::BigUInt(bytes)) => format!("I 0x{}", hex(&bytes.0)),
+            PlutusData::Integer(BigInt::BigNInt(bytes)) => format!("I -1-0x{}", hex(&bytes.0)),
+            PlutusData::Bytes(bytes) => format!("B #{}", hex(bytes)),
+        }
+    }
+}
+
+/// Purposes differ by era, so the failures generic over it print them through
+/// `Display` rather than [`Render`].
+impl fmt::Display for PlutusPurposeStruct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self.tag {
+            RedeemerTag::Spend => "spend",
+            RedeemerTag::Mint => "mint",
+            RedeemerTag::Cert => "cert",
+            RedeemerTag::Reward => "reward",
+            RedeemerTag::Voting => "vote",
+            RedeemerTag::Proposing => "propose",
+        };
+        write!(f, "{tag} #{}", self.index)
+    }
+}
+
+impl fmt::Display for AlonzoPlutusPurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self.tag {
+            AlonzoRedeemerTag::Spend => "spend",
+            AlonzoRedeemerTag::Mint => "mint",
+            AlonzoRedeemerTag::Cert => "cert",
+            AlonzoRedeemerTag::Reward => "reward",
+        };
+        write!(f, "{tag} #{}", self.index)
+    }
+}
+
+impl fmt::Display for dijkstra::PlutusPurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use dijkstra::RedeemerTag;
+        let tag = match self.tag {
+            RedeemerTag::Spend => "spend",
+            RedeemerTag::Mint => "mint",
+            RedeemerTag::Cert => "cert",
+            RedeemerTag::Reward => "reward",
+            RedeemerTag::Voting => "vote",
+            RedeemerTag::Proposing => "propose",
+            RedeemerTag::Guarding => "guard",
+        };
+        write!(f, "{tag} #{}", self.index)
+    }
+}
+
+impl fmt::Display for PlutusPurposeItemStruct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlutusPurposeItemStruct::Spending(input) => write!(f, "spending {}", input.render()),
+            PlutusPurposeItemStruct::Minting(policy) => write!(f, "minting {}", policy.render()),
+            PlutusPurposeItemStruct::Certifying(cert) => write!(f, "certifying {cert:?}"),
+            PlutusPurposeItemStruct::Rewarding(account) => {
+                write!(f, "withdrawing from {}", account.render())
+            }
+            PlutusPurposeItemStruct::Voting(voter) => write!(f, "voting as {}", voter.render()),
+            PlutusPurposeItemStruct::Proposing(proposal) => {
+                write!(f, "proposing {:?}", proposal.action)
+            }
+        }
+    }
+}
+
+impl fmt::Display for AlonzoPlutusPurposeItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use AlonzoPlutusPurposeItem::*;
+        match self {
+            Spending(input) => write!(f, "spending {}", input.render()),
+            Minting(policy) => write!(f, "minting {}", policy.render()),
+            Certifying(cert) => write!(f, "certifying {cert:?}"),
+            Rewarding(account) => write!(f, "withdrawing from {}", account.render()),
+        }
+    }
+}
+
+impl fmt::Display for dijkstra::PlutusPurposeItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use dijkstra::PlutusPurposeItem::*;
+        match self {
+            Spending(input) => write!(f, "spending {}", input.render()),
+            Minting(policy) => write!(f, "minting {}", policy.render()),
+            Certifying(cert) => write!(f, "certifying {:?}", cert.as_pool_cert()),
+            Rewarding(account) => write!(f, "withdrawing from {}", account.render()),
+            Voting(voter) => write!(f, "voting as {}", voter.render()),
+            Proposing(proposal) => write!(f, "proposing {:?}", proposal.action),
+            Guarding(script) => write!(f, "guarding with script {}", script.render()),
+        }
+    }
+}
+
+impl Render for TxOutSource {
+    fn render(&self) -> String {
+        match self {
+            TxOutSource::FromInput(input) => format!("input {}", input.render()),
+            TxOutSource::FromOutput(index) => format!("output #{}", index.0),
+        }
+    }
+}
+
+impl Render for Language {
+    fn render(&self) -> String {
+        match self {
+            Language::PlutusV1 => "PlutusV1".into(),
+            Language::PlutusV2 => "PlutusV2".into(),
+            Language::PlutusV3 => "PlutusV3".into(),
+            Language::PlutusV4 => "PlutusV4".into(),
+        }
+    }
+}
+
+impl Render for Withdrawals {
+    fn render(&self) -> String {
+        list(
+            self.0
+                .iter()
+                .map(|(account, coin)| format!("{} lovelace from {}", coin.0, account.render())),
+        )
+    }
+}
+
+impl<T: Render> Render for Option<T> {
+    fn render(&self) -> String {
+        match self {
+            Some(value) => value.render(),
+            None => "none".into(),
+        }
+    }
+}
+
+impl<A: Render, B: Render> Render for (A, B) {
+    fn render(&self) -> String {
+        format!("{} ({})", self.0.render(), self.1.render())
+    }
+}
+
+impl<T: Render> Render for Vec<T> {
+    fn render(&self) -> String {
+        list(self.iter().map(Render::render))
+    }
+}
+
+impl<T: Render> Render for NonEmpty<T> {
+    fn render(&self) -> String {
+        list(self.iter().map(Render::render))
+    }
+}
+
+impl<T: Render> Render for BTreeSet<T> {
+    fn render(&self) -> String {
+        list(self.iter().map(Render::render))
+    }
+}
+
+/// Values are indexed by the era; they are rendered through the
+/// representation shared by all eras.
+fn render_outputs<E: Era>(outputs: &[TxOutStruct<E>]) -> String {
+    list(outputs.iter().map(Render::render))
+}
+
+fn render_value<V: Clone + Into<ValueStruct>>(value: &V) -> String {
+    let value: ValueStruct = value.clone().into();
+    value.render()
+}
+
+fn list(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.collect();
+    if items.is_empty() {
+        "nothing".into()
+    } else {
+        items.join(", ")
+    }
+}
+
+fn lovelace(coin: &Coin) -> String {
+    format!("{} lovelace", coin.0)
+}
+
+/// Describe a `Mismatch` according to the relation the ledger checked.
+///
+/// `unit` follows the supplied value only, so the message reads
+/// "fee 170000 lovelace is below the required 172345".
+fn compare<R: Relation, T: Render>(subject: &str, unit: &str, mismatch: &Mismatch<R, T>) -> String {
+    let supplied = match unit {
+        "" => mismatch.supplied.render(),
+        unit => format!("{} {unit}", mismatch.supplied.render()),
+    };
+    let expected = mismatch.expected.render();
+    let gap = mismatch
+        .supplied
+        .amount()
+        .zip(mismatch.expected.amount())
+        .map(|(supplied, expected)| supplied - expected);
+    match mismatch.kind() {
+        RelationKind::Eq => format!("{subject} {supplied} does not match the expected {expected}"),
+        RelationKind::Lteq => match gap {
+            Some(over) if over > 0 => {
+                format!("{subject} {supplied} exceeds the maximum {expected} (over by {over})")
+            }
+            _ => format!("{subject} {supplied} exceeds the maximum {expected}"),
+        },
+        RelationKind::Lt => format!("{subject} {supplied} must be below {expected}"),
+        RelationKind::Gteq => match gap {
+            Some(short) if short < 0 => format!(
+                "{subject} {supplied} is below the required {expected} (short by {})",
+                -short
+            ),
+            _ => format!("{subject} {supplied} is below the required {expected}"),
+        },
+        RelationKind::Gt => format!("{subject} {supplied} must be above {expected}"),
+        RelationKind::Subset => format!("{subject} {supplied} are not all among {expected}"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shelley era predicate failures
+// ---------------------------------------------------------------------------
+
+impl Diagnose for shelley::PpupPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::PpupPredicateFailure::*;
+        match self {
+            NonGenesisUpdatePPUP { offending_keys } => Diagnostic::new(
+                "NonGenesisUpdatePPUP",
+                compare("update proposal keys", "", offending_keys),
+            ),
+            PPUpdateWrongEpoch {
+                current_epoch,
+                declared_epoch,
+                voting_period,
+            } => {
+                let period = match voting_period {
+                    shelley::VotingPeriod::VoteForThisEpoch => "this epoch",
+                    shelley::VotingPeriod::VoteForNextEpoch => "the next epoch",
+                };
+                Diagnostic::new(
+                    "PPUpdateWrongEpoch",
+                    format!(
+                        "update proposal targets epoch {} but votes in epoch {} are for {period}",
+                        declared_epoch.0, current_epoch.0
+                    ),
+                )
+            }
+            PVCannotFollowPPUP { proposed_version } => Diagnostic::new(
+                "PVCannotFollowPPUP",
+                format!(
+                    "protocol version {} cannot follow the current one",
+                    proposed_version.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl<E: Era> Diagnose for shelley::UtxoPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::UtxoPredicateFailure::*;
+        match self {
+            BadInputsUTxO { invalid_inputs } => Diagnostic::new(
+                "BadInputsUTxO",
+                format!("inputs not in the UTxO: {}", invalid_inputs.render()),
+            ),
+            ExpiredUTxO { ttl_mismatch } => Diagnostic::new(
+                "ExpiredUTxO",
+                format!(
+                    "transaction expired at slot {} but the current slot is {}",
+                    ttl_mismatch.supplied.0, ttl_mismatch.expected.0
+                ),
+            ),
+            MaxTxSizeUTxO { size_mismatch } => Diagnostic::new(
+                "MaxTxSizeUTxO",
+                compare("transaction size", "bytes", size_mismatch),
+            ),
+            InputSetEmptyUTxO => Diagnostic::new("InputSetEmptyUTxO", "transaction has no inputs"),
+            FeeTooSmallUTxO { fee_mismatch } => {
+                Diagnostic::new("FeeTooSmallUTxO", compare("fee", "lovelace", fee_mismatch))
+            }
+            ValueNotConservedUTxO { balance_mismatch } => Diagnostic::new(
+                "ValueNotConservedUTxO",
+                value_not_conserved(balance_mismatch),
+            ),
+            OutputTooSmallUTxO { tiny_outputs } => Diagnostic::new(
+                "OutputTooSmallUTxO",
+                format!(
+                    "outputs below the minimum UTxO value: {}",
+                    render_outputs(tiny_outputs)
+                ),
+            ),
+            UpdateFailure(failure) => failure.diagnose().within("UpdateFailure"),
+            WrongNetwork {
+                expected,
+                offending,
+            } => Diagnostic::new("WrongNetwork", wrong_network(expected, offending)),
+            WrongNetworkWithdrawal {
+                expected,
+                offending,
+            } => Diagnostic::new("WrongNetworkWithdrawal", wrong_network(expected, offending)),
+            OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs,
+            } => Diagnostic::new(
+                "OutputBootAddrAttrsTooBig",
+                format!(
+                    "bootstrap address attributes too big in outputs: {}",
+                    render_outputs(oversized_bootstrap_outputs)
+                ),
+            ),
+        }
+    }
+}
+
+/// The consumed side is the supplied value and the produced side the expected
+/// one, so a deficit means the transaction lacks inputs for what it produces.
+fn value_not_conserved<V: Clone + Into<ValueStruct>>(mismatch: &Mismatch<RelEQ, V>) -> String {
+    let supplied: ValueStruct = mismatch.supplied.clone().into();
+    let expected: ValueStruct = mismatch.expected.clone().into();
+    let imbalances = diff(&supplied, &expected).into_iter().map(|difference| {
+        let surplus = difference.surplus();
+        let quantity = quantity_of(&difference.asset, surplus.unsigned_abs());
+        if surplus < 0 {
+            format!("missing {quantity}")
+        } else {
+            format!("{quantity} consumed but not produced")
+        }
+    });
+    format!(
+        "consumed {} but produced {}: {}",
+        supplied.render(),
+        expected.render(),
+        list(imbalances)
+    )
+}
+
+fn quantity_of(asset: &AssetClass, quantity: u128) -> String {
+    match asset {
+        AssetClass::Ada => format!("{quantity} lovelace"),
+        AssetClass::Token { policy, name } => {
+            let tokens = if quantity == 1 { "token" } else { "tokens" };
+            format!(
+                "{quantity} {tokens} of policy {} / asset {}",
+                policy.render(),
+                name.render()
+            )
+        }
+    }
+}
+
+/// Address payloads that can be classified by their header.
+trait Classify: Render {
+    fn classify(&self) -> Result<ParsedAddress, AddressError>;
+}
+
+impl Classify for Address {
+    fn classify(&self) -> Result<ParsedAddress, AddressError> {
+        self.parse()
+    }
+}
+
+impl Classify for RewardAccount {
+    fn classify(&self) -> Result<ParsedAddress, AddressError> {
+        self.parse()
+    }
+}
+
+fn wrong_network<T: Classify>(expected: &NetworkId, offending: &BTreeSet<T>) -> String {
+    let offending = offending.iter().map(|address| match address.classify() {
+        Ok(parsed) => format!(
+            "{} ({} {} address)",
+            address.render(),
+            parsed.network().render(),
+            parsed.kind()
+        ),
+        Err(err) => format!("{} ({err})", address.render()),
+    });
+    format!(
+        "expected {} addresses but got {}",
+        expected.render(),
+        list(offending)
+    )
+}
+
+impl<Utxo: Diagnose> Diagnose for shelley::UtxowPredicateFailure<Utxo> {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::UtxowPredicateFailure::*;
+        match self {
+            InvalidWitnessesUTXOW { invalid_witnesses } => Diagnostic::new(
+                "InvalidWitnessesUTXOW",
+                format!("invalid signatures from {}", invalid_witnesses.render()),
+            ),
+            MissingVKeyWitnessesUTXOW { missing_signers } => Diagnostic::new(
+                "MissingVKeyWitnessesUTXOW",
+                format!("missing signatures from {}", missing_signers.render()),
+            ),
+            MissingScriptWitnessesUTXOW { missing_scripts } => Diagnostic::new(
+                "MissingScriptWitnessesUTXOW",
+                format!("missing scripts {}", missing_scripts.render()),
+            ),
+            ScriptWitnessNotValidatingUTXOW { failed_scripts } => Diagnostic::new(
+                "ScriptWitnessNotValidatingUTXOW",
+                format!("scripts failed to validate: {}", failed_scripts.render()),
+            ),
+            UtxoFailure(failure) => failure.diagnose().within("UtxoFailure"),
+            MIRInsufficientGenesisSigsUTXOW { missing_signatures } => Diagnostic::new(
+                "MIRInsufficientGenesisSigsUTXOW",
+                format!(
+                    "not enough genesis signatures for MIR, have {}",
+                    missing_signatures.render()
+                ),
+            ),
+            MissingTxBodyMetadataHash { expected } => Diagnostic::new(
+                "MissingTxBodyMetadataHash",
+                format!(
+                    "transaction has metadata but its body lacks the hash {}",
+                    expected.render()
+                ),
+            ),
+            MissingTxMetadata { referenced } => Diagnostic::new(
+                "MissingTxMetadata",
+                format!(
+                    "transaction body references metadata {} that is not attached",
+                    referenced.render()
+                ),
+            ),
+            ConflictingMetadataHash { mismatch } => Diagnostic::new(
+                "ConflictingMetadataHash",
+                compare("metadata hash", "", mismatch),
+            ),
+            InvalidMetadata => Diagnostic::new("InvalidMetadata", "metadata is malformed"),
+            ExtraneousScriptWitnessesUTXOW { extra_scripts } => Diagnostic::new(
+                "ExtraneousScriptWitnessesUTXOW",
+                format!(
+                    "scripts witnessed but not needed: {}",
+                    extra_scripts.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl Diagnose for shelley::DelegPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::DelegPredicateFailure::*;
+        match self {
+            StakeKeyAlreadyRegistered { credential } => Diagnostic::new(
+                "StakeKeyAlreadyRegistered",
+                format!(
+                    "stake credential {} is already registered",
+                    credential.render()
+                ),
+            ),
+            StakeKeyNotRegistered { credential } => Diagnostic::new(
+                "StakeKeyNotRegistered",
+                format!("stake credential {} is not registered", credential.render()),
+            ),
+            StakeKeyNonZeroAccountBalance { remaining_balance } => Diagnostic::new(
+                "StakeKeyNonZeroAccountBalance",
+                format!("reward account still holds {}", lovelace(remaining_balance)),
+            ),
+            StakeDelegationImpossible { credential } => Diagnostic::new(
+                "StakeDelegationImpossible",
+                format!(
+                    "cannot delegate unregistered stake credential {}",
+                    credential.render()
+                ),
+            ),
+            WrongCertificateType => {
+                Diagnostic::new("WrongCertificateType", "certificate is not allowed here")
+            }
+            GenesisKeyNotInMapping { genesis_key } => Diagnostic::new(
+                "GenesisKeyNotInMapping",
+                format!("{} is not a genesis key", genesis_key.render()),
+            ),
+            DuplicateGenesisDelegate { delegate } => Diagnostic::new(
+                "DuplicateGenesisDelegate",
+                format!("{} is already a genesis delegate", delegate.render()),
+            ),
+            InsufficientForInstantaneousRewards { pot, bound } => Diagnostic::new(
+                "InsufficientForInstantaneousRewards",
+                compare(
+                    &format!("instantaneous rewards from the {}", pot.render()),
+                    "lovelace",
+                    bound,
+                ),
+            ),
+            MIRCertificateTooLateInEpoch { cutoff } => Diagnostic::new(
+                "MIRCertificateTooLateInEpoch",
+                compare("MIR certificate slot", "", cutoff),
+            ),
+            DuplicateGenesisVRF { vrf } => Diagnostic::new(
+                "DuplicateGenesisVRF",
+                format!(
+                    "VRF key {} is already used by a genesis delegate",
+                    vrf.render()
+                ),
+            ),
+            MIRTransferNotCurrentlyAllowed => Diagnostic::new(
+                "MIRTransferNotCurrentlyAllowed",
+                "MIR transfers between pots are not allowed yet",
+            ),
+            MIRNegativesNotCurrentlyAllowed => Diagnostic::new(
+                "MIRNegativesNotCurrentlyAllowed",
+                "negative MIR amounts are not allowed yet",
+            ),
+            InsufficientForTransfer { pot, bound } => Diagnostic::new(
+                "InsufficientForTransfer",
+                compare(
+                    &format!("transfer from the {}", pot.render()),
+                    "lovelace",
+                    bound,
+                ),
+            ),
+            MIRProducesNegativeUpdate => Diagnostic::new(
+                "MIRProducesNegativeUpdate",
+                "MIR certificate would make a reward balance negative",
+            ),
+            MIRNegativeTransfer { pot, attempted } => Diagnostic::new(
+                "MIRNegativeTransfer",
+                format!(
+                    "negative transfer of {} from the {}",
+                    lovelace(attempted),
+                    pot.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl Diagnose for shelley::PoolPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::PoolPredicateFailure::*;
+        match self {
+            StakePoolNotRegisteredOnKey { pool_id } => Diagnostic::new(
+                "StakePoolNotRegisteredOnKey",
+                format!("stake pool {} is not registered", pool_id.render()),
+            ),
+            StakePoolRetirementWrongEpoch {
+                retirement_too_early,
+                retirement_too_late,
+            } => Diagnostic::new(
+                "StakePoolRetirementWrongEpoch",
+                format!(
+                    "retirement epoch {} must be after epoch {} and at most epoch {}",
+                    retirement_too_late.supplied.0,
+                    retirement_too_early.expected.0,
+                    retirement_too_late.expected.0
+                ),
+            ),
+            StakePoolCostTooLow { cost_bound } => Diagnostic::new(
+                "StakePoolCostTooLow",
+                compare("pool cost", "lovelace", cost_bound),
+            ),
+            WrongNetwork {
+                network_mismatch,
+                pool_id,
+            } => Diagnostic::new(
+                "WrongNetwork",
+                format!(
+                    "{} for pool {}",
+                    compare("reward account network", "", network_mismatch),
+                    pool_id.render()
+                ),
+            ),
+            PoolMetadataHashTooBig { pool_id, hash_size } => Diagnostic::new(
+                "PoolMetadataHashTooBig",
+                format!(
+                    "metadata hash of pool {} is {hash_size} bytes long",
+                    pool_id.render()
+                ),
+            ),
+            VRFKeyHashAlreadyRegistered { pool_id, vrf } => Diagnostic::new(
+                "VRFKeyHashAlreadyRegistered",
+                format!(
+                    "VRF key {} of pool {} is already registered",
+                    vrf.render(),
+                    pool_id.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl Diagnose for shelley::DelegsPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::DelegsPredicateFailure::*;
+        match self {
+            DelegateeNotRegistered { pool_id } => Diagnostic::new(
+                "DelegateeNotRegistered",
+                format!("delegation to unregistered pool {}", pool_id.render()),
+            ),
+            WithdrawalsNotInRewards { withdrawals } => Diagnostic::new(
+                "WithdrawalsNotInRewards",
+                format!(
+                    "withdrawals do not match the reward balances: {}",
+                    withdrawals.render()
+                ),
+            ),
+            DelplFailure(failure) => failure.diagnose().within("DelplFailure"),
+        }
+    }
+}
+
+impl Diagnose for shelley::DelplPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::DelplPredicateFailure::*;
+        match self {
+            PoolFailure(failure) => failure.diagnose().within("PoolFailure"),
+            DelegFailure(failure) => failure.diagnose().within("DelegFailure"),
+        }
+    }
+}
+
+impl<Utxo: Diagnose> Diagnose for shelley::LedgerPredicateFailure<Utxo> {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::LedgerPredicateFailure::*;
+        match self {
+            UtxowFailure(failure) => failure.diagnose().within("UtxowFailure"),
+            DelegsFailure(failure) => failure.diagnose().within("DelegsFailure"),
+        }
+    }
+}
+
+impl<E: Era> Diagnose for shelley::BbodyPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use shelley::BbodyPredicateFailure::*;
+        match self {
+            WrongBlockBodySizeBBODY { mismatch } => Diagnostic::new(
+                "WrongBlockBodySizeBBODY",
+                compare("block body size", "bytes", mismatch),
+            ),
+            InvalidBodyHashBBODY { mismatch } => Diagnostic::new(
+                "InvalidBodyHashBBODY",
+                compare("block body hash", "", mismatch),
+            ),
+            LedgersFailure(failure) => failure.diagnose().within("LedgersFailure"),
+        }
+    }
+}
+
+impl<Ledger: Diagnose> Diagnose for shelley::LedgersPredicateFailure<Ledger> {
+    fn diagnose(&self) -> Diagnostic {
+        match self {
+            shelley::LedgersPredicateFailure::LedgersFailure(failure) => {
+                failure.diagnose().within("LedgersFailure")
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Allegra & Mary era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: Era> Diagnose for allegra::UtxoPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use allegra::UtxoPredicateFailure::*;
+        match self {
+            BadInputsUTxO { invalid_inputs } => Diagnostic::new(
+                "BadInputsUTxO",
+                format!("inputs not in the UTxO: {}", invalid_inputs.render()),
+            ),
+            OutsideValidityIntervalUTxO {
+                interval,
+                current_slot,
+            } => Diagnostic::new(
+                "OutsideValidityIntervalUTxO",
+                outside_validity_interval(interval, current_slot),
+            ),
+            MaxTxSizeUTxO { size_mismatch } => Diagnostic::new(
+                "MaxTxSizeUTxO",
+                compare("transaction size", "bytes", size_mismatch),
+            ),
+            InputSetEmptyUTxO => Diagnostic::new("InputSetEmptyUTxO", "transaction has no inputs"),
+            FeeTooSmallUTxO { fee_mismatch } => {
+                Diagnostic::new("FeeTooSmallUTxO", compare("fee", "lovelace", fee_mismatch))
+            }
+            ValueNotConservedUTxO { balance_mismatch } => Diagnostic::new(
+                "ValueNotConservedUTxO",
+                value_not_conserved(balance_mismatch),
+            ),
+            OutputTooSmallUTxO { tiny_outputs } => Diagnostic::new(
+                "OutputTooSmallUTxO",
+                format!(
+                    "outputs below the minimum UTxO value: {}",
+                    render_outputs(tiny_outputs)
+                ),
+            ),
+            UpdateFailure(failure) => failure.diagnose().within("UpdateFailure"),
+            WrongNetwork {
+                expected,
+                offending,
+            } => Diagnostic::new("WrongNetwork", wrong_network(expected, offending)),
+            WrongNetworkWithdrawal {
+                expected,
+                offending,
+            } => Diagnostic::new("WrongNetworkWithdrawal", wrong_network(expected, offending)),
+            OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs,
+            } => Diagnostic::new(
+                "OutputBootAddrAttrsTooBig",
+                format!(
+                    "bootstrap address attributes too big in outputs: {}",
+                    render_outputs(oversized_bootstrap_outputs)
+                ),
+            ),
+            TriesToForgeADA => Diagnostic::new("TriesToForgeADA", "transaction mints lovelace"),
+            OutputTooBigUTxO { oversized_outputs } => Diagnostic::new(
+                "OutputTooBigUTxO",
+                format!(
+                    "outputs above the maximum value size: {}",
+                    render_outputs(oversized_outputs)
+                ),
+            ),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Alonzo era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> Diagnose for alonzo::BbodyPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use alonzo::BbodyPredicateFailure::*;
+        match self {
+            ShelleyInAlonzo(failure) => failure.diagnose().within("ShelleyInAlonzo"),
+            TooManyExUnits { bound } => Diagnostic::new(
+                "TooManyExUnits",
+                compare("block execution units", "", bound),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for alonzo::UtxoPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use alonzo::UtxoPredicateFailure::*;
+        match self {
+            BadInputsUTxO { invalid_inputs } => Diagnostic::new(
+                "BadInputsUTxO",
+                format!("inputs not in the UTxO: {}", invalid_inputs.render()),
+            ),
+            OutsideValidityIntervalUTxO {
+                interval,
+                current_slot,
+            } => Diagnostic::new(
+                "OutsideValidityIntervalUTxO",
+                outside_validity_interval(interval, current_slot),
+            ),
+            MaxTxSizeUTxO { bound } => {
+                Diagnostic::new("MaxTxSizeUTxO", compare("transaction size", "bytes", bound))
+            }
+            InputSetEmptyUTxO => Diagnostic::new("InputSetEmptyUTxO", "transaction has no inputs"),
+            FeeTooSmallUTxO { bound } => {
+                Diagnostic::new("FeeTooSmallUTxO", compare("fee", "lovelace", bound))
+            }
+            ValueNotConservedUTxO { mismatch } => {
+                Diagnostic::new("ValueNotConservedUTxO", value_not_conserved(mismatch))
+            }
+            OutputTooSmallUTxO { outputs } => Diagnostic::new(
+                "OutputTooSmallUTxO",
+                format!(
+                    "outputs below the minimum UTxO value: {}",
+                    render_outputs(outputs)
+                ),
+            ),
+            UtxosFailure(failure) => failure.diagnose().within("UtxosFailure"),
+            WrongNetwork {
+                expected,
+                offending,
+            } => Diagnostic::new("WrongNetwork", wrong_network(expected, offending)),
+            WrongNetworkWithdrawal {
+                expected,
+                offending,
+            } => Diagnostic::new("WrongNetworkWithdrawal", wrong_network(expected, offending)),
+            OutputBootAddrAttrsTooBig { outputs } => Diagnostic::new(
+                "OutputBootAddrAttrsTooBig",
+                format!(
+                    "bootstrap address attributes too big in outputs: {}",
+                    render_outputs(outputs)
+                ),
+            ),
+            OutputTooBigUTxO { oversized_outputs } => {
+                Diagnostic::new("OutputTooBigUTxO", oversized(oversized_outputs))
+            }
+            InsufficientCollateral { computed, required } => Diagnostic::new(
+                "InsufficientCollateral",
+                insufficient_collateral(computed, required),
+            ),
+            ScriptsNotPaidUTxO { offending_utxo } => {
+                Diagnostic::new("ScriptsNotPaidUTxO", scripts_not_paid(offending_utxo))
+            }
+            ExUnitsTooBigUTxO { bound } => {
+                Diagnostic::new("ExUnitsTooBigUTxO", compare("execution units", "", bound))
+            }
+            CollateralContainsNonADA { value } => Diagnostic::new(
+                "CollateralContainsNonADA",
+                format!("collateral holds native assets: {}", render_value(value)),
+            ),
+            WrongNetworkInTxBody { mismatch } => Diagnostic::new(
+                "WrongNetworkInTxBody",
+                compare("transaction network", "", mismatch),
+            ),
+            OutsideForecast { slot } => Diagnostic::new(
+                "OutsideForecast",
+                format!("slot {} is beyond the time forecast horizon", slot.0),
+            ),
+            TooManyCollateralInputs { bound } => Diagnostic::new(
+                "TooManyCollateralInputs",
+                compare("collateral input count", "", bound),
+            ),
+            NoCollateralInputs => Diagnostic::new(
+                "NoCollateralInputs",
+                "transaction runs scripts but has no collateral inputs",
+            ),
+        }
+    }
+}
+
+fn outside_validity_interval(interval: &ValidityInterval, current_slot: &SlotNo) -> String {
+    format!(
+        "current slot {} is outside the validity interval {}",
+        current_slot.0,
+        interval.render()
+    )
+}
+
+fn oversized<N: fmt::Display, E: Era>(outputs: &[(N, N, TxOutStruct<E>)]) -> String {
+    list(outputs.iter().map(|(actual, max, output)| {
+        format!(
+            "output {} is {actual} bytes, above the maximum {max}",
+            output.render()
+        )
+    }))
+}
+
+fn insufficient_collateral(provided: &DeltaCoin, required: &Coin) -> String {
+    let short = i128::from(required.0) - i128::from(provided.0);
+    format!(
+        "collateral {} lovelace is below the required {} (short by {short})",
+        provided.0, required.0
+    )
+}
+
+fn scripts_not_paid(utxo: &UTxOStruct) -> String {
+    format!(
+        "collateral inputs are locked by scripts: {}",
+        list(utxo.0.keys().map(Render::render))
+    )
+}
+
+impl Diagnose for alonzo::FailureDescription {
+    fn diagnose(&self) -> Diagnostic {
+        use alonzo::FailureDescription::*;
+        match self {
+            PassedUnexpectedly => Diagnostic::new(
+                "PassedUnexpectedly",
+                "scripts passed although the transaction is marked invalid",
+            ),
+            FailedUnexpectedly(descriptions) => Diagnostic::new(
+                "FailedUnexpectedly",
+                format!(
+                    "scripts failed although the transaction is marked valid ({} failures)",
+                    descriptions.len()
+                ),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for alonzo::UtxosPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use alonzo::UtxosPredicateFailure::*;
+        match self {
+            ValidationTagMismatch { description, .. } => {
+                description.diagnose().within("ValidationTagMismatch")
+            }
+            CollectErrors { errors } => collect_errors(errors),
+            UpdateFailure(failure) => failure.diagnose().within("UpdateFailure"),
+        }
+    }
+}
+
+fn collect_errors<T: Diagnose>(errors: &[T]) -> Diagnostic {
+    match errors {
+        [error] => error.diagnose().within("CollectErrors"),
+        errors => Diagnostic::new(
+            "CollectErrors",
+            list(errors.iter().map(|error| error.diagnose().to_string())),
+        ),
+    }
+}
+
+impl<E: PlutusEra> Diagnose for alonzo::CollectError<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use alonzo::CollectError::*;
+        match self {
+            NoRedeemer { purpose } => {
+                Diagnostic::new("NoRedeemer", format!("no redeemer for {purpose}"))
+            }
+            NoWitness { script_hash } => Diagnostic::new(
+                "NoWitness",
+                format!("script {} is not witnessed", script_hash.render()),
+            ),
+            NoCostModel { language } => Diagnostic::new(
+                "NoCostModel",
+                format!("no cost model for {}", language.render()),
+            ),
+            BadTranslation(error) => error.diagnose().within("BadTranslation"),
+        }
+    }
+}
+
+impl Diagnose for alonzo::ContextError {
+    fn diagnose(&self) -> Diagnostic {
+        use alonzo::ContextError::*;
+        match self {
+            TranslationLogicMissingInput { input } => Diagnostic::new(
+                "TranslationLogicMissingInput",
+                format!("input {} does not exist in the UTxO", input.render()),
+            ),
+            TimeTranslationPastHorizon { message } => Diagnostic::new(
+                "TimeTranslationPastHorizon",
+                format!("time translation requested past the horizon: {message}"),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for alonzo::UtxowPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use alonzo::UtxowPredicateFailure::*;
+        match self {
+            ShelleyInAlonzo(failure) => failure.diagnose().within("ShelleyInAlonzo"),
+            MissingRedeemers { missing } => {
+                Diagnostic::new("MissingRedeemers", missing_redeemers(missing))
+            }
+            MissingRequiredDatums {
+                missing_hashes,
+                provided_hashes,
+            } => Diagnostic::new(
+                "MissingRequiredDatums",
+                missing_datums(missing_hashes, provided_hashes),
+            ),
+            NotAllowedSupplementalDatums {
+                forbidden_hashes,
+                permitted,
+            } => Diagnostic::new(
+                "NotAllowedSupplementalDatums",
+                supplemental_datums(forbidden_hashes, permitted),
+            ),
+            PPViewHashesDontMatch { mismatch } => Diagnostic::new(
+                "PPViewHashesDontMatch",
+                compare("script integrity hash", "", mismatch),
+            ),
+            UnspendableUTxONoDatumHash { inputs } => {
+                Diagnostic::new("UnspendableUTxONoDatumHash", unspendable(inputs))
+            }
+            ExtraRedeemers { extra } => Diagnostic::new(
+                "ExtraRedeemers",
+                format!(
+                    "redeemers point at nothing: {}",
+                    list(extra.iter().map(ToString::to_string))
+                ),
+            ),
+            ScriptIntegrityHashMismatch { mismatch, .. } => Diagnostic::new(
+                "ScriptIntegrityHashMismatch",
+                compare("script integrity hash", "", mismatch),
+            ),
+        }
+    }
+}
+
+fn missing_redeemers<P: fmt::Display>(missing: &[(P, ScriptHash)]) -> String {
+    format!(
+        "missing redeemers for {}",
+        list(
+            missing
+                .iter()
+                .map(|(purpose, script)| format!("{purpose} (script {})", script.render()))
+        )
+    )
+}
+
+fn missing_datums(missing: &BTreeSet<DataHash>, provided: &BTreeSet<DataHash>) -> String {
+    format!(
+        "missing datums {} (provided {})",
+        missing.render(),
+        provided.render()
+    )
+}
+
+fn supplemental_datums(forbidden: &BTreeSet<DataHash>, allowed: &BTreeSet<DataHash>) -> String {
+    format!(
+        "datums {} are not allowed (allowed: {})",
+        forbidden.render(),
+        allowed.render()
+    )
+}
+
+fn unspendable(inputs: &BTreeSet<TxIn>) -> String {
+    format!(
+        "script-locked inputs have no datum hash: {}",
+        inputs.render()
+    )
+}
+
+impl<E: PlutusEra> Diagnose for alonzo::LedgerPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use alonzo::LedgerPredicateFailure::*;
+        match self {
+            UtxowFailure(failure) => failure.diagnose().within("UtxowFailure"),
+            DelegsFailure(failure) => failure.diagnose().within("DelegsFailure"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Babbage era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> Diagnose for babbage::UtxoPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use babbage::UtxoPredicateFailure::*;
+        match self {
+            AlonzoInBabbage(failure) => failure.diagnose().within("AlonzoInBabbage"),
+            IncorrectTotalCollateralField { provided, declared } => Diagnostic::new(
+                "IncorrectTotalCollateralField",
+                total_collateral(provided, declared),
+            ),
+            OutputTooSmall { outputs } => {
+                Diagnostic::new("OutputTooSmall", outputs_too_small(outputs))
+            }
+            NonDisjointReferenceInputs { overlapping } => Diagnostic::new(
+                "NonDisjointReferenceInputs",
+                format!(
+                    "inputs are also used as reference inputs: {}",
+                    overlapping.render()
+                ),
+            ),
+        }
+    }
+}
+
+fn total_collateral(provided: &DeltaCoin, declared: &Coin) -> String {
+    format!(
+        "collateral balance {} lovelace does not match the declared total {}",
+        provided.0, declared.0
+    )
+}
+
+fn outputs_too_small<E: Era>(outputs: &[(TxOutStruct<E>, Coin)]) -> String {
+    list(outputs.iter().map(|(output, minimum)| {
+        format!(
+            "output {} is below its minimum of {}",
+            output.render(),
+            lovelace(minimum)
+        )
+    }))
+}
+
+impl<E: PlutusEra> Diagnose for babbage::UtxowPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use babbage::UtxowPredicateFailure::*;
+        match self {
+            AlonzoInBabbage(failure) => failure.diagnose().within("AlonzoInBabbage"),
+            UtxoFailure(failure) => failure.diagnose().within("UtxoFailure"),
+            MalformedScriptWitnesses { witnesses } => Diagnostic::new(
+                "MalformedScriptWitnesses",
+                format!("malformed script witnesses: {}", witnesses.render()),
+            ),
+            MalformedReferenceScripts { scripts } => Diagnostic::new(
+                "MalformedReferenceScripts",
+                format!("malformed reference scripts: {}", scripts.render()),
+            ),
+            ScriptIntegrityHashMismatch { mismatch, .. } => Diagnostic::new(
+                "ScriptIntegrityHashMismatch",
+                compare("script integrity hash", "", mismatch),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for babbage::ContextError<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use babbage::ContextError::*;
+        match self {
+            ByronTxOutInContext { source } => Diagnostic::new(
+                "ByronTxOutInContext",
+                format!(
+                    "Byron address in {} cannot be passed to Plutus",
+                    source.render()
+                ),
+            ),
+            AlonzoContextError(error) => error.diagnose().within("AlonzoContextError"),
+            RedeemerPointerPointsToNothing { purpose } => Diagnostic::new(
+                "RedeemerPointerPointsToNothing",
+                format!("redeemer {purpose} points at nothing"),
+            ),
+            InlineDatumsNotSupported { source } => Diagnostic::new(
+                "InlineDatumsNotSupported",
+                format!(
+                    "inline datum in {} is not supported by PlutusV1",
+                    source.render()
+                ),
+            ),
+            ReferenceScriptsNotSupported { source } => Diagnostic::new(
+                "ReferenceScriptsNotSupported",
+                format!(
+                    "reference script in {} is not supported by PlutusV1",
+                    source.render()
+                ),
+            ),
+            ReferenceInputsNotSupported { inputs } => Diagnostic::new(
+                "ReferenceInputsNotSupported",
+                format!(
+                    "reference inputs are not supported by PlutusV1: {}",
+                    inputs.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for babbage::LedgerPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use babbage::LedgerPredicateFailure::*;
+        match self {
+            UtxowFailure(failure) => failure.diagnose().within("UtxowFailure"),
+            DelegsFailure(failure) => failure.diagnose().within("DelegsFailure"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Conway era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> Diagnose for conway::UtxosPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::UtxosPredicateFailure::*;
+        match self {
+            ValidationTagMismatch { tag, description } => Diagnostic::new(
+                "ValidationTagMismatch",
+                format!("transaction is marked is_valid = {tag} but {description}"),
+            ),
+            CollectErrors { errors } => collect_errors(errors),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for conway::ContextError<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::ContextError::*;
+        let unsupported = |constructor, field: &str| {
+            Diagnostic::new(
+                constructor,
+                format!("{field} cannot be passed to this Plutus version"),
+            )
+        };
+        match self {
+            BabbageContextError(error) => error.diagnose().within("BabbageContextError"),
+            CertificateNotSupported { certificate } => Diagnostic::new(
+                "CertificateNotSupported",
+                format!("certificate {certificate:?} cannot be passed to this Plutus version"),
+            ),
+            PlutusPurposeNotSupported { purpose } => Diagnostic::new(
+                "PlutusPurposeNotSupported",
+                format!("script purpose {purpose} is not supported by this Plutus version"),
+            ),
+            CurrentTreasuryFieldNotSupported { .. } => {
+                unsupported("CurrentTreasuryFieldNotSupported", "current treasury value")
+            }
+            VotingProceduresFieldNotSupported { .. } => {
+                unsupported("VotingProceduresFieldNotSupported", "voting procedures")
+            }
+            ProposalProceduresFieldNotSupported { .. } => {
+                unsupported("ProposalProceduresFieldNotSupported", "proposal procedures")
+            }
+            TreasuryDonationFieldNotSupported { .. } => {
+                unsupported("TreasuryDonationFieldNotSupported", "treasury donation")
+            }
+            ReferenceInputsNotDisjointFromInputs { common } => Diagnostic::new(
+                "ReferenceInputsNotDisjointFromInputs",
+                format!(
+                    "inputs are also used as reference inputs: {}",
+                    common.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for conway::UtxoPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::UtxoPredicateFailure::*;
+        match self {
+            UtxosFailure(failure) => failure.diagnose().within("UtxosFailure"),
+            BadInputsUTxO { invalid_inputs } => Diagnostic::new(
+                "BadInputsUTxO",
+                format!("inputs not in the UTxO: {}", invalid_inputs.render()),
+            ),
+            OutsideValidityIntervalUTxO {
+                validity_interval,
+                current_slot,
+            } => Diagnostic::new(
+                "OutsideValidityIntervalUTxO",
+                outside_validity_interval(validity_interval, current_slot),
+            ),
+            MaxTxSizeUTxO { size_mismatch } => Diagnostic::new(
+                "MaxTxSizeUTxO",
+                compare("transaction size", "bytes", size_mismatch),
+            ),
+            InputSetEmptyUTxO => Diagnostic::new("InputSetEmptyUTxO", "transaction has no inputs"),
+            FeeTooSmallUTxO { fee_mismatch } => {
+                Diagnostic::new("FeeTooSmallUTxO", compare("fee", "lovelace", fee_mismatch))
+            }
+            ValueNotConservedUTxO { balance_mismatch } => Diagnostic::new(
+                "ValueNotConservedUTxO",
+                value_not_conserved(balance_mismatch),
+            ),
+            WrongNetwork {
+                expected,
+                offending,
+            } => Diagnostic::new("WrongNetwork", wrong_network(expected, offending)),
+            WrongNetworkWithdrawal {
+                expected,
+                offending,
+            } => Diagnostic::new("WrongNetworkWithdrawal", wrong_network(expected, offending)),
+            OutputTooSmallUTxO { tiny_outputs } => Diagnostic::new(
+                "OutputTooSmallUTxO",
+                format!(
+                    "outputs below the minimum UTxO value: {}",
+                    render_outputs(tiny_outputs)
+                ),
+            ),
+            OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs,
+            } => Diagnostic::new(
+                "OutputBootAddrAttrsTooBig",
+                format!(
+                    "bootstrap address attributes too big in outputs: {}",
+                    render_outputs(oversized_bootstrap_outputs)
+                ),
+            ),
+            OutputTooBigUTxO { outputs } => Diagnostic::new("OutputTooBigUTxO", oversized(outputs)),
+            InsufficientCollateral { provided, required } => Diagnostic::new(
+                "InsufficientCollateral",
+                insufficient_collateral(provided, required),
+            ),
+            ScriptsNotPaidUTxO { unpaid } => {
+                Diagnostic::new("ScriptsNotPaidUTxO", scripts_not_paid(unpaid))
+            }
+            ExUnitsTooBigUTxO { limit_mismatch } => Diagnostic::new(
+                "ExUnitsTooBigUTxO",
+                compare("execution units", "", limit_mismatch),
+            ),
+            CollateralContainsNonADA { offending_value } => Diagnostic::new(
+                "CollateralContainsNonADA",
+                format!(
+                    "collateral holds native assets: {}",
+                    render_value(offending_value)
+                ),
+            ),
+            WrongNetworkInTxBody { mismatch } => Diagnostic::new(
+                "WrongNetworkInTxBody",
+                compare("transaction network", "", mismatch),
+            ),
+            OutsideForecast { slot } => Diagnostic::new(
+                "OutsideForecast",
+                format!("slot {} is beyond the time forecast horizon", slot.0),
+            ),
+            TooManyCollateralInputs { bound } => Diagnostic::new(
+                "TooManyCollateralInputs",
+                compare("collateral input count", "", bound),
+            ),
+            NoCollateralInputs => Diagnostic::new(
+                "NoCollateralInputs",
+                "transaction runs scripts but has no collateral inputs",
+            ),
+            IncorrectTotalCollateralField { provided, declared } => Diagnostic::new(
+                "IncorrectTotalCollateralField",
+                total_collateral(provided, declared),
+            ),
+            BabbageOutputTooSmallUTxO { outputs } => {
+                Diagnostic::new("BabbageOutputTooSmallUTxO", outputs_too_small(outputs))
+            }
+            BabbageNonDisjointRefInputs { overlapping } => Diagnostic::new(
+                "BabbageNonDisjointRefInputs",
+                format!(
+                    "inputs are also used as reference inputs: {}",
+                    overlapping.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for conway::UtxowPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::UtxowPredicateFailure::*;
+        match self {
+            UtxoFailure(failure) => failure.diagnose().within("UtxoFailure"),
+            InvalidWitnessesUTXOW { witnesses } => Diagnostic::new(
+                "InvalidWitnessesUTXOW",
+                format!("invalid signatures from keys {}", witnesses.render()),
+            ),
+            MissingVKeyWitnessesUTXOW { missing } => Diagnostic::new(
+                "MissingVKeyWitnessesUTXOW",
+                format!("missing signatures from {}", missing.render()),
+            ),
+            MissingScriptWitnessesUTXOW { missing } => Diagnostic::new(
+                "MissingScriptWitnessesUTXOW",
+                format!("missing scripts {}", missing.render()),
+            ),
+            ScriptWitnessNotValidatingUTXOW { failing } => Diagnostic::new(
+                "ScriptWitnessNotValidatingUTXOW",
+                format!("scripts failed to validate: {}", failing.render()),
+            ),
+            MissingTxBodyMetadataHash { expected } => Diagnostic::new(
+                "MissingTxBodyMetadataHash",
+                format!(
+                    "transaction has metadata but its body lacks the hash {}",
+                    expected.render()
+                ),
+            ),
+            MissingTxMetadata { expected } => Diagnostic::new(
+                "MissingTxMetadata",
+                format!(
+                    "transaction body references metadata {} that is not attached",
+                    expected.render()
+                ),
+            ),
+            ConflictingMetadataHash { mismatch } => Diagnostic::new(
+                "ConflictingMetadataHash",
+                compare("metadata hash", "", mismatch),
+            ),
+            InvalidMetadata => Diagnostic::new("InvalidMetadata", "metadata is malformed"),
+            ExtraneousScriptWitnessesUTXOW { extraneous } => Diagnostic::new(
+                "ExtraneousScriptWitnessesUTXOW",
+                format!("scripts witnessed but not needed: {}", extraneous.render()),
+            ),
+            MissingRedeemers { missing } => {
+                Diagnostic::new("MissingRedeemers", missing_redeemers(missing))
+            }
+            MissingRequiredDatums {
+                missing_hashes,
+                provided_hashes,
+            } => Diagnostic::new(
+                "MissingRequiredDatums",
+                missing_datums(missing_hashes, provided_hashes),
+            ),
+            NotAllowedSupplementalDatums {
+                disallowed_hashes,
+                allowed,
+            } => Diagnostic::new(
+                "NotAllowedSupplementalDatums",
+                supplemental_datums(disallowed_hashes, allowed),
+            ),
+            PPViewHashesDontMatch { mismatch } => Diagnostic::new(
+                "PPViewHashesDontMatch",
+                compare("script integrity hash", "", mismatch),
+            ),
+            UnspendableUTxONoDatumHash { inputs } => {
+                Diagnostic::new("UnspendableUTxONoDatumHash", unspendable(inputs))
+            }
+            ExtraRedeemers { extra } => Diagnostic::new(
+                "ExtraRedeemers",
+                format!(
+                    "redeemers point at nothing: {}",
+                    list(extra.iter().map(ToString::to_string))
+                ),
+            ),
+            MalformedScriptWitnesses { scripts } => Diagnostic::new(
+                "MalformedScriptWitnesses",
+                format!("malformed script witnesses: {}", scripts.render()),
+            ),
+            MalformedReferenceScripts { scripts } => Diagnostic::new(
+                "MalformedReferenceScripts",
+                format!("malformed reference scripts: {}", scripts.render()),
+            ),
+            ScriptIntegrityHashMismatch { mismatch, .. } => Diagnostic::new(
+                "ScriptIntegrityHashMismatch",
+                compare("script integrity hash", "", mismatch),
+            ),
+        }
+    }
+}
+
+impl Diagnose for conway::DelegPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::DelegPredicateFailure::*;
+        match self {
+            IncorrectDepositDELEG { deposit } => Diagnostic::new(
+                "IncorrectDepositDELEG",
+                format!("deposit of {} is incorrect", lovelace(deposit)),
+            ),
+            StakeKeyRegisteredDELEG { stake_credential } => Diagnostic::new(
+                "StakeKeyRegisteredDELEG",
+                format!(
+                    "stake credential {} is already registered",
+                    stake_credential.render()
+                ),
+            ),
+            StakeKeyNotRegisteredDELEG { stake_credential } => Diagnostic::new(
+                "StakeKeyNotRegisteredDELEG",
+                format!(
+                    "stake credential {} is not registered",
+                    stake_credential.render()
+                ),
+            ),
+            StakeKeyHasNonZeroRewardAccountBalanceDELEG { balance } => Diagnostic::new(
+                "StakeKeyHasNonZeroRewardAccountBalanceDELEG",
+                format!("reward account still holds {}", lovelace(balance)),
+            ),
+            DelegateeDRepNotRegisteredDELEG { delegatee } => Diagnostic::new(
+                "DelegateeDRepNotRegisteredDELEG",
+                format!(
+                    "delegation to unregistered DRep {}",
+                    drep_to_bech32(delegatee)
+                ),
+            ),
+            DelegateeStakePoolNotRegisteredDELEG { delegatee } => Diagnostic::new(
+                "DelegateeStakePoolNotRegisteredDELEG",
+                format!("delegation to unregistered pool {}", delegatee.render()),
+            ),
+            DepositIncorrectDELEG { mismatch } => Diagnostic::new(
+                "DepositIncorrectDELEG",
+                compare("deposit", "lovelace", mismatch),
+            ),
+            RefundIncorrectDELEG { mismatch } => Diagnostic::new(
+                "RefundIncorrectDELEG",
+                compare("refund", "lovelace", mismatch),
+            ),
+        }
+    }
+}
+
+impl Diagnose for conway::GovCertPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::GovCertPredicateFailure::*;
+        match self {
+            ConwayDRepAlreadyRegistered { credential } => Diagnostic::new(
+                "ConwayDRepAlreadyRegistered",
+                format!("DRep {} is already registered", drep_to_bech32(credential)),
+            ),
+            ConwayDRepNotRegistered { credential } => Diagnostic::new(
+                "ConwayDRepNotRegistered",
+                format!("DRep {} is not registered", drep_to_bech32(credential)),
+            ),
+            ConwayDRepIncorrectDeposit { mismatch } => Diagnostic::new(
+                "ConwayDRepIncorrectDeposit",
+                compare("DRep deposit", "lovelace", mismatch),
+            ),
+            ConwayCommitteeHasPreviouslyResigned { cold_credential } => Diagnostic::new(
+                "ConwayCommitteeHasPreviouslyResigned",
+                format!(
+                    "committee member {} has already resigned",
+                    cold_credential.render()
+                ),
+            ),
+            ConwayDRepIncorrectRefund { mismatch } => Diagnostic::new(
+                "ConwayDRepIncorrectRefund",
+                compare("DRep refund", "lovelace", mismatch),
+            ),
+            ConwayCommitteeIsUnknown { cold_credential } => Diagnostic::new(
+                "ConwayCommitteeIsUnknown",
+                format!("{} is not a committee member", cold_credential.render()),
+            ),
+        }
+    }
+}
+
+impl Diagnose for conway::CertPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::CertPredicateFailure::*;
+        match self {
+            DelegFailure(failure) => failure.diagnose().within("DelegFailure"),
+            PoolFailure(failure) => failure.diagnose().within("PoolFailure"),
+            GovCertFailure(failure) => failure.diagnose().within("GovCertFailure"),
+        }
+    }
+}
+
+impl Diagnose for conway::CertsPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::CertsPredicateFailure::*;
+        match self {
+            WithdrawalsNotInRewardsCERTS { withdrawals } => Diagnostic::new(
+                "WithdrawalsNotInRewardsCERTS",
+                format!(
+                    "withdrawals do not match the reward balances: {}",
+                    withdrawals.render()
+                ),
+            ),
+            CertFailure(failure) => failure.diagnose().within("CertFailure"),
+        }
+    }
+}
+
+impl Diagnose for conway::GovPredicateFailure {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::GovPredicateFailure::*;
+        match self {
+            GovActionsDoNotExist { missing } => Diagnostic::new(
+                "GovActionsDoNotExist",
+                format!("governance actions do not exist: {}", missing.render()),
+            ),
+            MalformedProposal { proposal } => Diagnostic::new(
+                "MalformedProposal",
+                format!("proposal is malformed: {proposal:?}"),
+            ),
+            ProposalProcedureNetworkIdMismatch {
+                reward_account,
+                expected_network,
+            } => Diagnostic::new(
+                "ProposalProcedureNetworkIdMismatch",
+                format!(
+                    "return account {} is not on {}",
+                    reward_account.render(),
+                    expected_network.render()
+                ),
+            ),
+            TreasuryWithdrawalsNetworkIdMismatch {
+                offending_accounts,
+                expected_network,
+            } => Diagnostic::new(
+                "TreasuryWithdrawalsNetworkIdMismatch",
+                wrong_network(expected_network, offending_accounts),
+            ),
+            ProposalDepositIncorrect { mismatch } => Diagnostic::new(
+                "ProposalDepositIncorrect",
+                compare("proposal deposit", "lovelace", mismatch),
+            ),
+            DisallowedVoters { voters } => Diagnostic::new(
+                "DisallowedVoters",
+                format!("voters may not vote on these actions: {}", voters.render()),
+            ),
+            ConflictingCommitteeUpdate { members } => Diagnostic::new(
+                "ConflictingCommitteeUpdate",
+                format!("members are both added and removed: {}", members.render()),
+            ),
+            ExpirationEpochTooSmall { expired } => Diagnostic::new(
+                "ExpirationEpochTooSmall",
+                format!(
+                    "members would expire immediately: {}",
+                    list(expired.iter().map(|(member, epoch)| format!(
+                        "{} at epoch {}",
+                        member.render(),
+                        epoch.0
+                    )))
+                ),
+            ),
+            InvalidPrevGovActionId { proposal } => Diagnostic::new(
+                "InvalidPrevGovActionId",
+                format!(
+                    "proposal refers to the wrong previous action: {:?}",
+                    proposal.action
+                ),
+            ),
+            VotingOnExpiredGovAction { votes } => Diagnostic::new(
+                "VotingOnExpiredGovAction",
+                format!("votes on expired actions: {}", votes.render()),
+            ),
+            ProposalCantFollow {
+                version_mismatch, ..
+            } => Diagnostic::new(
+                "ProposalCantFollow",
+                compare("proposed protocol version", "", version_mismatch),
+            ),
+            InvalidPolicyHash { provided, expected } => Diagnostic::new(
+                "InvalidPolicyHash",
+                format!(
+                    "guardrail script {} does not match the constitution's {}",
+                    provided.render(),
+                    expected.render()
+                ),
+            ),
+            DisallowedProposalDuringBootstrap { proposal } => Diagnostic::new(
+                "DisallowedProposalDuringBootstrap",
+                format!(
+                    "proposal is not allowed during bootstrap: {:?}",
+                    proposal.action
+                ),
+            ),
+            DisallowedVotesDuringBootstrap { votes } => Diagnostic::new(
+                "DisallowedVotesDuringBootstrap",
+                format!("votes are not allowed during bootstrap: {}", votes.render()),
+            ),
+            VotersDoNotExist { voters } => Diagnostic::new(
+                "VotersDoNotExist",
+                format!("voters are not registered: {}", voters.render()),
+            ),
+            ZeroTreasuryWithdrawals { .. } => Diagnostic::new(
+                "ZeroTreasuryWithdrawals",
+                "treasury withdrawal proposal withdraws nothing",
+            ),
+            ProposalReturnAccountDoesNotExist { reward_account } => Diagnostic::new(
+                "ProposalReturnAccountDoesNotExist",
+                format!(
+                    "return account {} is not registered",
+                    reward_account.render()
+                ),
+            ),
+            TreasuryWithdrawalReturnAccountsDoNotExist { reward_accounts } => Diagnostic::new(
+                "TreasuryWithdrawalReturnAccountsDoNotExist",
+                format!(
+                    "withdrawal accounts are not registered: {}",
+                    reward_accounts.render()
+                ),
+            ),
+            UnelectedCommitteeVoters { voters } => Diagnostic::new(
+                "UnelectedCommitteeVoters",
+                format!(
+                    "committee voters are not elected members: {}",
+                    voters.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for conway::LedgerPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::LedgerPredicateFailure::*;
+        match self {
+            ConwayUtxowFailure(failure) => failure.diagnose().within("ConwayUtxowFailure"),
+            ConwayCertsFailure(failure) => failure.diagnose().within("ConwayCertsFailure"),
+            ConwayGovFailure(failure) => failure.diagnose().within("ConwayGovFailure"),
+            ConwayWdrlNotDelegatedToDRep { withdrawals } => Diagnostic::new(
+                "ConwayWdrlNotDelegatedToDRep",
+                format!(
+                    "withdrawing accounts are not delegated to a DRep: {}",
+                    withdrawals.render()
+                ),
+            ),
+            ConwayTreasuryValueMismatch { mismatch } => Diagnostic::new(
+                "ConwayTreasuryValueMismatch",
+                compare("declared treasury", "lovelace", mismatch),
+            ),
+            ConwayTxRefScriptsSizeTooBig { size_mismatch } => Diagnostic::new(
+                "ConwayTxRefScriptsSizeTooBig",
+                compare("reference scripts size", "bytes", size_mismatch),
+            ),
+            ConwayMempoolFailure { reason } => Diagnostic::new(
+                "ConwayMempoolFailure",
+                format!("rejected by the mempool: {reason}"),
+            ),
+            ConwayWithdrawalsMissingAccounts { withdrawals } => Diagnostic::new(
+                "ConwayWithdrawalsMissingAccounts",
+                format!(
+                    "withdrawals from unregistered accounts: {}",
+                    withdrawals.render()
+                ),
+            ),
+            ConwayIncompleteWithdrawals { withdrawals } => Diagnostic::new(
+                "ConwayIncompleteWithdrawals",
+                format!(
+                    "withdrawals must drain the whole balance: {}",
+                    withdrawals.render()
+                ),
+            ),
+        }
+    }
+}
+
+impl<E: PlutusEra> Diagnose for conway::BbodyPredicateFailure<E> {
+    fn diagnose(&self) -> Diagnostic {
+        use conway::BbodyPredicateFailure::*;
+        match self {
+            WrongBlockBodySizeBBODY { mismatch } => Diagnostic::new(
+                "WrongBlockBodySizeBBODY",
+                compare("block body size", "bytes", mismatch),
+            ),
+            InvalidBodyHashBBODY { mismatch } => Diagnostic::new(
+                "InvalidBodyHashBBODY",
+                compare("block body hash", "", mismatch),
+            ),
+            LedgersFailure(failure) => failure.diagnose().within("LedgersFailure"),
+            TooManyExUnits { bound } => Diagnostic::new(
+                "TooManyExUnits",
+                compare("block execution units", "", bound),
+            ),
+            BodyRefScriptsSizeTooBig { size_mismatch } => Diagnostic::new(
+                "BodyRefScriptsSizeTooBig",
+                compare("block reference scripts size", "bytes", size_mismatch),
+            ),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Display
+// ---------------------------------------------------------------------------
+
+macro_rules! display_via_diagnose {
+    ($(impl$(<$param:ident: $bound:ident>)? for $ty:ty;)*) => {
+        $(
+            impl$(<$param: $bound>)? fmt::Display for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    self.diagnose().fmt(f)
+                }
+            }
+        )*
+    };
+}
+
+display_via_diagnose! {
+    impl for shelley::PpupPredicateFailure;
+    impl<E: Era> for shelley::UtxoPredicateFailure<E>;
+    impl<Utxo: Diagnose> for shelley::UtxowPredicateFailure<Utxo>;
+    impl for shelley::DelegPredicateFailure;
+    impl for shelley::PoolPredicateFailure;
+    impl for shelley::DelegsPredicateFailure;
+    impl for shelley::DelplPredicateFailure;
+    impl<Utxo: Diagnose> for shelley::LedgerPredicateFailure<Utxo>;
+    impl<E: Era> for shelley::BbodyPredicateFailure<E>;
+    impl<Ledger: Diagnose> for shelley::LedgersPredicateFailure<Ledger>;
+    impl<E: Era> for allegra::UtxoPredicateFailure<E>;
+    impl<E: PlutusEra> for alonzo::BbodyPredicateFailure<E>;
+    impl<E: PlutusEra> for alonzo::UtxoPredicateFailure<E>;
+    impl for alonzo::FailureDescription;
+    impl<E: PlutusEra> for alonzo::UtxosPredicateFailure<E>;
+    impl<E: PlutusEra> for alonzo::CollectError<E>;
+    impl for alonzo::ContextError;
+    impl<E: PlutusEra> for alonzo::UtxowPredicateFailure<E>;
+    impl<E: PlutusEra> for alonzo::LedgerPredicateFailure<E>;
+    impl<E: PlutusEra> for babbage::UtxoPredicateFailure<E>;
+    impl<E: PlutusEra> for babbage::UtxowPredicateFailure<E>;
+    impl<E: PlutusEra> for babbage::ContextError<E>;
+    impl<E: PlutusEra> for babbage::LedgerPredicateFailure<E>;
+    impl<E: PlutusEra> for conway::UtxosPredicateFailure<E>;
+    impl<E: PlutusEra> for conway::ContextError<E>;
+    impl<E: PlutusEra> for conway::UtxoPredicateFailure<E>;
+    impl<E: PlutusEra> for conway::UtxowPredicateFailure<E>;
+    impl for conway::DelegPredicateFailure;
+    impl for conway::GovCertPredicateFailure;
+    impl for conway::CertPredicateFailure;
+    impl for conway::CertsPredicateFailure;
+    impl for conway::GovPredicateFailure;
+    impl<E: PlutusEra> for conway::LedgerPredicateFailure<E>;
+    impl<E: PlutusEra> for conway::BbodyPredicateFailure<E>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure_decoder::decode_failure_bytes;
+
+    #[test]
+    fn renders_fee_shortfall_with_breadcrumb() {
+        let failure: conway::LedgerPredicateFailure<Conway> =
+            conway::LedgerPredicateFailure::ConwayUtxowFailure(
+                conway::UtxowPredicateFailure::UtxoFailure(
+                    conway::UtxoPredicateFailure::FeeTooSmallUTxO {
+                        fee_mismatch: Mismatch {
+                            relation: RelGTEQ,
+                            supplied: Coin(170_000),
+                            expected: Coin(172_345),
+                        },
+                    },
+                ),
+            );
+
+        let diagnostic = failure.diagnose();
+        assert_eq!(
+            diagnostic.path,
+            ["ConwayUtxowFailure", "UtxoFailure", "FeeTooSmallUTxO"]
+        );
+        assert_eq!(
+            diagnostic.message,
+            "fee 170000 lovelace is below the required 172345 (short by 2345)"
+        );
+        assert_eq!(
+            failure.to_string(),
+            "ConwayUtxowFailure > UtxoFailure > FeeTooSmallUTxO: \
+             fee 170000 lovelace is below the required 172345 (short by 2345)"
+        );
+    }
+
+    #[test]
+    fn renders_upper_bound_overrun() {
+        let failure: alonzo::UtxoPredicateFailure<Alonzo> =
+            alonzo::UtxoPredicateFailure::MaxTxSizeUTxO {
+                bound: Mismatch {
+                    relation: RelLTEQ,
+                    supplied: 17_000,
+                    expected: 16_384,
+                },
+            };
+        assert_eq!(
+            failure.diagnose().message,
+            "transaction size 17000 bytes exceeds the maximum 16384 (over by 616)"
+        );
+    }
+
+    #[test]
+    fn explains_value_not_conserved_per_asset() {
+        let policy = ScriptHash(Hash28([0xab; 28]));
+        let tokens = |quantity| {
+            MultiAsset(BTreeMap::from([(
+                policy.clone(),
+                BTreeMap::from([(AssetName(b"HOSKY".to_vec()), quantity)]),
+            )]))
+        };
+        let failure: conway::UtxoPredicateFailure<Conway> =
+            conway::UtxoPredicateFailure::ValueNotConservedUTxO {
+                balance_mismatch: Mismatch {
+                    relation: RelEQ,
+                    supplied: ValueStruct::MultiAsset {
+                        coin: Coin(3_000_000),
+                        assets: tokens(7),
+                    },
+                    expected: ValueStruct::MultiAsset {
+                        coin: Coin(2_000_000),
+                        assets: tokens(10),
+                    },
+                },
+            };
+        assert_eq!(
+            failure.diagnose().message,
+            format!(
+                "consumed 3000000 lovelace and 1 native assets but produced 2000000 lovelace \
+                 and 1 native assets: 1000000 lovelace consumed but not produced, missing 3 \
+                 tokens of policy {} / asset HOSKY",
+                "ab".repeat(28)
+            )
+        );
+    }
+
+    #[test]
+    fn nests_breadcrumbs_through_era_wrappers() {
+        let failure: babbage::UtxowPredicateFailure<Babbage> =
+            babbage::UtxowPredicateFailure::UtxoFailure(
+                babbage::UtxoPredicateFailure::AlonzoInBabbage(
+                    alonzo::UtxoPredicateFailure::UtxosFailure(
+                        alonzo::UtxosPredicateFailure::CollectErrors {
+                            errors: vec![alonzo::CollectError::NoCostModel {
+                                language: Language::PlutusV2,
+                            }],
+                        },
+                    ),
+                ),
+            );
+        let diagnostic = failure.diagnose();
+        assert_eq!(
+            diagnostic.path,
+            [
+                "UtxoFailure",
+                "AlonzoInBabbage",
+                "UtxosFailure",
+                "CollectErrors",
+                "NoCostModel"
+            ]
+        );
+        assert_eq!(diagnostic.message, "no cost model for PlutusV2");
+    }
+
+    #[test]
+    fn explains_addresses_on_the_wrong_network() {
+        let mut testnet = vec![0x60];
+        testnet.extend([0x11; 28]);
+        let failure: allegra::UtxoPredicateFailure<Allegra> =
+            allegra::UtxoPredicateFailure::WrongNetwork {
+                expected: NetworkId(1),
+                offending: BTreeSet::from([Address(testnet), Address(vec![0x61, 1, 2])]),
+            };
+        assert_eq!(
+            failure.to_string(),
+            "WrongNetwork: expected mainnet addresses but got \
+             addr_test1vqg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygxrcya6 \
+             (testnet enterprise address), \
+             addr1vyqsyf3mcax (malformed address: unexpected address length)"
+        );
+
+        let mut account = vec![0xe0];
+        account.extend([0x33; 28]);
+        let failure: shelley::UtxoPredicateFailure<Shelley> =
+            shelley::UtxoPredicateFailure::WrongNetworkWithdrawal {
+                expected: NetworkId(1),
+                offending: BTreeSet::from([RewardAccount(account)]),
+            };
+        assert_eq!(
+            failure.diagnose().message,
+            "expected mainnet addresses but got \
+             stake_test1uqenxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxvc9n6u70 \
+             (testnet reward address)"
+        );
+    }
+
+    #[test]
+    fn explains_each_unbalanced_asset() {
+        let (hosky, snek) = (
+            ScriptHash(Hash28([0xab; 28])),
+            ScriptHash(Hash28([0xcd; 28])),
+        );
+        let value = |coin, hoskies, sneks| ValueStruct::MultiAsset {
+            coin: Coin(coin),
+            assets: MultiAsset(BTreeMap::from([
+                (
+                    hosky.clone(),
+                    BTreeMap::from([(AssetName(b"HOSKY".to_vec()), hoskies)]),
+                ),
+                (
+                    snek.clone(),
+                    BTreeMap::from([(AssetName(b"SNEK".to_vec()), sneks)]),
+                ),
+            ])),
+        };
+        let failure: mary::UtxoPredicateFailure<Mary> =
+            mary::UtxoPredicateFailure::ValueNotConservedUTxO {
+                balance_mismatch: Mismatch {
+                    relation: RelEQ,
+                    supplied: value(2_000_000, 5, 1),
+                    expected: value(2_000_000, 5, 3),
+                },
+            };
+        assert_eq!(
+            failure.diagnose().message,
+            format!(
+                "consumed 2000000 lovelace and 2 native assets but produced 2000000 lovelace \
+                 and 2 native assets: missing 2 tokens of policy {} / asset SNEK",
+                "cd".repeat(28)
+            )
+        );
+
+        let failure: mary::UtxoPredicateFailure<Mary> =
+            mary::UtxoPredicateFailure::ValueNotConservedUTxO {
+                balance_mismatch: Mismatch {
+                    relation: RelEQ,
+                    supplied: value(2_000_000, 6, 3),
+                    expected: value(1_500_000, 5, 4),
+                },
+            };
+        assert!(failure.diagnose().message.ends_with(&format!(
+            "500000 lovelace consumed but not produced, 1 token of policy {} / asset HOSKY \
+             consumed but not produced, missing 1 token of policy {} / asset SNEK",
+            "ab".repeat(28),
+            "cd".repeat(28)
+        )));
+    }
+
+    #[test]
+    fn falls_back_on_unknown_constructors() {
+        // ConwayUtxowFailure (UtxoFailure <tag 99>).
+        let err = decode_failure_bytes::<conway::LedgerPredicateFailure<Conway>>(&[
+            0x82, 0x01, 0x82, 0x00, 0x81, 0x18, 0x63,
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.diagnose().to_string(),
+            "LedgerPredicateFailure > UtxowPredicateFailure > UtxoPredicateFailure: \
+             unknown constructor tag 99, perhaps added by a newer node"
+        );
+    }
+}
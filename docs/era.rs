@@ -0,0 +1,227 @@
+//! Ledger eras as marker types.
+//!
+//! The predicate failures of a rule are shared by several eras, but the
+//! payloads they carry are not: a Shelley `ValueNotConservedUTxO` compares
+//! plain lovelace, while the same failure from Mary onwards compares
+//! multi-asset values.  The Haskell ledger resolves this through type
+//! families indexed by the era (`Value era`, `TxOut era`, `Script era`); the
+//! [`Era`] trait plays that role here, so that `allegra::UtxoPredicateFailure<Allegra>`
+//! and `mary::UtxoPredicateFailure<Mary>` decode the same bytes differently.
+
+use std::fmt;
+use std::hash::Hash;
+
+use crate::apply_tx_error::{
+    ALLEGRA_ERA_INDEX, ALONZO_ERA_INDEX, BABBAGE_ERA_INDEX, CONWAY_ERA_INDEX, DIJKSTRA_ERA_INDEX,
+    MARY_ERA_INDEX, SHELLEY_ERA_INDEX,
+};
+use crate::diagnostic::Diagnose;
+use crate::failure_decoder::{FromTaggedTree, FromTerm};
+use crate::failure_encoder::ToTerm;
+use crate::failure_json::{FromJson, ToJson};
+use crate::rust_rule_errors::*;
+
+/// A ledger era, with the types its rules are instantiated at.
+pub trait Era: Copy + Default + fmt::Debug + Eq + Ord + Hash + 'static {
+    /// Era name as printed by the node, e.g. in `EraMismatch`.
+    const NAME: &'static str;
+    /// Hard-fork combinator index, which also orders the eras: features such
+    /// as datum hashes on outputs are available from some index onwards.
+    const INDEX: u64;
+
+    /// Amounts moved by a transaction: lovelace until Mary, multi-asset after.
+    type Value: Clone
+        + fmt::Debug
+        + Default
+        + Ord
+        + Hash
+        + FromTerm
+        + ToTerm
+        + ToJson
+        + FromJson
+        + Into<ValueStruct>;
+    /// Scripts: native scripts until Alonzo, which added Plutus.  They are
+    /// what an output's reference script holds.
+    type Script: Clone + fmt::Debug + Ord + Hash + FromTerm + ToTerm + ToJson + FromJson;
+}
+
+macro_rules! eras {
+    ($($(#[$doc:meta])* $era:ident($index:expr) { $($assoc:ident = $ty:ty;)* })*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+            pub struct $era;
+
+            impl Era for $era {
+                const NAME: &'static str = stringify!($era);
+                const INDEX: u64 = $index;
+                $(type $assoc = $ty;)*
+            }
+        )*
+    };
+}
+
+eras! {
+    /// Shelley: lovelace-only values and multi-signature scripts.
+    Shelley(SHELLEY_ERA_INDEX) { Value = Coin; Script = NativeScript; }
+    /// Allegra: adds validity intervals to native scripts.
+    Allegra(ALLEGRA_ERA_INDEX) { Value = Coin; Script = NativeScript; }
+    /// Mary: multi-asset values.
+    Mary(MARY_ERA_INDEX) { Value = ValueStruct; Script = NativeScript; }
+    /// Alonzo: Plutus scripts and datum hashes on outputs.
+    Alonzo(ALONZO_ERA_INDEX) { Value = ValueStruct; Script = Script; }
+    /// Babbage: map-encoded outputs with inline datums and reference scripts.
+    Babbage(BABBAGE_ERA_INDEX) { Value = ValueStruct; Script = Script; }
+    /// Conway: on-chain governance.
+    Conway(CONWAY_ERA_INDEX) { Value = ValueStruct; Script = Script; }
+    /// Dijkstra: the era after Conway, with guard scripts.
+    Dijkstra(DIJKSTRA_ERA_INDEX) { Value = ValueStruct; Script = dijkstra::Script; }
+}
+
+/// `EraPlutusContext`: an era with Plutus scripts, whose `TxInfo` can fail
+/// to build.
+pub trait PlutusEra: Era {
+    /// Reason the `TxInfo` could not be built, carried by `BadTranslation`.
+    type ContextError: Clone
+        + fmt::Debug
+        + Eq
+        + FromTaggedTree
+        + ToTerm
+        + ToJson
+        + FromJson
+        + Diagnose;
+    /// Certificates, as named by `CertificateNotSupported`.
+    type TxCert: Clone + fmt::Debug + Eq + FromTerm + ToTerm + ToJson + FromJson;
+    /// `PlutusPurpose AsIx era`: a redeemer pointer.
+    type PurposeIx: Clone + fmt::Debug + fmt::Display + Eq + FromTerm + ToTerm + ToJson + FromJson;
+    /// `PlutusPurpose AsItem era`: the item a redeemer is attached to.
+    type PurposeItem: Clone + fmt::Debug + fmt::Display + Eq + FromTerm + ToTerm + ToJson + FromJson;
+}
+
+impl PlutusEra for Alonzo {
+    type ContextError = alonzo::ContextError;
+    type TxCert = ShelleyTxCert;
+    type PurposeIx = AlonzoPlutusPurpose;
+    type PurposeItem = AlonzoPlutusPurposeItem;
+}
+
+impl PlutusEra for Babbage {
+    type ContextError = babbage::ContextError<Self>;
+    type TxCert = ShelleyTxCert;
+    type PurposeIx = AlonzoPlutusPurpose;
+    type PurposeItem = AlonzoPlutusPurposeItem;
+}
+
+impl PlutusEra for Conway {
+    type ContextError = conway::ContextError<Self>;
+    type TxCert = PoolCert;
+    type PurposeIx = PlutusPurposeStruct;
+    type PurposeItem = PlutusPurposeItemStruct;
+}
+
+impl PlutusEra for Dijkstra {
+    type ContextError = dijkstra::ContextError<Self>;
+    type TxCert = dijkstra::TxCert;
+    type PurposeIx = dijkstra::PlutusPurpose;
+    type PurposeItem = dijkstra::PlutusPurposeItem;
+}
+
+impl From<Coin> for ValueStruct {
+    fn from(coin: Coin) -> Self {
+        ValueStruct::Coin(coin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_parser::decode_term;
+    use crate::failure_decoder::decode_failure_bytes;
+    use ciborium::{ser::into_writer, value::Value};
+
+    fn uint(n: u64) -> Value {
+        Value::Integer(n.into())
+    }
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        into_writer(value, &mut buffer).expect("encode test value");
+        buffer
+    }
+
+    #[test]
+    fn decodes_values_per_era() {
+        // ValueNotConservedUTxO (Mismatch [2000000, {policy: {"": 1}}] 2000000)
+        let assets = Value::Map(vec![(
+            Value::Bytes(vec![0x11; 28]),
+            Value::Map(vec![(Value::Bytes(Vec::new()), uint(1))]),
+        )]);
+        let tokens = Value::Array(vec![
+            uint(5),
+            Value::Array(vec![
+                Value::Array(vec![uint(2_000_000), assets]),
+                uint(2_000_000),
+            ]),
+        ]);
+        let lovelace = Value::Array(vec![
+            uint(5),
+            Value::Array(vec![uint(2_000_000), uint(1_800_000)]),
+        ]);
+
+        let failure: mary::UtxoPredicateFailure<Mary> =
+            decode_failure_bytes(&encode(&tokens)).expect("decode Mary failure");
+        match failure {
+            mary::UtxoPredicateFailure::ValueNotConservedUTxO { balance_mismatch } => {
+                assert_eq!(balance_mismatch.supplied.assets().0.len(), 1);
+                assert_eq!(
+                    balance_mismatch.expected,
+                    ValueStruct::Coin(Coin(2_000_000))
+                );
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+        assert!(
+            decode_failure_bytes::<allegra::UtxoPredicateFailure<Allegra>>(&encode(&tokens))
+                .is_err()
+        );
+
+        let failure: shelley::UtxoPredicateFailure<Shelley> =
+            decode_failure_bytes(&encode(&lovelace)).expect("decode Shelley failure");
+        match failure {
+            shelley::UtxoPredicateFailure::ValueNotConservedUTxO { balance_mismatch } => {
+                assert_eq!(balance_mismatch.supplied, Coin(2_000_000));
+                assert_eq!(balance_mismatch.expected, Coin(1_800_000));
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+    }
+    #[test]
+    fn rejects_outputs_the_era_cannot_produce() {
+        let address = Value::Bytes(vec![0x61; 29]);
+        let tokens = Value::Array(vec![
+            uint(2_000_000),
+            Value::Map(vec![(
+                Value::Bytes(vec![0x11; 28]),
+                Value::Map(vec![(Value::Bytes(Vec::new()), uint(1))]),
+            )]),
+        ]);
+        let multi_asset = encode(&Value::Array(vec![address.clone(), tokens]));
+        let datum_hash = encode(&Value::Array(vec![
+            address.clone(),
+            uint(2_000_000),
+            Value::Bytes(vec![5; 32]),
+        ]));
+        let map_form = encode(&Value::Map(vec![
+            (uint(0), address),
+            (uint(1), uint(2_000_000)),
+        ]));
+        let output = |bytes: &[u8]| decode_term(bytes).expect("decode test term");
+
+        assert!(TxOutStruct::<Mary>::from_term(&output(&multi_asset)).is_ok());
+        assert!(TxOutStruct::<Allegra>::from_term(&output(&multi_asset)).is_err());
+        assert!(TxOutStruct::<Alonzo>::from_term(&output(&datum_hash)).is_ok());
+        assert!(TxOutStruct::<Mary>::from_term(&output(&datum_hash)).is_err());
+        assert!(TxOutStruct::<Babbage>::from_term(&output(&map_form)).is_ok());
+        assert!(TxOutStruct::<Alonzo>::from_term(&output(&map_form)).is_err());
+    }
+}
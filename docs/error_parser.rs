@@ -4,11 +4,16 @@
 //! `rust_rule_errors.rs` can be connected to a real decoder without having to
 //! manually pattern-match every constructor.
 //!
-//! At the moment the module only lifts the CBOR message into a structured
-//! `TaggedTree`.  A follow-up step (see the TODOs below) will translate that
-//! tree into the strongly-typed enums that mirror the Haskell definitions.
+//! The module only lifts the CBOR message into a structured `TaggedTree`.
+//! Translating that tree into the strongly-typed enums that mirror the Haskell
+//! definitions is done by `failure_decoder.rs`.
+//!
+//! Terms are read by a small decoder over the input bytes rather than through
+//! `ciborium::value::Value`, so that every node can keep the byte range it was
+//! decoded from.  Errors found while translating a node are reported with an
+//! [`ErrorContext`]: the path to the node, its range and the term found there.
 
-use ciborium::{de, value::Value};
+use ciborium::de;
 use std::{fmt, io};
 
 /// Error returned while building a [`TaggedTree`].
@@ -18,6 +23,125 @@ pub enum ParseError {
     Cbor(de::Error<std::io::Error>),
     /// The structure was not a tagged sum as expected.
     Malformed(&'static str),
+    /// A tagged sum carried a constructor number that the failure family does
+    /// not define.
+    UnknownConstructor { family: &'static str, tag: u64 },
+    /// The payload type has no CBOR decoder yet.
+    Unsupported(&'static str),
+    /// An error together with where in the failure it was found.
+    Located {
+        context: Box<ErrorContext>,
+        error: Box<ParseError>,
+    },
+}
+
+/// Where an error was found while decoding a failure: the era being decoded,
+/// the path from the root of the failure, the byte range and the term found
+/// there.  The expected shape is the message of the underlying error.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorContext {
+    pub era: Option<&'static str>,
+    pub path: Vec<PathSegment>,
+    pub span: Option<Span>,
+    pub found: Option<Term>,
+}
+
+/// One step of the path from the root of a failure to an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Field `field` of constructor `tag` of `family`.
+    Field {
+        family: &'static str,
+        tag: u64,
+        field: usize,
+    },
+    /// The constructor tag of a sum of `family`.
+    Tag { family: &'static str },
+    /// Element `index` of a list.
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The era is reported separately, so `conway::` is noise here.
+        let short = |family: &'static str| family.rsplit("::").next().unwrap_or(family);
+        match self {
+            PathSegment::Field { family, tag, field } => {
+                write!(f, "{}({tag})[{field}]", short(family))
+            }
+            PathSegment::Tag { family } => write!(f, "{}.tag", short(family)),
+            PathSegment::Index(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+impl ErrorContext {
+    /// The path rendered as `LedgerPredicateFailure(0)[0].UtxoPredicateFailure.tag`.
+    pub fn path_string(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.path {
+            if !path.is_empty() && !matches!(segment, PathSegment::Index(_)) {
+                path.push('.');
+            }
+            path.push_str(&segment.to_string());
+        }
+        path
+    }
+}
+
+impl ParseError {
+    fn context_mut(self) -> (Box<ErrorContext>, Box<ParseError>) {
+        match self {
+            ParseError::Located { context, error } => (context, error),
+            error => (Box::default(), Box::new(error)),
+        }
+    }
+
+    /// Record the byte range and the term the error was found at, unless a
+    /// more specific location is already known.
+    pub fn at(self, span: Option<Span>, found: Option<&Term>) -> ParseError {
+        let (mut context, error) = self.context_mut();
+        if context.span.is_none() && context.found.is_none() {
+            context.span = span;
+            context.found = found.cloned();
+        }
+        ParseError::Located { context, error }
+    }
+
+    /// Prepend a step to the path of the error.
+    pub fn within(self, segment: PathSegment) -> ParseError {
+        let (mut context, error) = self.context_mut();
+        context.path.insert(0, segment);
+        ParseError::Located { context, error }
+    }
+
+    /// Record the era whose failures were being decoded.
+    pub fn in_era(self, era: &'static str) -> ParseError {
+        let (mut context, error) = self.context_mut();
+        context.era.get_or_insert(era);
+        ParseError::Located { context, error }
+    }
+
+    /// Where the error was found, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            ParseError::Located { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The error without its location.
+    pub fn cause(&self) -> &ParseError {
+        match self {
+            ParseError::Located { error, .. } => error.cause(),
+            error => error,
+        }
+    }
+
+    /// Byte range of the input the error was found at, if known.
+    pub fn span(&self) -> Option<Span> {
+        self.context().and_then(|context| context.span)
+    }
 }
 
 impl fmt::Display for ParseError {
@@ -25,6 +149,30 @@ impl fmt::Display for ParseError {
         match self {
             ParseError::Cbor(err) => write!(f, "CBOR decoding error: {err}"),
             ParseError::Malformed(msg) => write!(f, "malformed predicate failure: {msg}"),
+            ParseError::UnknownConstructor { family, tag } => {
+                write!(f, "unknown constructor tag {tag} for {family}")
+            }
+            ParseError::Unsupported(what) => write!(f, "decoding {what} is not supported yet"),
+            ParseError::Located { context, error } => {
+                if let Some(era) = context.era {
+                    write!(f, "{era} era: ")?;
+                }
+                if !context.path.is_empty() {
+                    write!(f, "at {}", context.path_string())?;
+                }
+                if let Some(span) = context.span {
+                    let space = if context.path.is_empty() { "" } else { " " };
+                    write!(f, "{space}({span})")?;
+                }
+                if !context.path.is_empty() || context.span.is_some() {
+                    write!(f, ": ")?;
+                }
+                write!(f, "{error}")?;
+                if let Some(found) = &context.found {
+                    write!(f, ", found {}", found.summary())?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -33,7 +181,10 @@ impl std::error::Error for ParseError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             ParseError::Cbor(err) => Some(err),
-            ParseError::Malformed(_) => None,
+            ParseError::Located { error, .. } => Some(error.as_ref()),
+            ParseError::Malformed(_)
+            | ParseError::UnknownConstructor { .. }
+            | ParseError::Unsupported(_) => None,
         }
     }
 }
@@ -63,30 +214,59 @@ pub enum Term {
 }
 
 impl Term {
-    fn from_value(value: Value) -> Term {
-        match value {
-            Value::Integer(int) => match int {
-                ciborium::value::Integer::Pos(n) => Term::Unsigned(n),
-                ciborium::value::Integer::Neg(n) => Term::Negative(n),
-            },
-            Value::Bytes(bytes) => Term::Bytes(bytes),
-            Value::Text(text) => Term::Text(text),
-            Value::Bool(b) => Term::Bool(b),
-            Value::Null => Term::Null,
-            Value::Float(f) => Term::Float(f),
-            Value::Array(items) => {
-                Term::Array(items.into_iter().map(Term::from_value).collect())
+    /// Short description of the term for error messages.
+    pub fn summary(&self) -> String {
+        match self {
+            Term::Unsigned(value) => format!("unsigned integer {value}"),
+            Term::Negative(value) => format!("negative integer {value}"),
+            Term::Bytes(bytes) if bytes.len() <= 32 => {
+                let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+                format!("byte string h'{hex}'")
             }
-            Value::Map(entries) => Term::Map(
-                entries
-                    .into_iter()
-                    .map(|(k, v)| (Term::from_value(k), Term::from_value(v)))
-                    .collect(),
-            ),
-            Value::Tag(tag, boxed) => {
-                Term::Tagged(tag, Box::new(Term::from_value(*boxed)))
+            Term::Bytes(bytes) => format!("byte string of {} bytes", bytes.len()),
+            Term::Text(text) if text.chars().count() <= 32 => format!("text {text:?}"),
+            Term::Text(text) => format!("text of {} characters", text.chars().count()),
+            Term::Bool(value) => format!("boolean {value}"),
+            Term::Float(value) => format!("float {value}"),
+            Term::Null => "null".to_owned(),
+            Term::Array(items) => match items.first() {
+                Some(Term::Unsigned(tag)) => {
+                    format!("array of {} items starting with {tag}", items.len())
+                }
+                _ => format!("array of {} items", items.len()),
+            },
+            Term::Map(entries) => format!("map of {} entries", entries.len()),
+            Term::Tagged(tag, inner) => match (SemanticTag::of(*tag), inner.as_ref()) {
+                (Some(SemanticTag::Set), Term::Array(items)) => {
+                    format!("set of {} items", items.len())
+                }
+                (Some(SemanticTag::Rational), Term::Array(items)) => match items.as_slice() {
+                    [Term::Unsigned(numerator), Term::Unsigned(denominator)] => {
+                        format!("rational {numerator}/{denominator}")
+                    }
+                    _ => format!("tag {tag} over {}", inner.summary()),
+                },
+                (Some(SemanticTag::EmbeddedCbor), Term::Bytes(bytes)) => {
+                    format!("embedded CBOR of {} bytes", bytes.len())
+                }
+                (Some(SemanticTag::PlutusConstr(index)), _) => {
+                    format!("Plutus constructor {index}")
+                }
+                _ => format!("tag {tag} over {}", inner.summary()),
+            },
+        }
+    }
+
+    /// An integer in its shortest form: a plain integer when it fits 64
+    /// bits, and a tag 2 or 3 bignum otherwise.
+    pub fn integer(value: i128) -> Term {
+        match (u64::try_from(value), u64::try_from(-1 - value)) {
+            (Ok(value), _) => Term::Unsigned(value),
+            (_, Ok(_)) => Term::Negative(value),
+            _ if value < 0 => {
+                Term::Tagged(3, Box::new(Term::Bytes(magnitude((-1 - value) as u128))))
             }
-            Value::Simple(simple) => Term::Unsigned(simple.into()),
+            _ => Term::Tagged(2, Box::new(Term::Bytes(magnitude(value as u128)))),
         }
     }
 
@@ -96,6 +276,328 @@ impl Term {
             _ => None,
         }
     }
+
+    /// Serialise the term the way the Haskell encoders do: integers and
+    /// lengths use their shortest form, and arrays and maps with more than 23
+    /// entries switch to the indefinite-length encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Term::Unsigned(value) => write_head(out, 0, *value),
+            Term::Negative(value) => match u64::try_from(-1 - *value) {
+                Ok(argument) => write_head(out, 1, argument),
+                Err(_) => Term::integer(*value).write(out),
+            },
+            Term::Bytes(bytes) => {
+                write_head(out, 2, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            }
+            Term::Text(text) => {
+                write_head(out, 3, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Term::Array(items) => {
+                write_length(out, 4, items.len());
+                items.iter().for_each(|item| item.write(out));
+                write_break(out, items.len());
+            }
+            Term::Map(entries) => {
+                write_length(out, 5, entries.len());
+                for (key, value) in entries {
+                    key.write(out);
+                    value.write(out);
+                }
+                write_break(out, entries.len());
+            }
+            Term::Tagged(tag, inner) => {
+                write_head(out, 6, *tag);
+                inner.write(out);
+            }
+            Term::Bool(false) => out.push(0xf4),
+            Term::Bool(true) => out.push(0xf5),
+            Term::Null => out.push(0xf6),
+            Term::Float(value) => {
+                out.push(0xfb);
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+}
+
+/// Containers longer than this are encoded with an indefinite length.
+const DEFINITE_LENGTH_THRESHOLD: usize = 23;
+
+pub(crate) fn write_head(out: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => out.push(major | value as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, value as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+fn write_length(out: &mut Vec<u8>, major: u8, len: usize) {
+    if len > DEFINITE_LENGTH_THRESHOLD {
+        out.push((major << 5) | 31);
+    } else {
+        write_head(out, major, len as u64);
+    }
+}
+
+fn write_break(out: &mut Vec<u8>, len: usize) {
+    if len > DEFINITE_LENGTH_THRESHOLD {
+        out.push(0xff);
+    }
+}
+
+/// Byte range `start..end` of a term in the decoded input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bytes {:#x}..{:#x}", self.start, self.end)
+    }
+}
+
+/// Byte ranges of a decoded [`Term`] and of the terms nested in it.
+///
+/// `children` follows the nesting of the term: the elements of an array,
+/// the keys and values of a map in alternation, and the single content of a
+/// tagged term.  Bignums that fit an integer term are folded into it and have
+/// no children.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SpanTree {
+    pub span: Span,
+    pub children: Vec<SpanTree>,
+}
+
+impl SpanTree {
+    pub fn child(&self, index: usize) -> Option<&SpanTree> {
+        self.children.get(index)
+    }
+}
+
+/// Nesting depth at which decoding gives up, as `ciborium` does.
+const RECURSION_LIMIT: usize = 256;
+
+/// CBOR decoder over an in-memory message that records where each term
+/// starts and ends.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ParseError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| de::Error::Io(io::ErrorKind::UnexpectedEof.into()))?;
+        let taken = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(taken)
+    }
+
+    fn peek_break(&self) -> bool {
+        self.bytes.get(self.offset) == Some(&0xff)
+    }
+
+    /// Read an initial byte and its argument; `None` is an indefinite length.
+    fn head(&mut self) -> Result<(u8, u8, Option<u64>), ParseError> {
+        let offset = self.offset;
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let argument = match info {
+            0..=23 => Some(u64::from(info)),
+            24..=27 => {
+                let len = 1 << (info - 24);
+                let mut value = [0u8; 8];
+                value[8 - len..].copy_from_slice(self.take(len)?);
+                Some(u64::from_be_bytes(value))
+            }
+            31 if matches!(major, 2..=5 | 7) => None,
+            _ => return Err(de::Error::Syntax(offset).into()),
+        };
+        Ok((major, info, argument))
+    }
+
+    fn length(&self, argument: u64) -> Result<usize, ParseError> {
+        usize::try_from(argument).map_err(|_| de::Error::Syntax(self.offset).into())
+    }
+
+    fn term(&mut self, depth: usize) -> Result<(Term, SpanTree), ParseError> {
+        if depth == RECURSION_LIMIT {
+            return Err(de::Error::RecursionLimitExceeded.into());
+        }
+        let start = self.offset;
+        let mut children = Vec::new();
+        let term = match self.head()? {
+            (0, _, Some(value)) => Term::Unsigned(value),
+            (1, _, Some(value)) => Term::Negative(-1 - i128::from(value)),
+            (major @ (2 | 3), _, len) => {
+                let bytes = self.string(major, len)?;
+                if major == 2 {
+                    Term::Bytes(bytes)
+                } else {
+                    let text = String::from_utf8(bytes)
+                        .map_err(|_| de::Error::Semantic(Some(start), "invalid UTF-8".into()))?;
+                    Term::Text(text)
+                }
+            }
+            (4, _, len) => {
+                let mut items = Vec::new();
+                while self.more(&mut items, len)? {
+                    let (item, spans) = self.term(depth + 1)?;
+                    items.push(item);
+                    children.push(spans);
+                }
+                Term::Array(items)
+            }
+            (5, _, len) => {
+                let mut entries = Vec::new();
+                while self.more(&mut entries, len)? {
+                    let (key, key_spans) = self.term(depth + 1)?;
+                    let (value, value_spans) = self.term(depth + 1)?;
+                    entries.push((key, value));
+                    children.extend([key_spans, value_spans]);
+                }
+                Term::Map(entries)
+            }
+            (6, _, Some(tag)) => {
+                let (inner, spans) = self.term(depth + 1)?;
+                children.push(spans);
+                Term::Tagged(tag, Box::new(inner))
+            }
+            (7, 20, _) => Term::Bool(false),
+            (7, 21, _) => Term::Bool(true),
+            (7, 22 | 23, _) => Term::Null,
+            (7, 25, Some(bits)) => Term::Float(half_to_f64(bits as u16)),
+            (7, 26, Some(bits)) => Term::Float(f64::from(f32::from_bits(bits as u32))),
+            (7, 27, Some(bits)) => Term::Float(f64::from_bits(bits)),
+            _ => return Err(de::Error::Syntax(start).into()),
+        };
+        let span = Span {
+            start,
+            end: self.offset,
+        };
+        Ok((term, SpanTree { span, children }))
+    }
+
+    /// Whether a container with `len` entries (or an indefinite length) has
+    /// another entry, consuming the closing break.
+    fn more<T>(&mut self, items: &mut Vec<T>, len: Option<u64>) -> Result<bool, ParseError> {
+        match len {
+            Some(len) => {
+                if items.is_empty() {
+                    items.reserve(self.length(len)?.min(self.bytes.len()));
+                }
+                Ok((items.len() as u64) < len)
+            }
+            None if self.peek_break() => {
+                self.offset += 1;
+                Ok(false)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// The contents of a byte or text string, joining indefinite-length
+    /// chunks.
+    fn string(&mut self, major: u8, len: Option<u64>) -> Result<Vec<u8>, ParseError> {
+        if let Some(len) = len {
+            let len = self.length(len)?;
+            return self.take(len).map(<[u8]>::to_vec);
+        }
+        let mut contents = Vec::new();
+        while !self.peek_break() {
+            let offset = self.offset;
+            match self.head()? {
+                (chunk_major, _, Some(len)) if chunk_major == major => {
+                    let len = self.length(len)?;
+                    contents.extend_from_slice(self.take(len)?);
+                }
+                _ => return Err(de::Error::Syntax(offset).into()),
+            }
+        }
+        self.offset += 1;
+        Ok(contents)
+    }
+}
+
+/// Big-endian bytes of a bignum magnitude, without leading zeros.
+pub(crate) fn magnitude(value: u128) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    bytes[zeros..].to_vec()
+}
+
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let fraction = f64::from(bits & 0x3ff);
+    sign * match exponent {
+        0 => fraction * 2f64.powi(-24),
+        31 if fraction == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+/// CBOR tags that ledger encodings use to mark values rather than sum
+/// constructors.  A tagged array carrying one of them is data, never a sum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTag {
+    /// Tag 2: unsigned bignum.
+    PositiveBignum,
+    /// Tag 3: negative bignum.
+    NegativeBignum,
+    /// Tag 24: CBOR embedded in a byte string (inline datums, scripts).
+    EmbeddedCbor,
+    /// Tag 30: rational number `[numerator, denominator]`.
+    Rational,
+    /// Tag 258: set, used by Conway-era encodings.
+    Set,
+    /// Tags 121–127 and 1280–1400: Plutus `Constr` with the given index.
+    PlutusConstr(u64),
+    /// Tag 102: Plutus `Constr` as `[index, fields]`.
+    PlutusGeneralConstr,
+}
+
+impl SemanticTag {
+    pub fn of(tag: u64) -> Option<SemanticTag> {
+        Some(match tag {
+            2 => SemanticTag::PositiveBignum,
+            3 => SemanticTag::NegativeBignum,
+            24 => SemanticTag::EmbeddedCbor,
+            30 => SemanticTag::Rational,
+            258 => SemanticTag::Set,
+            121..=127 => SemanticTag::PlutusConstr(tag - 121),
+            1280..=1400 => SemanticTag::PlutusConstr(tag - 1280 + 7),
+            102 => SemanticTag::PlutusGeneralConstr,
+            _ => return None,
+        })
+    }
 }
 
 /// Representation of a CBOR-encoded sum value.
@@ -103,25 +605,49 @@ impl Term {
 pub struct TaggedSum {
     pub tag: u64,
     pub fields: Vec<Term>,
+    /// Where the sum was decoded from, when built from the input bytes.
+    pub span: Option<Span>,
 }
 
 impl TaggedSum {
-    fn from_term(term: Term) -> Option<TaggedSum> {
+    /// Split a sum into its constructor tag and fields, together with the
+    /// span trees of the fields.
+    fn from_term(
+        term: Term,
+        spans: Option<&SpanTree>,
+    ) -> Option<(TaggedSum, Vec<Option<&SpanTree>>)> {
+        let span = spans.map(|spans| spans.span);
         match term {
             Term::Array(mut elements) if !elements.is_empty() => {
                 let tag_term = elements.remove(0);
                 let tag = tag_term.as_unsigned()?;
-                Some(TaggedSum {
-                    tag,
-                    fields: elements,
-                })
-            }
-            Term::Tagged(tag, boxed) => {
-                if let Term::Array(mut elements) = *boxed {
-                    Some(TaggedSum {
+                let field_spans = (1..=elements.len())
+                    .map(|index| spans.and_then(|spans| spans.child(index)))
+                    .collect();
+                Some((
+                    TaggedSum {
                         tag,
                         fields: elements,
-                    })
+                        span,
+                    },
+                    field_spans,
+                ))
+            }
+            Term::Tagged(tag, _) if SemanticTag::of(tag).is_some() => None,
+            Term::Tagged(tag, boxed) => {
+                if let Term::Array(elements) = *boxed {
+                    let array = spans.and_then(|spans| spans.child(0));
+                    let field_spans = (0..elements.len())
+                        .map(|index| array.and_then(|array| array.child(index)))
+                        .collect();
+                    Some((
+                        TaggedSum {
+                            tag,
+                            fields: elements,
+                            span,
+                        },
+                        field_spans,
+                    ))
                 } else {
                     None
                 }
@@ -133,61 +659,126 @@ impl TaggedSum {
 
 /// Tree structure that mirrors the nesting of predicate failures.  Each node
 /// is either a tagged sum (which corresponds to one constructor of a predicate
-/// failure) or a leaf containing an arbitrary CBOR term.
+/// failure) or a leaf containing an arbitrary CBOR term.  Terms under a
+/// [`SemanticTag`] are always leaves.
+///
+/// The shape of a term alone does not tell a sum apart from plain data: a
+/// `TxIn` `[txid, 0]` or an `ExUnits` `[mem, steps]` look just like a
+/// constructor.  A node is therefore only read as a sum where the expected
+/// type is a sum type.  The tree is built lazily: the root is read as a sum,
+/// its fields are left as leaves, and the decoder of a constructor, which
+/// knows the type of each field, expands the fields that are nested failures
+/// with [`TaggedTree::as_sum`].  Leaves are the untyped view of positions
+/// whose type is not known.
+///
+/// Trees built from the input bytes record the span of every node; leaves
+/// keep the span tree of their term so that nested failures decoded from a
+/// leaf later on can be located too.
 #[derive(Debug, Clone, PartialEq)]
 pub enum TaggedTree {
     Sum(TaggedSum, Vec<TaggedTree>),
-    Leaf(Term),
+    Leaf(Term, Option<SpanTree>),
 }
 
 impl TaggedTree {
-    fn from_term(term: Term) -> TaggedTree {
-        if let Some(sum) = TaggedSum::from_term(term.clone()) {
-            let children = sum
-                .fields
-                .iter()
-                .cloned()
-                .map(TaggedTree::from_term)
-                .collect();
-            TaggedTree::Sum(sum, children)
-        } else {
-            TaggedTree::Leaf(term)
+    pub(crate) fn from_term(term: Term) -> TaggedTree {
+        TaggedTree::from_spanned_term(term, None)
+    }
+
+    /// Read a term whose expected type is a sum type.  Its fields are kept as
+    /// untyped leaves.
+    pub(crate) fn from_spanned_term(term: Term, spans: Option<&SpanTree>) -> TaggedTree {
+        match TaggedSum::from_term(term.clone(), spans) {
+            Some((sum, field_spans)) => {
+                let children = sum
+                    .fields
+                    .iter()
+                    .cloned()
+                    .zip(field_spans)
+                    .map(|(field, spans)| TaggedTree::Leaf(field, spans.cloned()))
+                    .collect();
+                TaggedTree::Sum(sum, children)
+            }
+            None => TaggedTree::Leaf(term, spans.cloned()),
+        }
+    }
+
+    /// Re-read an untyped leaf at a position whose expected type turns out to
+    /// be a sum type.  Sums are returned as they are.
+    pub fn as_sum(&self) -> TaggedTree {
+        match self {
+            TaggedTree::Sum(..) => self.clone(),
+            TaggedTree::Leaf(term, spans) => {
+                TaggedTree::from_spanned_term(term.clone(), spans.as_ref())
+            }
+        }
+    }
+
+    /// Where the node was decoded from, when built from the input bytes.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            TaggedTree::Sum(sum, _) => sum.span,
+            TaggedTree::Leaf(_, spans) => spans.as_ref().map(|spans| spans.span),
         }
     }
 }
 
+/// Decode a CBOR message into a raw [`Term`] without interpreting its shape.
+///
+/// # Errors
+///
+/// Returns [`ParseError::Cbor`] if the raw bytes are not valid CBOR.
+pub fn decode_term<R: io::Read>(mut reader: R) -> Result<Term, ParseError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).map_err(de::Error::Io)?;
+    decode_spanned_term(&bytes).map(|(term, _)| term)
+}
+
+/// Decode a CBOR message into a [`Term`] together with the byte range of
+/// every term in it.  Bytes after the first term are ignored.
+///
+/// # Errors
+///
+/// Returns [`ParseError::Cbor`] if the raw bytes are not valid CBOR.
+pub fn decode_spanned_term(bytes: &[u8]) -> Result<(Term, SpanTree), ParseError> {
+    Reader { bytes, offset: 0 }.term(0)
+}
+
 /// Decode a CBOR message and build a [`TaggedTree`] describing its structure.
 ///
 /// # Errors
 ///
 /// Returns [`ParseError::Malformed`] if the input cannot be interpreted as a
 /// tagged sum and [`ParseError::Cbor`] if the raw bytes are not valid CBOR.
-pub fn decode_predicate_failure<R: io::Read>(reader: R) -> Result<TaggedTree, ParseError> {
-    let value: Value = de::from_reader(reader)?;
-    let root = Term::from_value(value);
-    if TaggedSum::from_term(root.clone()).is_none() {
-        return Err(ParseError::Malformed("expected a tagged sum"));
-    }
-    Ok(TaggedTree::from_term(root))
+pub fn decode_predicate_failure<R: io::Read>(mut reader: R) -> Result<TaggedTree, ParseError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).map_err(de::Error::Io)?;
+    decode_predicate_failure_bytes(&bytes)
 }
 
 /// Convenience helper for decoding from an in-memory slice.
 pub fn decode_predicate_failure_bytes(bytes: &[u8]) -> Result<TaggedTree, ParseError> {
-    decode_predicate_failure(bytes)
+    let (root, spans) = decode_spanned_term(bytes)?;
+    match TaggedTree::from_spanned_term(root, Some(&spans)) {
+        tree @ TaggedTree::Sum(..) => Ok(tree),
+        TaggedTree::Leaf(root, _) => {
+            Err(ParseError::Malformed("expected a tagged sum").at(Some(spans.span), Some(&root)))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ciborium::ser::into_writer;
+    use ciborium::{ser::into_writer, value::Value};
 
     #[test]
     fn round_trip_simple_sum() {
         let mut buffer = Vec::new();
         // Encode a dummy predicate failure represented as [5, [1], "payload"].
         let message = Value::Array(vec![
-            Value::Integer(ciborium::value::Integer::Pos(5)),
-            Value::Array(vec![Value::Integer(ciborium::value::Integer::Pos(1))]),
+            Value::Integer(5.into()),
+            Value::Array(vec![Value::Integer(1.into())]),
             Value::Text("payload".into()),
         ]);
         into_writer(&message, &mut buffer).expect("encode test value");
@@ -198,13 +789,72 @@ mod tests {
                 assert_eq!(sum.tag, 5);
                 assert_eq!(children.len(), 2);
             }
-            TaggedTree::Leaf(_) => panic!("expected a sum"),
+            TaggedTree::Leaf(..) => panic!("expected a sum"),
         }
     }
-}
 
-// TODO: Implement translation from `TaggedTree` into the typed enums in
-// `rust_rule_errors.rs` once the serde-based decoders are derived for the helper
-// structures.  The intent is to walk the tree, look up the constructor metadata,
-// and feed each node into `ciborium::de::from_reader` to produce the concrete
-// Rust representation.
+    #[test]
+    fn records_byte_spans() {
+        // [5, [1], "payload"]
+        let buffer = [
+            0x83, 0x05, 0x81, 0x01, 0x67, b'p', b'a', b'y', b'l', b'o', b'a', b'd',
+        ];
+        let tree = decode_predicate_failure_bytes(&buffer).expect("parse tree");
+        assert_eq!(tree.span(), Some(Span { start: 0, end: 12 }));
+        match tree {
+            TaggedTree::Sum(_, children) => {
+                assert_eq!(children[0].span(), Some(Span { start: 2, end: 4 }));
+                assert_eq!(children[1].span(), Some(Span { start: 4, end: 12 }));
+            }
+            TaggedTree::Leaf(..) => panic!("expected a sum"),
+        }
+
+        let err = decode_predicate_failure_bytes(&[0x82, 0x01]).unwrap_err();
+        assert!(matches!(err, ParseError::Cbor(de::Error::Io(_))));
+        let err =
+            decode_predicate_failure_bytes(&[0x65, b'h', b'e', b'l', b'l', b'o']).unwrap_err();
+        assert_eq!(err.span(), Some(Span { start: 0, end: 6 }));
+    }
+
+    #[test]
+    fn semantic_tags_are_not_sums() {
+        // [1, 258([[h'00', 0]]), 30([1, 2]), 121([])]
+        let buffer = [
+            0x84, 0x01, 0xd9, 0x01, 0x02, 0x81, 0x82, 0x41, 0x00, 0x00, 0xd8, 0x1e, 0x82, 0x01,
+            0x02, 0xd8, 0x79, 0x80,
+        ];
+        let tree = decode_predicate_failure_bytes(&buffer).expect("parse tree");
+        let TaggedTree::Sum(sum, children) = tree else {
+            panic!("expected a sum");
+        };
+        assert_eq!(sum.tag, 1);
+        assert!(children
+            .iter()
+            .all(|child| matches!(child, TaggedTree::Leaf(..))));
+        assert_eq!(sum.fields[0].summary(), "set of 1 items");
+        assert_eq!(sum.fields[1].summary(), "rational 1/2");
+        assert_eq!(sum.fields[2].summary(), "Plutus constructor 0");
+    }
+
+    #[test]
+    fn fields_stay_untyped_until_expanded() {
+        // [0, [h'00', 0], [1, [2]]]: a TxIn-like pair and a nested sum.
+        let buffer = [0x83, 0x00, 0x82, 0x41, 0x00, 0x00, 0x82, 0x01, 0x81, 0x02];
+        let tree = decode_predicate_failure_bytes(&buffer).expect("parse tree");
+        let TaggedTree::Sum(_, children) = tree else {
+            panic!("expected a sum");
+        };
+        assert!(matches!(children[0], TaggedTree::Leaf(Term::Array(_), _)));
+        assert!(matches!(children[1], TaggedTree::Leaf(..)));
+
+        let TaggedTree::Sum(nested, grandchildren) = children[1].as_sum() else {
+            panic!("expected a nested sum");
+        };
+        assert_eq!(nested.tag, 1);
+        assert_eq!(nested.span, Some(Span { start: 6, end: 10 }));
+        assert!(matches!(
+            grandchildren[0],
+            TaggedTree::Leaf(Term::Array(_), _)
+        ));
+    }
+}
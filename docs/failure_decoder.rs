@@ -0,0 +1,2908 @@
+//! Translation of a [`TaggedTree`] into the strongly-typed predicate failure
+//! enums defined in `rust_rule_errors.rs`.
+//!
+//! Every constructor is selected by the `/// Tag: N` number documented on the
+//! corresponding Rust variant.  Nested predicate failures are read from the
+//! child trees built by `error_parser.rs`, while payload fields (coins, hashes,
+//! sets, ...) are decoded directly from their CBOR terms.
+//!
+//! The Haskell encoders are not uniform in how they lay out a `Mismatch`: some
+//! constructors nest it as a `[supplied, expected]` record (`To mm`), others
+//! flatten it into the constructor (`ToGroup mm`), and a few flatten it with
+//! the two values swapped (`ToGroup (swapMismatch mm)`).  The decoders below
+//! follow the encoder of each constructor individually.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::era::*;
+use crate::error_parser::{
+    decode_predicate_failure_bytes, decode_term, ParseError, PathSegment, SemanticTag, TaggedTree,
+    Term,
+};
+use crate::rust_rule_errors::*;
+
+/// Decoding of a payload value from a single CBOR term.
+pub trait FromTerm: Sized {
+    fn from_term(term: &Term) -> Result<Self, ParseError>;
+}
+
+/// Decoding of a predicate failure from a node of a [`TaggedTree`].
+pub trait FromTaggedTree: Sized {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError>;
+}
+
+/// Decode a CBOR-encoded predicate failure straight into its typed enum.
+///
+/// # Errors
+///
+/// Propagates the errors of [`decode_predicate_failure_bytes`] and reports
+/// constructors or payloads that do not match the expected failure type.
+pub fn decode_failure_bytes<T: FromTaggedTree>(bytes: &[u8]) -> Result<T, ParseError> {
+    let tree = decode_predicate_failure_bytes(bytes)?;
+    T::from_tagged_tree(&tree)
+}
+
+// ---------------------------------------------------------------------------
+// Constructor field cursor
+// ---------------------------------------------------------------------------
+
+/// Sequential reader over the fields of one tagged-sum constructor.
+///
+/// Errors in a field, or in a nested failure, get the field added to their
+/// path, and the field's term and span if they have no location yet.
+struct Fields<'a> {
+    family: &'static str,
+    tag: u64,
+    terms: &'a [Term],
+    children: &'a [TaggedTree],
+    position: usize,
+}
+
+impl<'a> Fields<'a> {
+    /// Open a predicate-failure node of the tree.
+    fn of_tree(tree: &'a TaggedTree, family: &'static str) -> Result<Self, ParseError> {
+        match tree {
+            TaggedTree::Sum(sum, children) => Ok(Fields {
+                family,
+                tag: sum.tag,
+                terms: &sum.fields,
+                children,
+                position: 0,
+            }),
+            TaggedTree::Leaf(..) => Err(ParseError::Malformed("expected a tagged sum")),
+        }
+    }
+
+    /// Open a `[tag, field, ...]` payload that is not itself a failure.
+    fn of_term(term: &'a Term, family: &'static str) -> Result<Self, ParseError> {
+        let (tag, terms) = match array(term)?.split_first() {
+            Some((tag, terms)) => {
+                let tag = u64::from_term(tag)
+                    .map_err(|err| err.at(None, Some(tag)).within(PathSegment::Tag { family }))?;
+                (tag, terms)
+            }
+            None => return Err(ParseError::Malformed("expected a tagged sum")),
+        };
+        Ok(Fields {
+            family,
+            tag,
+            terms,
+            children: &[],
+            position: 0,
+        })
+    }
+
+    fn next_term(&mut self) -> Result<&'a Term, ParseError> {
+        let term = self
+            .terms
+            .get(self.position)
+            .ok_or(ParseError::Malformed("missing constructor field"))?;
+        self.position += 1;
+        Ok(term)
+    }
+
+    /// Locate an error in the field just read.
+    fn located<T>(&self, result: Result<T, ParseError>) -> Result<T, ParseError> {
+        let field = self.position - 1;
+        result.map_err(|err| {
+            let span = self.children.get(field).and_then(TaggedTree::span);
+            err.at(span, self.terms.get(field))
+                .within(PathSegment::Field {
+                    family: self.family,
+                    tag: self.tag,
+                    field,
+                })
+        })
+    }
+
+    fn field<T: FromTerm>(&mut self) -> Result<T, ParseError> {
+        let term = self.next_term()?;
+        self.located(T::from_term(term))
+    }
+
+    /// A field that is `null` when absent (`encodeNullStrictMaybe`).
+    fn nullable<T: FromTerm>(&mut self) -> Result<Option<T>, ParseError> {
+        let term = self.next_term()?;
+        self.located(nullable(term))
+    }
+
+    /// A `Mismatch` encoded as a nested `[supplied, expected]` record.
+    fn mismatch<R: Relation, T: FromTerm>(&mut self) -> Result<Mismatch<R, T>, ParseError> {
+        let term = self.next_term()?;
+        let (supplied, expected) = self.located(<(T, T)>::from_term(term))?;
+        Ok(Mismatch::new(supplied, expected))
+    }
+
+    /// A `Mismatch` flattened into the constructor as `supplied, expected`.
+    fn grouped_mismatch<R: Relation, T: FromTerm>(&mut self) -> Result<Mismatch<R, T>, ParseError> {
+        let supplied = self.field()?;
+        let expected = self.field()?;
+        Ok(Mismatch::new(supplied, expected))
+    }
+
+    /// A `Mismatch` flattened into the constructor as `expected, supplied`.
+    fn swapped_mismatch<R: Relation, T: FromTerm>(&mut self) -> Result<Mismatch<R, T>, ParseError> {
+        let expected = self.field()?;
+        let supplied = self.field()?;
+        Ok(Mismatch::new(supplied, expected))
+    }
+
+    /// A nested predicate failure.
+    fn failure<T: FromTaggedTree>(&mut self) -> Result<T, ParseError> {
+        let index = self.position;
+        self.next_term()?;
+        let child = self
+            .children
+            .get(index)
+            .ok_or(ParseError::Malformed("missing nested predicate failure"))?
+            .as_sum();
+        self.located(T::from_tagged_tree(&child))
+    }
+
+    /// A list of nested predicate failures.
+    fn failures<T: FromTaggedTree>(&mut self) -> Result<Vec<T>, ParseError> {
+        let spans = match self.children.get(self.position) {
+            Some(TaggedTree::Leaf(_, spans)) => spans.as_ref(),
+            _ => None,
+        };
+        let items = self.next_term()?;
+        let failures = array(items).and_then(|items| {
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    let spans = spans.and_then(|spans| spans.child(index));
+                    let tree = TaggedTree::from_spanned_term(item.clone(), spans);
+                    T::from_tagged_tree(&tree).map_err(|err| {
+                        err.at(tree.span(), Some(item))
+                            .within(PathSegment::Index(index))
+                    })
+                })
+                .collect()
+        });
+        self.located(failures)
+    }
+
+    /// A field holding CBOR embedded in a byte string under tag 24, such as
+    /// an inline datum.
+    fn embedded<T: FromTerm>(&mut self) -> Result<T, ParseError> {
+        let term = self.next_term()?;
+        self.located(embedded(term))
+    }
+
+    fn unknown<T>(&self) -> Result<T, ParseError> {
+        Err(ParseError::UnknownConstructor {
+            family: self.family,
+            tag: self.tag,
+        }
+        .within(PathSegment::Tag {
+            family: self.family,
+        }))
+    }
+
+    /// Check that every field has been consumed and return the decoded value.
+    fn finish<T>(self, value: T) -> Result<T, ParseError> {
+        if self.position == self.terms.len() {
+            Ok(value)
+        } else {
+            Err(ParseError::Malformed(
+                "unexpected trailing constructor fields",
+            ))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Primitive and container payloads
+// ---------------------------------------------------------------------------
+
+fn array(term: &Term) -> Result<&[Term], ParseError> {
+    match term {
+        Term::Array(items) => Ok(items),
+        _ => Err(ParseError::Malformed("expected an array")),
+    }
+}
+
+fn array_of_len(term: &Term, len: usize) -> Result<&[Term], ParseError> {
+    let items = array(term)?;
+    if items.len() == len {
+        Ok(items)
+    } else {
+        Err(ParseError::Malformed("unexpected array length"))
+    }
+}
+
+fn bytes(term: &Term) -> Result<&[u8], ParseError> {
+    match term {
+        Term::Bytes(bytes) => Ok(bytes),
+        _ => Err(ParseError::Malformed("expected a byte string")),
+    }
+}
+
+fn fixed_bytes<const N: usize>(term: &Term) -> Result<[u8; N], ParseError> {
+    bytes(term)?
+        .try_into()
+        .map_err(|_| ParseError::Malformed("unexpected byte string length"))
+}
+
+fn embedded<T: FromTerm>(term: &Term) -> Result<T, ParseError> {
+    match term {
+        Term::Tagged(24, inner) => T::from_term(&decode_term(bytes(inner)?)?),
+        _ => Err(ParseError::Malformed("expected embedded CBOR")),
+    }
+}
+
+fn nullable<T: FromTerm>(term: &Term) -> Result<Option<T>, ParseError> {
+    match term {
+        Term::Null => Ok(None),
+        other => T::from_term(other).map(Some),
+    }
+}
+
+impl FromTerm for u64 {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Unsigned(value) => Ok(*value),
+            _ => Err(ParseError::Malformed("expected an unsigned integer")),
+        }
+    }
+}
+
+macro_rules! narrow_unsigned {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl FromTerm for $ty {
+                fn from_term(term: &Term) -> Result<Self, ParseError> {
+                    <$ty>::try_from(u64::from_term(term)?)
+                        .map_err(|_| ParseError::Malformed("unsigned integer out of range"))
+                }
+            }
+        )*
+    };
+}
+
+narrow_unsigned!(u16, u32, usize);
+
+impl FromTerm for i64 {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let value = match term {
+            Term::Unsigned(value) => i128::from(*value),
+            Term::Negative(value) => *value,
+            _ => return Err(ParseError::Malformed("expected an integer")),
+        };
+        i64::try_from(value).map_err(|_| ParseError::Malformed("integer out of range"))
+    }
+}
+
+/// Integers beyond 64 bits arrive as tag 2 or 3 bignums.
+impl FromTerm for i128 {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Unsigned(value) => Ok(i128::from(*value)),
+            Term::Negative(value) => Ok(*value),
+            Term::Tagged(tag @ (2 | 3), inner) => {
+                let bytes = bytes(inner)?;
+                let out_of_range = || ParseError::Malformed("integer out of range");
+                if bytes.len() > 16 {
+                    return Err(out_of_range());
+                }
+                let mut value = [0u8; 16];
+                value[16 - bytes.len()..].copy_from_slice(bytes);
+                let raw = i128::try_from(u128::from_be_bytes(value)).map_err(|_| out_of_range())?;
+                Ok(if *tag == 2 { raw } else { -1 - raw })
+            }
+            _ => Err(ParseError::Malformed("expected an integer")),
+        }
+    }
+}
+
+impl FromTerm for i32 {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        i32::try_from(i64::from_term(term)?)
+            .map_err(|_| ParseError::Malformed("integer out of range"))
+    }
+}
+
+impl FromTerm for bool {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Bool(value) => Ok(*value),
+            _ => Err(ParseError::Malformed("expected a boolean")),
+        }
+    }
+}
+
+impl FromTerm for String {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Text(text) => Ok(text.clone()),
+            _ => Err(ParseError::Malformed("expected a text string")),
+        }
+    }
+}
+
+impl FromTerm for Vec<u8> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        bytes(term).map(<[u8]>::to_vec)
+    }
+}
+
+impl<const N: usize> FromTerm for [u8; N] {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        fixed_bytes(term)
+    }
+}
+
+/// `StrictMaybe` is encoded as a list of zero or one element.
+impl<T: FromTerm> FromTerm for Option<T> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match array(term)? {
+            [] => Ok(None),
+            [value] => T::from_term(value).map(Some),
+            _ => Err(ParseError::Malformed(
+                "expected a list of at most one element",
+            )),
+        }
+    }
+}
+
+impl<T: FromTerm> FromTerm for Box<T> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        T::from_term(term).map(Box::new)
+    }
+}
+
+impl<T: FromTaggedTree> FromTaggedTree for Box<T> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        T::from_tagged_tree(tree).map(Box::new)
+    }
+}
+
+impl<T: FromTerm> FromTerm for Vec<T> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        array(term)?.iter().map(T::from_term).collect()
+    }
+}
+
+/// Sets may carry the optional set tag 258 introduced with Conway.
+impl<T: FromTerm + Ord> FromTerm for BTreeSet<T> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let items = match term {
+            Term::Tagged(258, inner) => array(inner)?,
+            other => array(other)?,
+        };
+        items.iter().map(T::from_term).collect()
+    }
+}
+
+/// Non-empty sets and lists, with or without tag 258; an empty one is
+/// rejected rather than leaving a failure without offenders.
+impl<T: FromTerm> FromTerm for NonEmpty<T> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let items = match term {
+            Term::Tagged(258, inner) => array(inner)?,
+            other => array(other)?,
+        };
+        let items = items.iter().map(T::from_term).collect::<Result<_, _>>()?;
+        NonEmpty::new(items).ok_or(ParseError::Malformed("expected a non-empty list"))
+    }
+}
+
+/// Ordered sets keep their wire order, with or without tag 258.
+impl<T: FromTerm> FromTerm for OSet<T> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let items = match term {
+            Term::Tagged(258, inner) => array(inner)?,
+            other => array(other)?,
+        };
+        items
+            .iter()
+            .map(T::from_term)
+            .collect::<Result<_, _>>()
+            .map(OSet)
+    }
+}
+
+impl<K: FromTerm + Ord, V: FromTerm> FromTerm for BTreeMap<K, V> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| Ok((K::from_term(key)?, V::from_term(value)?)))
+                .collect(),
+            _ => Err(ParseError::Malformed("expected a map")),
+        }
+    }
+}
+
+impl<A: FromTerm, B: FromTerm> FromTerm for (A, B) {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match array_of_len(term, 2)? {
+            [a, b] => Ok((A::from_term(a)?, B::from_term(b)?)),
+            _ => unreachable!("length checked above"),
+        }
+    }
+}
+
+impl<A: FromTerm, B: FromTerm, C: FromTerm> FromTerm for (A, B, C) {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match array_of_len(term, 3)? {
+            [a, b, c] => Ok((A::from_term(a)?, B::from_term(b)?, C::from_term(c)?)),
+            _ => unreachable!("length checked above"),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helper stand-ins
+// ---------------------------------------------------------------------------
+
+macro_rules! newtype_from_term {
+    ($($ty:ident($inner:ty)),* $(,)?) => {
+        $(
+            impl FromTerm for $ty {
+                fn from_term(term: &Term) -> Result<Self, ParseError> {
+                    <$inner>::from_term(term).map($ty)
+                }
+            }
+        )*
+    };
+}
+
+newtype_from_term!(
+    Coin(u64),
+    DeltaCoin(i64),
+    SlotNo(u64),
+    EpochNo(u64),
+    TxIx(u16),
+    EpochInterval(u32),
+    GovActionIx(u16),
+    Address(Vec<u8>),
+    RewardAccount(Vec<u8>),
+    ScriptHash(Hash28),
+    DataHash(Hash32),
+    ScriptIntegrityHash(Hash32),
+    TxAuxDataHash(Hash32),
+    TxId(Hash32),
+    AddrKeyHash(Hash28),
+    KeyHash(Hash28),
+    PoolKeyHash(Hash28),
+    VRFKeyHash(Hash32),
+    Withdrawals(BTreeMap<RewardAccount, Coin>),
+    AssetName(Vec<u8>),
+    MultiAsset(BTreeMap<PolicyId, BTreeMap<AssetName, i128>>),
+);
+
+impl FromTerm for Hash28 {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        fixed_bytes(term).map(Hash28)
+    }
+}
+
+impl FromTerm for Hash32 {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        fixed_bytes(term).map(Hash32)
+    }
+}
+
+impl FromTerm for VerificationKey {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        fixed_bytes(term).map(VerificationKey)
+    }
+}
+
+impl FromTerm for NetworkId {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match u64::from_term(term)? {
+            id @ (0 | 1) => Ok(NetworkId(id as u8)),
+            _ => Err(ParseError::Malformed("unknown network id")),
+        }
+    }
+}
+
+impl FromTerm for TxIn {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (transaction_id, index) = FromTerm::from_term(term)?;
+        Ok(TxIn {
+            transaction_id,
+            index,
+        })
+    }
+}
+
+impl FromTerm for ProtVer {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (major, minor) = FromTerm::from_term(term)?;
+        Ok(ProtVer { major, minor })
+    }
+}
+
+impl FromTerm for ExUnits {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (mem, steps) = FromTerm::from_term(term)?;
+        Ok(ExUnits { mem, steps })
+    }
+}
+
+impl FromTerm for ValidityInterval {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (invalid_before, invalid_hereafter) = FromTerm::from_term(term)?;
+        Ok(ValidityInterval {
+            invalid_before,
+            invalid_hereafter,
+        })
+    }
+}
+
+impl FromTerm for MIRPot {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match u64::from_term(term)? {
+            0 => Ok(MIRPot::Reserves),
+            1 => Ok(MIRPot::Treasury),
+            _ => Err(ParseError::Malformed("unknown MIR pot")),
+        }
+    }
+}
+
+impl FromTerm for MIRTarget {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Map(_) => BTreeMap::from_term(term).map(MIRTarget::StakeAddressesMIR),
+            other => Coin::from_term(other).map(MIRTarget::SendToOppositePotMIR),
+        }
+    }
+}
+
+impl FromTerm for MIRCert {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (pot, target) = FromTerm::from_term(term)?;
+        Ok(MIRCert { pot, target })
+    }
+}
+
+impl FromTerm for Credential {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "Credential")?;
+        let credential = match fields.tag {
+            0 => Credential::KeyHash(fields.field()?),
+            1 => Credential::ScriptHash(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(credential)
+    }
+}
+
+impl FromTerm for VoterEnum {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "Voter")?;
+        let voter = match fields.tag {
+            0 => VoterEnum::CommitteeKey(fields.field()?),
+            1 => VoterEnum::CommitteeScript(fields.field()?),
+            2 => VoterEnum::DRepKey(fields.field()?),
+            3 => VoterEnum::DRepScript(fields.field()?),
+            4 => VoterEnum::StakePool(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(voter)
+    }
+}
+
+impl FromTerm for DRep {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "DRep")?;
+        let drep = match fields.tag {
+            0 => DRep::KeyHash(fields.field()?),
+            1 => DRep::ScriptHash(fields.field()?),
+            2 => DRep::Abstain,
+            3 => DRep::NoConfidence,
+            _ => return fields.unknown(),
+        };
+        fields.finish(drep)
+    }
+}
+
+impl FromTerm for Relay {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "Relay")?;
+        let relay = match fields.tag {
+            0 => Relay::SingleHostAddr {
+                port: fields.nullable()?,
+                ipv4: fields.nullable()?,
+                ipv6: fields.nullable()?,
+            },
+            1 => Relay::SingleHostName {
+                port: fields.nullable()?,
+                dns_name: fields.field()?,
+            },
+            2 => Relay::MultiHostName {
+                dns_name: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(relay)
+    }
+}
+
+impl FromTerm for PoolMetadata {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (url, metadata_hash) = FromTerm::from_term(term)?;
+        Ok(PoolMetadata { url, metadata_hash })
+    }
+}
+
+/// Certificates are flat sums; pool registrations inline the pool parameters.
+impl FromTerm for PoolCert {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "TxCert")?;
+        let cert = match fields.tag {
+            0 => PoolCert::StakeRegistration {
+                credential: fields.field()?,
+            },
+            1 => PoolCert::StakeDeregistration {
+                credential: fields.field()?,
+            },
+            2 => PoolCert::StakeDelegation {
+                credential: fields.field()?,
+                pool: fields.field()?,
+            },
+            3 => PoolCert::PoolRegistration {
+                params: PoolParams {
+                    operator: fields.field()?,
+                    vrf_keyhash: fields.field()?,
+                    pledge: fields.field()?,
+                    cost: fields.field()?,
+                    margin: fields.field()?,
+                    reward_account: fields.field()?,
+                    owners: fields.field()?,
+                    relays: fields.field()?,
+                    metadata: fields.nullable()?,
+                },
+            },
+            4 => PoolCert::PoolRetirement {
+                pool: fields.field()?,
+                epoch: fields.field()?,
+            },
+            5 => {
+                return Err(ParseError::Malformed(
+                    "genesis delegation certificates are no longer supported",
+                ))
+            }
+            6 => {
+                return Err(ParseError::Malformed(
+                    "MIR certificates are no longer supported",
+                ))
+            }
+            7 => PoolCert::RegCert {
+                credential: fields.field()?,
+                coin: fields.field()?,
+            },
+            8 => PoolCert::UnregCert {
+                credential: fields.field()?,
+                coin: fields.field()?,
+            },
+            9 => PoolCert::VoteDelegCert {
+                credential: fields.field()?,
+                drep: fields.field()?,
+            },
+            10 => PoolCert::StakeVoteDelegCert {
+                credential: fields.field()?,
+                pool: fields.field()?,
+                drep: fields.field()?,
+            },
+            11 => PoolCert::StakeRegDelegCert {
+                credential: fields.field()?,
+                pool: fields.field()?,
+                deposit: fields.field()?,
+            },
+            12 => PoolCert::VoteRegDelegCert {
+                credential: fields.field()?,
+                drep: fields.field()?,
+                deposit: fields.field()?,
+            },
+            13 => PoolCert::StakeVoteRegDelegCert {
+                credential: fields.field()?,
+                pool: fields.field()?,
+                drep: fields.field()?,
+                deposit: fields.field()?,
+            },
+            14 => PoolCert::AuthCommitteeHotCert {
+                cold: fields.field()?,
+                hot: fields.field()?,
+            },
+            15 => PoolCert::ResignCommitteeColdCert {
+                cold: fields.field()?,
+                anchor: fields.nullable()?,
+            },
+            16 => PoolCert::RegDRepCert {
+                credential: fields.field()?,
+                deposit: fields.field()?,
+                anchor: fields.nullable()?,
+            },
+            17 => PoolCert::UnregDRepCert {
+                credential: fields.field()?,
+                deposit: fields.field()?,
+            },
+            18 => PoolCert::UpdateDRepCert {
+                credential: fields.field()?,
+                anchor: fields.nullable()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(cert)
+    }
+}
+
+/// `DijkstraTxCert` fails on the certificates it dropped before reading any
+/// fields, with the messages of the Haskell decoder.
+/// The certificates Conway added (tags 7 onwards) are unknown here.
+impl FromTerm for ShelleyTxCert {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "ShelleyTxCert")?;
+        let cert = match fields.tag {
+            0..=4 => return PoolCert::from_term(term).map(ShelleyTxCert::Shared),
+            5 => ShelleyTxCert::GenesisDelegation {
+                genesis: fields.field()?,
+                delegate: fields.field()?,
+                vrf: fields.field()?,
+            },
+            6 => ShelleyTxCert::Mir(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(cert)
+    }
+}
+
+impl FromTerm for dijkstra::TxCert {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let removed = match Fields::of_term(term, "DijkstraTxCert")?.tag {
+            0 | 1 => "certificates without deposits are no longer supported",
+            5 => "genesis delegation certificates are no longer supported",
+            6 => "MIR certificates are no longer supported",
+            _ => {
+                return dijkstra::TxCert::new(PoolCert::from_term(term)?)
+                    .ok_or(ParseError::Malformed("expected a Dijkstra certificate"))
+            }
+        };
+        Err(ParseError::Malformed(removed))
+    }
+}
+
+impl FromTerm for VotingProcedure {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match array_of_len(term, 2)? {
+            [vote, anchor] => Ok(VotingProcedure {
+                vote: u8::try_from(u64::from_term(vote)?)
+                    .map_err(|_| ParseError::Malformed("vote out of range"))?,
+                anchor: nullable(anchor)?,
+            }),
+            _ => unreachable!("length checked above"),
+        }
+    }
+}
+
+impl FromTerm for VotingProceduresStruct {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        FromTerm::from_term(term).map(VotingProceduresStruct)
+    }
+}
+
+impl FromTerm for GovActionId {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (tx_id, action_index) = FromTerm::from_term(term)?;
+        Ok(GovActionId {
+            tx_id,
+            action_index,
+        })
+    }
+}
+
+impl FromTerm for Anchor {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (url, data_hash) = FromTerm::from_term(term)?;
+        Ok(Anchor { url, data_hash })
+    }
+}
+
+impl FromTerm for Constitution {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match array_of_len(term, 2)? {
+            [anchor, script_hash] => Ok(Constitution {
+                anchor: Anchor::from_term(anchor)?,
+                script_hash: nullable(script_hash)?,
+            }),
+            _ => unreachable!("length checked above"),
+        }
+    }
+}
+
+impl FromTerm for CostModels {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let models: BTreeMap<u64, Vec<i64>> = FromTerm::from_term(term)?;
+        models
+            .into_iter()
+            .map(|(language, model)| {
+                u8::try_from(language)
+                    .map(|language| (language, model))
+                    .map_err(|_| ParseError::Malformed("cost model language out of range"))
+            })
+            .collect::<Result<_, _>>()
+            .map(CostModels)
+    }
+}
+
+impl FromTerm for ExUnitPrices {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (mem_price, step_price) = FromTerm::from_term(term)?;
+        Ok(ExUnitPrices {
+            mem_price,
+            step_price,
+        })
+    }
+}
+
+/// `#6.30([numerator, denominator])`; like `decodeRational`, the tag is
+/// optional.
+fn ratio(term: &Term) -> Result<(u64, u64), ParseError> {
+    match term {
+        Term::Tagged(30, inner) => FromTerm::from_term(inner),
+        other => FromTerm::from_term(other),
+    }
+}
+
+fn bounded<T>(interval: Result<T, IntervalError>) -> Result<T, ParseError> {
+    interval.map_err(|err| match err {
+        IntervalError::ZeroDenominator => ParseError::Malformed("zero denominator"),
+        IntervalError::AboveOne => ParseError::Malformed("unit interval above one"),
+    })
+}
+
+impl FromTerm for UnitInterval {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (numerator, denominator) = ratio(term)?;
+        bounded(UnitInterval::new(numerator, denominator))
+    }
+}
+
+impl FromTerm for NonNegativeInterval {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (numerator, denominator) = ratio(term)?;
+        bounded(NonNegativeInterval::new(numerator, denominator))
+    }
+}
+
+impl FromTerm for PoolVotingThresholds {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let items = array_of_len(term, 5)?;
+        Ok(PoolVotingThresholds {
+            motion_no_confidence: FromTerm::from_term(&items[0])?,
+            committee_normal: FromTerm::from_term(&items[1])?,
+            committee_no_confidence: FromTerm::from_term(&items[2])?,
+            hard_fork_initiation: FromTerm::from_term(&items[3])?,
+            security_parameter: FromTerm::from_term(&items[4])?,
+        })
+    }
+}
+
+impl FromTerm for DRepVotingThresholds {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let items = array_of_len(term, 10)?;
+        Ok(DRepVotingThresholds {
+            motion_no_confidence: FromTerm::from_term(&items[0])?,
+            committee_normal: FromTerm::from_term(&items[1])?,
+            committee_no_confidence: FromTerm::from_term(&items[2])?,
+            update_constitution: FromTerm::from_term(&items[3])?,
+            hard_fork_initiation: FromTerm::from_term(&items[4])?,
+            pparam_network: FromTerm::from_term(&items[5])?,
+            pparam_economic: FromTerm::from_term(&items[6])?,
+            pparam_technical: FromTerm::from_term(&items[7])?,
+            pparam_governance: FromTerm::from_term(&items[8])?,
+            treasury_withdrawal: FromTerm::from_term(&items[9])?,
+        })
+    }
+}
+
+/// `protocol_param_update` is a map keyed by the CDDL field numbers.
+impl FromTerm for ProtocolParamUpdate {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let Term::Map(entries) = term else {
+            return Err(ParseError::Malformed("expected a map"));
+        };
+        let mut update = ProtocolParamUpdate::default();
+        for (key, value) in entries {
+            match u64::from_term(key)? {
+                0 => update.minfee_a = Some(FromTerm::from_term(value)?),
+                1 => update.minfee_b = Some(FromTerm::from_term(value)?),
+                2 => update.max_block_body_size = Some(FromTerm::from_term(value)?),
+                3 => update.max_tx_size = Some(FromTerm::from_term(value)?),
+                4 => update.max_block_header_size = Some(FromTerm::from_term(value)?),
+                5 => update.key_deposit = Some(FromTerm::from_term(value)?),
+                6 => update.pool_deposit = Some(FromTerm::from_term(value)?),
+                7 => update.max_epoch = Some(FromTerm::from_term(value)?),
+                8 => update.desired_number_of_pools = Some(FromTerm::from_term(value)?),
+                9 => update.pool_pledge_influence = Some(FromTerm::from_term(value)?),
+                10 => update.expansion_rate = Some(FromTerm::from_term(value)?),
+                11 => update.treasury_growth_rate = Some(FromTerm::from_term(value)?),
+                16 => update.min_pool_cost = Some(FromTerm::from_term(value)?),
+                17 => update.ada_per_utxo_byte = Some(FromTerm::from_term(value)?),
+                18 => update.cost_models = Some(FromTerm::from_term(value)?),
+                19 => update.ex_unit_prices = Some(FromTerm::from_term(value)?),
+                20 => update.max_tx_ex_units = Some(FromTerm::from_term(value)?),
+                21 => update.max_block_ex_units = Some(FromTerm::from_term(value)?),
+                22 => update.max_value_size = Some(FromTerm::from_term(value)?),
+                23 => update.collateral_percentage = Some(FromTerm::from_term(value)?),
+                24 => update.max_collateral_inputs = Some(FromTerm::from_term(value)?),
+                25 => update.pool_voting_thresholds = Some(FromTerm::from_term(value)?),
+                26 => update.drep_voting_thresholds = Some(FromTerm::from_term(value)?),
+                27 => update.min_committee_size = Some(FromTerm::from_term(value)?),
+                28 => update.committee_term_limit = Some(FromTerm::from_term(value)?),
+                29 => update.governance_action_validity_period = Some(FromTerm::from_term(value)?),
+                30 => update.governance_action_deposit = Some(FromTerm::from_term(value)?),
+                31 => update.drep_deposit = Some(FromTerm::from_term(value)?),
+                32 => update.drep_inactivity_period = Some(FromTerm::from_term(value)?),
+                33 => update.ref_script_coins_per_byte = Some(FromTerm::from_term(value)?),
+                _ => return Err(ParseError::Malformed("unknown protocol parameter key")),
+            }
+        }
+        Ok(update)
+    }
+}
+
+/// The purpose of a previous governance action is implied by the action type.
+fn previous_action(
+    fields: &mut Fields<'_>,
+    purpose: GovActionPurpose,
+) -> Result<Option<GovPurposeIdStruct>, ParseError> {
+    Ok(fields
+        .nullable::<GovActionId>()?
+        .map(|id| GovPurposeIdStruct { purpose, id }))
+}
+
+impl FromTerm for GovActionStruct {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "GovAction")?;
+        let action = match fields.tag {
+            0 => GovActionStruct::ParameterChange {
+                previous: previous_action(&mut fields, GovActionPurpose::ParameterChange)?,
+                update: fields.field()?,
+                policy_hash: fields.nullable()?,
+            },
+            1 => GovActionStruct::HardForkInitiation {
+                previous: previous_action(&mut fields, GovActionPurpose::HardFork)?,
+                protocol_version: fields.field()?,
+            },
+            2 => GovActionStruct::TreasuryWithdrawals {
+                withdrawals: fields.field()?,
+                policy_hash: fields.nullable()?,
+            },
+            3 => GovActionStruct::NoConfidence {
+                previous: previous_action(&mut fields, GovActionPurpose::Committee)?,
+            },
+            4 => GovActionStruct::UpdateCommittee {
+                previous: previous_action(&mut fields, GovActionPurpose::Committee)?,
+                removals: fields.field()?,
+                additions: fields.field()?,
+                new_quorum: fields.field()?,
+            },
+            5 => GovActionStruct::NewConstitution {
+                previous: previous_action(&mut fields, GovActionPurpose::Constitution)?,
+                constitution: fields.field()?,
+            },
+            6 => GovActionStruct::InfoAction,
+            _ => return fields.unknown(),
+        };
+        fields.finish(action)
+    }
+}
+
+impl FromTerm for ProposalProcedureStruct {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match array_of_len(term, 4)? {
+            [deposit, reward_account, action, anchor] => Ok(ProposalProcedureStruct {
+                deposit: FromTerm::from_term(deposit)?,
+                reward_account: FromTerm::from_term(reward_account)?,
+                action: FromTerm::from_term(action)?,
+                anchor: FromTerm::from_term(anchor)?,
+            }),
+            _ => unreachable!("length checked above"),
+        }
+    }
+}
+
+/// Only the `AsIx` form (`[tag, index]`) fits the stand-in purpose type.
+impl FromTerm for PlutusPurposeStruct {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (tag, index): (u64, u32) = FromTerm::from_term(term)?;
+        let tag = match tag {
+            0 => RedeemerTag::Spend,
+            1 => RedeemerTag::Mint,
+            2 => RedeemerTag::Cert,
+            3 => RedeemerTag::Reward,
+            4 => RedeemerTag::Voting,
+            5 => RedeemerTag::Proposing,
+            _ => return Err(ParseError::Malformed("unknown redeemer tag")),
+        };
+        Ok(PlutusPurposeStruct { tag, index })
+    }
+}
+
+impl FromTerm for AlonzoPlutusPurpose {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let (tag, index): (u64, u32) = FromTerm::from_term(term)?;
+        let tag = match tag {
+            0 => AlonzoRedeemerTag::Spend,
+            1 => AlonzoRedeemerTag::Mint,
+            2 => AlonzoRedeemerTag::Cert,
+            3 => AlonzoRedeemerTag::Reward,
+            _ => return Err(ParseError::Malformed("unknown redeemer tag")),
+        };
+        Ok(AlonzoPlutusPurpose { tag, index })
+    }
+}
+
+impl FromTerm for dijkstra::PlutusPurpose {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        use dijkstra::RedeemerTag;
+        let (tag, index): (u64, u32) = FromTerm::from_term(term)?;
+        let tag = match tag {
+            0 => RedeemerTag::Spend,
+            1 => RedeemerTag::Mint,
+            2 => RedeemerTag::Cert,
+            3 => RedeemerTag::Reward,
+            4 => RedeemerTag::Voting,
+            5 => RedeemerTag::Proposing,
+            6 => RedeemerTag::Guarding,
+            _ => return Err(ParseError::Malformed("unknown redeemer tag")),
+        };
+        Ok(dijkstra::PlutusPurpose { tag, index })
+    }
+}
+
+impl FromTerm for PlutusPurposeItemStruct {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "PlutusPurpose")?;
+        let purpose = match fields.tag {
+            0 => PlutusPurposeItemStruct::Spending(fields.field()?),
+            1 => PlutusPurposeItemStruct::Minting(fields.field()?),
+            2 => PlutusPurposeItemStruct::Certifying(fields.field()?),
+            3 => PlutusPurposeItemStruct::Rewarding(fields.field()?),
+            4 => PlutusPurposeItemStruct::Voting(fields.field()?),
+            5 => PlutusPurposeItemStruct::Proposing(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(purpose)
+    }
+}
+
+impl FromTerm for AlonzoPlutusPurposeItem {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        use AlonzoPlutusPurposeItem::*;
+        let mut fields = Fields::of_term(term, "AlonzoPlutusPurpose")?;
+        let purpose = match fields.tag {
+            0 => Spending(fields.field()?),
+            1 => Minting(fields.field()?),
+            2 => Certifying(fields.field()?),
+            3 => Rewarding(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(purpose)
+    }
+}
+
+impl FromTerm for dijkstra::PlutusPurposeItem {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        use dijkstra::PlutusPurposeItem::*;
+        let mut fields = Fields::of_term(term, "DijkstraPlutusPurpose")?;
+        let purpose = match fields.tag {
+            0 => Spending(fields.field()?),
+            1 => Minting(fields.field()?),
+            2 => Certifying(fields.field()?),
+            3 => Rewarding(fields.field()?),
+            4 => Voting(fields.field()?),
+            5 => Proposing(fields.field()?),
+            6 => Guarding(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(purpose)
+    }
+}
+
+impl FromTerm for TxOutSource {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "TxOutSource")?;
+        let source = match fields.tag {
+            0 => TxOutSource::FromInput(fields.field()?),
+            1 => TxOutSource::FromOutput(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(source)
+    }
+}
+
+impl FromTerm for Language {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match u64::from_term(term)? {
+            0 => Ok(Language::PlutusV1),
+            1 => Ok(Language::PlutusV2),
+            2 => Ok(Language::PlutusV3),
+            3 => Ok(Language::PlutusV4),
+            _ => Err(ParseError::Malformed("unknown Plutus language")),
+        }
+    }
+}
+
+/// Byte strings longer than 64 bytes are encoded as indefinite-length
+/// chunks of at most 64 bytes; the reader has already joined them.
+impl FromTerm for BoundedBytes {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        Vec::from_term(term).map(BoundedBytes)
+    }
+}
+
+impl FromTerm for BigInt {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Unsigned(value) => Ok(BigInt::Int(i128::from(*value))),
+            Term::Negative(value) => Ok(BigInt::Int(*value)),
+            Term::Tagged(2, inner) => BoundedBytes::from_term(inner).map(BigInt::BigUInt),
+            Term::Tagged(3, inner) => BoundedBytes::from_term(inner).map(BigInt::BigNInt),
+            _ => Err(ParseError::Malformed("expected an integer")),
+        }
+    }
+}
+
+/// Constructors use the compact tags 121–127 and 1280–1400 for indices up to
+/// 127, and tag 102 over `[index, fields]` beyond that.
+impl FromTerm for PlutusData {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Tagged(tag, inner) => match SemanticTag::of(*tag) {
+                Some(SemanticTag::PlutusConstr(index)) => plutus_constr(index, inner),
+                Some(SemanticTag::PlutusGeneralConstr) => {
+                    let items = array_of_len(inner, 2)?;
+                    plutus_constr(u64::from_term(&items[0])?, &items[1])
+                }
+                Some(SemanticTag::PositiveBignum | SemanticTag::NegativeBignum) => {
+                    BigInt::from_term(term).map(PlutusData::Integer)
+                }
+                _ => Err(ParseError::Malformed("unexpected tag in Plutus data")),
+            },
+            Term::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| {
+                    Ok((PlutusData::from_term(key)?, PlutusData::from_term(value)?))
+                })
+                .collect::<Result<_, _>>()
+                .map(PlutusData::Map),
+            Term::Array(_) => plutus_list(term).map(PlutusData::List),
+            Term::Bytes(bytes) => Ok(PlutusData::Bytes(bytes.clone())),
+            Term::Unsigned(_) | Term::Negative(_) => {
+                BigInt::from_term(term).map(PlutusData::Integer)
+            }
+            _ => Err(ParseError::Malformed("expected Plutus data")),
+        }
+    }
+}
+
+fn plutus_constr(index: u64, fields: &Term) -> Result<PlutusData, ParseError> {
+    let tag = u32::try_from(index)
+        .map_err(|_| ParseError::Malformed("Plutus constructor index out of range"))?;
+    let fields = plutus_list(fields)?;
+    Ok(PlutusData::Constr { tag, fields })
+}
+
+fn plutus_list(term: &Term) -> Result<Vec<PlutusData>, ParseError> {
+    array(term)?
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            PlutusData::from_term(item)
+                .map_err(|err| err.at(None, Some(item)).within(PathSegment::Index(index)))
+        })
+        .collect()
+}
+
+impl FromTerm for DatumOption {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "DatumOption")?;
+        let datum = match fields.tag {
+            0 => DatumOption::Hash(fields.field()?),
+            1 => DatumOption::Inline(fields.embedded()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(datum)
+    }
+}
+
+impl FromTerm for NativeScript {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "NativeScript")?;
+        let script = match fields.tag {
+            0 => NativeScript::ScriptPubkey(fields.field()?),
+            1 => NativeScript::ScriptAll(fields.field()?),
+            2 => NativeScript::ScriptAny(fields.field()?),
+            3 => NativeScript::ScriptNOfK {
+                required: fields.field()?,
+                scripts: fields.field()?,
+            },
+            4 => NativeScript::InvalidBefore(fields.field()?),
+            5 => NativeScript::InvalidHereafter(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(script)
+    }
+}
+
+impl FromTerm for dijkstra::NativeScript {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        use dijkstra::NativeScript::*;
+        let mut fields = Fields::of_term(term, "DijkstraNativeScript")?;
+        let script = match fields.tag {
+            0 => ScriptPubkey(fields.field()?),
+            1 => ScriptAll(fields.field()?),
+            2 => ScriptAny(fields.field()?),
+            3 => ScriptNOfK {
+                required: fields.field()?,
+                scripts: fields.field()?,
+            },
+            4 => InvalidBefore(fields.field()?),
+            5 => InvalidHereafter(fields.field()?),
+            6 => RequireGuard(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(script)
+    }
+}
+
+impl FromTerm for Script {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "Script")?;
+        let script = match fields.tag {
+            0 => Script::Native(fields.field()?),
+            1 => Script::Plutus(PlutusScript::V1(fields.field()?)),
+            2 => Script::Plutus(PlutusScript::V2(fields.field()?)),
+            3 => Script::Plutus(PlutusScript::V3(fields.field()?)),
+            _ => return fields.unknown(),
+        };
+        fields.finish(script)
+    }
+}
+
+/// Dijkstra adds guard native scripts and Plutus V4; the other Plutus
+/// languages are read as in Conway.
+impl FromTerm for dijkstra::Script {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        let mut fields = Fields::of_term(term, "DijkstraScript")?;
+        let script = match fields.tag {
+            0 => dijkstra::Script::Native(fields.field()?),
+            4 => dijkstra::Script::PlutusV4(fields.field()?),
+            _ => {
+                return match Script::from_term(term)? {
+                    Script::Plutus(script) => Ok(dijkstra::Script::Plutus(script)),
+                    Script::Native(_) => unreachable!("tag 0 is read above"),
+                }
+            }
+        };
+        fields.finish(script)
+    }
+}
+
+/// Reference scripts are embedded CBOR under tag 24.
+impl<S: FromTerm> FromTerm for ScriptRef<S> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        embedded(term).map(ScriptRef)
+    }
+}
+
+/// Values are a bare coin, or `[coin, multiasset]` from Mary onwards.  The
+/// coin may be zero when a value only carries tokens, and quantities may be
+/// negative in the consumed side of a balance that burns tokens.  An empty
+/// multiasset is kept as it is so that the value re-encodes the same way.
+impl FromTerm for ValueStruct {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Array(_) => {
+                let (coin, assets) = FromTerm::from_term(term)?;
+                Ok(ValueStruct::MultiAsset { coin, assets })
+            }
+            other => Coin::from_term(other).map(ValueStruct::Coin),
+        }
+    }
+}
+
+/// Outputs use the legacy array form `[address, amount, ?datum_hash]`, or
+/// from Babbage onwards the map form with keys 0 to 3.  Each form decodes
+/// into its own variant so that the output is re-encoded the way it came.
+/// Forms the era cannot produce are rejected: datum hashes before Alonzo,
+/// the map form before Babbage, and multi-asset amounts before Mary, the
+/// latter through `E::Value`.
+impl<E: Era> FromTerm for TxOutStruct<E> {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match term {
+            Term::Array(items) => {
+                let (address, amount, datum_hash) = match items.as_slice() {
+                    [address, amount] => (address, amount, None),
+                    [address, amount, datum_hash] if E::INDEX >= Alonzo::INDEX => {
+                        (address, amount, Some(datum_hash))
+                    }
+                    _ => {
+                        return Err(ParseError::Malformed(
+                            "unexpected transaction output length",
+                        ))
+                    }
+                };
+                Ok(TxOutStruct::Shelley {
+                    address: Address::from_term(address)?,
+                    amount: E::Value::from_term(amount)?,
+                    datum_hash: datum_hash.map(DataHash::from_term).transpose()?,
+                })
+            }
+            Term::Map(_) if E::INDEX < Babbage::INDEX => Err(ParseError::Malformed(
+                "map-encoded transaction outputs need the Babbage era",
+            )),
+            Term::Map(entries) => {
+                let mut fields: [Option<&Term>; 4] = [None; 4];
+                for (key, value) in entries {
+                    let field = usize::from_term(key)
+                        .ok()
+                        .and_then(|key| fields.get_mut(key))
+                        .ok_or(ParseError::Malformed("unknown transaction output key"))?;
+                    if field.replace(value).is_some() {
+                        return Err(ParseError::Malformed("duplicate transaction output key"));
+                    }
+                }
+                let [Some(address), Some(amount), datum_option, script_ref] = fields else {
+                    return Err(ParseError::Malformed("missing transaction output field"));
+                };
+                Ok(TxOutStruct::Babbage {
+                    address: Address::from_term(address)?,
+                    amount: E::Value::from_term(amount)?,
+                    datum_option: datum_option.map(DatumOption::from_term).transpose()?,
+                    script_ref: script_ref.map(ScriptRef::from_term).transpose()?,
+                })
+            }
+            _ => Err(ParseError::Malformed("expected a transaction output")),
+        }
+    }
+}
+
+impl FromTerm for UTxOStruct {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        BTreeMap::from_term(term).map(UTxOStruct)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shelley era predicate failures
+// ---------------------------------------------------------------------------
+
+impl FromTerm for shelley::VotingPeriod {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        match u64::from_term(term)? {
+            0 => Ok(shelley::VotingPeriod::VoteForThisEpoch),
+            1 => Ok(shelley::VotingPeriod::VoteForNextEpoch),
+            _ => Err(ParseError::Malformed("unknown voting period")),
+        }
+    }
+}
+
+impl FromTaggedTree for shelley::PpupPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use shelley::PpupPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "shelley::PpupPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => NonGenesisUpdatePPUP {
+                offending_keys: fields.mismatch()?,
+            },
+            1 => PPUpdateWrongEpoch {
+                current_epoch: fields.field()?,
+                declared_epoch: fields.field()?,
+                voting_period: fields.field()?,
+            },
+            2 => PVCannotFollowPPUP {
+                proposed_version: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: Era> FromTaggedTree for shelley::UtxoPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use shelley::UtxoPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "shelley::UtxoPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => BadInputsUTxO {
+                invalid_inputs: fields.field()?,
+            },
+            1 => ExpiredUTxO {
+                ttl_mismatch: fields.mismatch()?,
+            },
+            2 => MaxTxSizeUTxO {
+                size_mismatch: fields.mismatch()?,
+            },
+            3 => InputSetEmptyUTxO,
+            4 => FeeTooSmallUTxO {
+                fee_mismatch: fields.mismatch()?,
+            },
+            5 => ValueNotConservedUTxO {
+                balance_mismatch: fields.mismatch()?,
+            },
+            6 => OutputTooSmallUTxO {
+                tiny_outputs: fields.field()?,
+            },
+            7 => UpdateFailure(fields.failure()?),
+            8 => WrongNetwork {
+                expected: fields.field()?,
+                offending: fields.field()?,
+            },
+            9 => WrongNetworkWithdrawal {
+                expected: fields.field()?,
+                offending: fields.field()?,
+            },
+            10 => OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<Utxo: FromTaggedTree> FromTaggedTree for shelley::UtxowPredicateFailure<Utxo> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use shelley::UtxowPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "shelley::UtxowPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => InvalidWitnessesUTXOW {
+                invalid_witnesses: fields.field()?,
+            },
+            1 => MissingVKeyWitnessesUTXOW {
+                missing_signers: fields.field()?,
+            },
+            2 => MissingScriptWitnessesUTXOW {
+                missing_scripts: fields.field()?,
+            },
+            3 => ScriptWitnessNotValidatingUTXOW {
+                failed_scripts: fields.field()?,
+            },
+            4 => UtxoFailure(fields.failure()?),
+            5 => MIRInsufficientGenesisSigsUTXOW {
+                missing_signatures: fields.field()?,
+            },
+            6 => MissingTxBodyMetadataHash {
+                expected: fields.field()?,
+            },
+            7 => MissingTxMetadata {
+                referenced: fields.field()?,
+            },
+            8 => ConflictingMetadataHash {
+                mismatch: fields.mismatch()?,
+            },
+            9 => InvalidMetadata,
+            10 => ExtraneousScriptWitnessesUTXOW {
+                extra_scripts: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for shelley::DelegPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use shelley::DelegPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "shelley::DelegPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => StakeKeyAlreadyRegistered {
+                credential: fields.field()?,
+            },
+            1 => StakeKeyNotRegistered {
+                credential: fields.field()?,
+            },
+            2 => StakeKeyNonZeroAccountBalance {
+                remaining_balance: fields.field()?,
+            },
+            3 => StakeDelegationImpossible {
+                credential: fields.field()?,
+            },
+            4 => WrongCertificateType,
+            5 => GenesisKeyNotInMapping {
+                genesis_key: fields.field()?,
+            },
+            6 => DuplicateGenesisDelegate {
+                delegate: fields.field()?,
+            },
+            7 => InsufficientForInstantaneousRewards {
+                pot: fields.field()?,
+                bound: fields.mismatch()?,
+            },
+            8 => MIRCertificateTooLateInEpoch {
+                cutoff: fields.mismatch()?,
+            },
+            9 => DuplicateGenesisVRF {
+                vrf: fields.field()?,
+            },
+            11 => MIRTransferNotCurrentlyAllowed,
+            12 => MIRNegativesNotCurrentlyAllowed,
+            13 => InsufficientForTransfer {
+                pot: fields.field()?,
+                bound: fields.mismatch()?,
+            },
+            14 => MIRProducesNegativeUpdate,
+            15 => MIRNegativeTransfer {
+                pot: fields.field()?,
+                attempted: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for shelley::PoolPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use shelley::PoolPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "shelley::PoolPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => StakePoolNotRegisteredOnKey {
+                pool_id: fields.field()?,
+            },
+            // Both mismatches share the supplied epoch, which is sent once:
+            // `[1, gt_expected, supplied, lteq_expected]`.
+            1 => {
+                let too_early: EpochNo = fields.field()?;
+                let supplied: EpochNo = fields.field()?;
+                let too_late: EpochNo = fields.field()?;
+                StakePoolRetirementWrongEpoch {
+                    retirement_too_early: Mismatch::new(supplied, too_early),
+                    retirement_too_late: Mismatch::new(supplied, too_late),
+                }
+            }
+            3 => StakePoolCostTooLow {
+                cost_bound: fields.grouped_mismatch()?,
+            },
+            4 => WrongNetwork {
+                network_mismatch: fields.swapped_mismatch()?,
+                pool_id: fields.field()?,
+            },
+            5 => PoolMetadataHashTooBig {
+                pool_id: fields.field()?,
+                hash_size: fields.field()?,
+            },
+            6 => VRFKeyHashAlreadyRegistered {
+                pool_id: fields.field()?,
+                vrf: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for shelley::DelegsPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use shelley::DelegsPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "shelley::DelegsPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => DelegateeNotRegistered {
+                pool_id: fields.field()?,
+            },
+            1 => WithdrawalsNotInRewards {
+                withdrawals: fields.field()?,
+            },
+            2 => DelplFailure(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for shelley::DelplPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use shelley::DelplPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "shelley::DelplPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => PoolFailure(fields.failure()?),
+            1 => DelegFailure(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<Utxo: FromTaggedTree> FromTaggedTree for shelley::LedgerPredicateFailure<Utxo> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use shelley::LedgerPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "shelley::LedgerPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => UtxowFailure(fields.failure()?),
+            1 => DelegsFailure(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+/// `ShelleyLedgersPredFailure` is encoded transparently as its LEDGER failure.
+impl<Ledger: FromTaggedTree> FromTaggedTree for shelley::LedgersPredicateFailure<Ledger> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        FromTaggedTree::from_tagged_tree(tree).map(shelley::LedgersPredicateFailure::LedgersFailure)
+    }
+}
+
+/// The Haskell node defines no CBOR instance for `ShelleyBbodyPredFailure`.
+impl<E: Era> FromTaggedTree for shelley::BbodyPredicateFailure<E> {
+    fn from_tagged_tree(_tree: &TaggedTree) -> Result<Self, ParseError> {
+        Err(ParseError::Unsupported("Shelley BBODY failures"))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Allegra & Mary era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: Era> FromTaggedTree for allegra::UtxoPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use allegra::UtxoPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "allegra::UtxoPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => BadInputsUTxO {
+                invalid_inputs: fields.field()?,
+            },
+            1 => OutsideValidityIntervalUTxO {
+                interval: fields.field()?,
+                current_slot: fields.field()?,
+            },
+            2 => MaxTxSizeUTxO {
+                size_mismatch: fields.mismatch()?,
+            },
+            3 => InputSetEmptyUTxO,
+            4 => FeeTooSmallUTxO {
+                fee_mismatch: fields.mismatch()?,
+            },
+            5 => ValueNotConservedUTxO {
+                balance_mismatch: fields.mismatch()?,
+            },
+            6 => OutputTooSmallUTxO {
+                tiny_outputs: fields.field()?,
+            },
+            7 => UpdateFailure(fields.failure()?),
+            8 => WrongNetwork {
+                expected: fields.field()?,
+                offending: fields.field()?,
+            },
+            9 => WrongNetworkWithdrawal {
+                expected: fields.field()?,
+                offending: fields.field()?,
+            },
+            10 => OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs: fields.field()?,
+            },
+            11 if E::INDEX == Mary::INDEX => TriesToForgeADA,
+            12 => OutputTooBigUTxO {
+                oversized_outputs: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Alonzo era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> FromTaggedTree for alonzo::BbodyPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use alonzo::BbodyPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "alonzo::BbodyPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => ShelleyInAlonzo(fields.failure()?),
+            1 => TooManyExUnits {
+                bound: fields.mismatch()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for alonzo::UtxoPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use alonzo::UtxoPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "alonzo::UtxoPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => BadInputsUTxO {
+                invalid_inputs: fields.field()?,
+            },
+            1 => OutsideValidityIntervalUTxO {
+                interval: fields.field()?,
+                current_slot: fields.field()?,
+            },
+            2 => MaxTxSizeUTxO {
+                bound: fields.mismatch()?,
+            },
+            3 => InputSetEmptyUTxO,
+            4 => FeeTooSmallUTxO {
+                bound: fields.mismatch()?,
+            },
+            5 => ValueNotConservedUTxO {
+                mismatch: fields.mismatch()?,
+            },
+            6 => OutputTooSmallUTxO {
+                outputs: fields.field()?,
+            },
+            7 => UtxosFailure(fields.failure()?),
+            8 => WrongNetwork {
+                expected: fields.field()?,
+                offending: fields.field()?,
+            },
+            9 => WrongNetworkWithdrawal {
+                expected: fields.field()?,
+                offending: fields.field()?,
+            },
+            10 => OutputBootAddrAttrsTooBig {
+                outputs: fields.field()?,
+            },
+            12 => OutputTooBigUTxO {
+                oversized_outputs: fields.field()?,
+            },
+            13 => InsufficientCollateral {
+                computed: fields.field()?,
+                required: fields.field()?,
+            },
+            14 => ScriptsNotPaidUTxO {
+                offending_utxo: fields.field()?,
+            },
+            15 => ExUnitsTooBigUTxO {
+                bound: fields.mismatch()?,
+            },
+            16 => CollateralContainsNonADA {
+                value: fields.field()?,
+            },
+            17 => WrongNetworkInTxBody {
+                mismatch: fields.mismatch()?,
+            },
+            18 => OutsideForecast {
+                slot: fields.field()?,
+            },
+            19 => TooManyCollateralInputs {
+                bound: fields.mismatch()?,
+            },
+            20 => NoCollateralInputs,
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTerm for alonzo::FailureDescription {
+    fn from_term(term: &Term) -> Result<Self, ParseError> {
+        use alonzo::FailureDescription::*;
+        let mut fields = Fields::of_term(term, "alonzo::FailureDescription")?;
+        let description = match fields.tag {
+            0 => PassedUnexpectedly,
+            1 => FailedUnexpectedly(fields.field()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(description)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for alonzo::UtxosPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use alonzo::UtxosPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "alonzo::UtxosPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => ValidationTagMismatch {
+                is_valid: fields.field()?,
+                description: fields.field()?,
+            },
+            1 => CollectErrors {
+                errors: fields.failures()?,
+            },
+            2 => UpdateFailure(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for alonzo::CollectError<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use alonzo::CollectError::*;
+        let mut fields = Fields::of_tree(tree, "alonzo::CollectError")?;
+        let error = match fields.tag {
+            0 => NoRedeemer {
+                purpose: fields.field()?,
+            },
+            1 => NoWitness {
+                script_hash: fields.field()?,
+            },
+            2 => NoCostModel {
+                language: fields.field()?,
+            },
+            3 => BadTranslation(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(error)
+    }
+}
+
+impl FromTaggedTree for alonzo::ContextError {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use alonzo::ContextError::*;
+        let mut fields = Fields::of_tree(tree, "alonzo::ContextError")?;
+        let error = match fields.tag {
+            1 => TranslationLogicMissingInput {
+                input: fields.field()?,
+            },
+            7 => TimeTranslationPastHorizon {
+                message: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(error)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for alonzo::UtxowPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use alonzo::UtxowPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "alonzo::UtxowPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => ShelleyInAlonzo(fields.failure()?),
+            1 => MissingRedeemers {
+                missing: fields.field()?,
+            },
+            2 => MissingRequiredDatums {
+                missing_hashes: fields.field()?,
+                provided_hashes: fields.field()?,
+            },
+            3 => NotAllowedSupplementalDatums {
+                forbidden_hashes: fields.field()?,
+                permitted: fields.field()?,
+            },
+            4 => PPViewHashesDontMatch {
+                mismatch: fields.mismatch()?,
+            },
+            6 => UnspendableUTxONoDatumHash {
+                inputs: fields.field()?,
+            },
+            7 => ExtraRedeemers {
+                extra: fields.field()?,
+            },
+            8 => ScriptIntegrityHashMismatch {
+                mismatch: fields.mismatch()?,
+                provided: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for alonzo::LedgerPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use alonzo::LedgerPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "alonzo::LedgerPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => UtxowFailure(fields.failure()?),
+            1 => DelegsFailure(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Babbage era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> FromTaggedTree for babbage::UtxoPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use babbage::UtxoPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "babbage::UtxoPredicateFailure")?;
+        let failure = match fields.tag {
+            1 => AlonzoInBabbage(fields.failure()?),
+            2 => IncorrectTotalCollateralField {
+                provided: fields.field()?,
+                declared: fields.field()?,
+            },
+            3 => OutputTooSmall {
+                outputs: fields.field()?,
+            },
+            4 => NonDisjointReferenceInputs {
+                overlapping: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for babbage::UtxowPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use babbage::UtxowPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "babbage::UtxowPredicateFailure")?;
+        let failure = match fields.tag {
+            1 => AlonzoInBabbage(fields.failure()?),
+            2 => UtxoFailure(fields.failure()?),
+            3 => MalformedScriptWitnesses {
+                witnesses: fields.field()?,
+            },
+            4 => MalformedReferenceScripts {
+                scripts: fields.field()?,
+            },
+            5 => ScriptIntegrityHashMismatch {
+                mismatch: fields.mismatch()?,
+                provided: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for babbage::ContextError<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use babbage::ContextError::*;
+        let mut fields = Fields::of_tree(tree, "babbage::ContextError")?;
+        let error = match fields.tag {
+            0 => ByronTxOutInContext {
+                source: fields.field()?,
+            },
+            1 | 7 => return FromTaggedTree::from_tagged_tree(tree).map(AlonzoContextError),
+            2 => RedeemerPointerPointsToNothing {
+                purpose: fields.field()?,
+            },
+            4 => InlineDatumsNotSupported {
+                source: fields.field()?,
+            },
+            5 => ReferenceScriptsNotSupported {
+                source: fields.field()?,
+            },
+            6 => ReferenceInputsNotSupported {
+                inputs: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(error)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for babbage::LedgerPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use babbage::LedgerPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "babbage::LedgerPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => UtxowFailure(fields.failure()?),
+            1 => DelegsFailure(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Conway era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> FromTaggedTree for conway::UtxosPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::UtxosPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::UtxosPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => ValidationTagMismatch {
+                tag: fields.field()?,
+                description: fields.field()?,
+            },
+            1 => CollectErrors {
+                errors: fields.failures()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for conway::ContextError<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::ContextError::*;
+        let mut fields = Fields::of_tree(tree, "conway::ContextError")?;
+        let error = match fields.tag {
+            8 => BabbageContextError(fields.failure()?),
+            9 => CertificateNotSupported {
+                certificate: fields.field()?,
+            },
+            10 => PlutusPurposeNotSupported {
+                purpose: fields.field()?,
+            },
+            11 => CurrentTreasuryFieldNotSupported {
+                treasury: fields.field()?,
+            },
+            12 => VotingProceduresFieldNotSupported {
+                procedures: fields.field()?,
+            },
+            13 => ProposalProceduresFieldNotSupported {
+                procedures: fields.field()?,
+            },
+            14 => TreasuryDonationFieldNotSupported {
+                donation: fields.field()?,
+            },
+            15 => ReferenceInputsNotDisjointFromInputs {
+                common: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(error)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for conway::UtxoPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::UtxoPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::UtxoPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => UtxosFailure(fields.failure()?),
+            1 => BadInputsUTxO {
+                invalid_inputs: fields.field()?,
+            },
+            2 => OutsideValidityIntervalUTxO {
+                validity_interval: fields.field()?,
+                current_slot: fields.field()?,
+            },
+            3 => MaxTxSizeUTxO {
+                size_mismatch: fields.grouped_mismatch()?,
+            },
+            4 => InputSetEmptyUTxO,
+            5 => FeeTooSmallUTxO {
+                fee_mismatch: fields.swapped_mismatch()?,
+            },
+            6 => ValueNotConservedUTxO {
+                balance_mismatch: fields.grouped_mismatch()?,
+            },
+            7 => WrongNetwork {
+                expected: fields.field()?,
+                offending: fields.field()?,
+            },
+            8 => WrongNetworkWithdrawal {
+                expected: fields.field()?,
+                offending: fields.field()?,
+            },
+            9 => OutputTooSmallUTxO {
+                tiny_outputs: fields.field()?,
+            },
+            10 => OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs: fields.field()?,
+            },
+            11 => OutputTooBigUTxO {
+                outputs: fields.field()?,
+            },
+            12 => InsufficientCollateral {
+                provided: fields.field()?,
+                required: fields.field()?,
+            },
+            13 => ScriptsNotPaidUTxO {
+                unpaid: fields.field()?,
+            },
+            14 => ExUnitsTooBigUTxO {
+                limit_mismatch: fields.swapped_mismatch()?,
+            },
+            15 => CollateralContainsNonADA {
+                offending_value: fields.field()?,
+            },
+            16 => WrongNetworkInTxBody {
+                mismatch: fields.swapped_mismatch()?,
+            },
+            17 => OutsideForecast {
+                slot: fields.field()?,
+            },
+            18 => TooManyCollateralInputs {
+                bound: fields.swapped_mismatch()?,
+            },
+            19 => NoCollateralInputs,
+            20 => IncorrectTotalCollateralField {
+                provided: fields.field()?,
+                declared: fields.field()?,
+            },
+            21 => BabbageOutputTooSmallUTxO {
+                outputs: fields.field()?,
+            },
+            22 => BabbageNonDisjointRefInputs {
+                overlapping: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for conway::UtxowPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::UtxowPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::UtxowPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => UtxoFailure(fields.failure()?),
+            1 => InvalidWitnessesUTXOW {
+                witnesses: fields.field()?,
+            },
+            2 => MissingVKeyWitnessesUTXOW {
+                missing: fields.field()?,
+            },
+            3 => MissingScriptWitnessesUTXOW {
+                missing: fields.field()?,
+            },
+            4 => ScriptWitnessNotValidatingUTXOW {
+                failing: fields.field()?,
+            },
+            5 => MissingTxBodyMetadataHash {
+                expected: fields.field()?,
+            },
+            6 => MissingTxMetadata {
+                expected: fields.field()?,
+            },
+            7 => ConflictingMetadataHash {
+                mismatch: fields.grouped_mismatch()?,
+            },
+            8 => InvalidMetadata,
+            9 => ExtraneousScriptWitnessesUTXOW {
+                extraneous: fields.field()?,
+            },
+            10 => MissingRedeemers {
+                missing: fields.field()?,
+            },
+            11 => MissingRequiredDatums {
+                missing_hashes: fields.field()?,
+                provided_hashes: fields.field()?,
+            },
+            12 => NotAllowedSupplementalDatums {
+                disallowed_hashes: fields.field()?,
+                allowed: fields.field()?,
+            },
+            13 => PPViewHashesDontMatch {
+                mismatch: fields.grouped_mismatch()?,
+            },
+            14 => UnspendableUTxONoDatumHash {
+                inputs: fields.field()?,
+            },
+            15 => ExtraRedeemers {
+                extra: fields.field()?,
+            },
+            16 => MalformedScriptWitnesses {
+                scripts: fields.field()?,
+            },
+            17 => MalformedReferenceScripts {
+                scripts: fields.field()?,
+            },
+            18 => ScriptIntegrityHashMismatch {
+                mismatch: fields.mismatch()?,
+                provided: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for conway::DelegPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::DelegPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::DelegPredicateFailure")?;
+        let failure = match fields.tag {
+            1 => IncorrectDepositDELEG {
+                deposit: fields.field()?,
+            },
+            2 => StakeKeyRegisteredDELEG {
+                stake_credential: fields.field()?,
+            },
+            3 => StakeKeyNotRegisteredDELEG {
+                stake_credential: fields.field()?,
+            },
+            4 => StakeKeyHasNonZeroRewardAccountBalanceDELEG {
+                balance: fields.field()?,
+            },
+            5 => DelegateeDRepNotRegisteredDELEG {
+                delegatee: fields.field()?,
+            },
+            6 => DelegateeStakePoolNotRegisteredDELEG {
+                delegatee: fields.field()?,
+            },
+            7 => DepositIncorrectDELEG {
+                mismatch: fields.mismatch()?,
+            },
+            8 => RefundIncorrectDELEG {
+                mismatch: fields.mismatch()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for conway::GovCertPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::GovCertPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::GovCertPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => ConwayDRepAlreadyRegistered {
+                credential: fields.field()?,
+            },
+            1 => ConwayDRepNotRegistered {
+                credential: fields.field()?,
+            },
+            2 => ConwayDRepIncorrectDeposit {
+                mismatch: fields.grouped_mismatch()?,
+            },
+            3 => ConwayCommitteeHasPreviouslyResigned {
+                cold_credential: fields.field()?,
+            },
+            4 => ConwayDRepIncorrectRefund {
+                mismatch: fields.grouped_mismatch()?,
+            },
+            5 => ConwayCommitteeIsUnknown {
+                cold_credential: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for conway::CertPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::CertPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::CertPredicateFailure")?;
+        let failure = match fields.tag {
+            1 => DelegFailure(fields.failure()?),
+            2 => PoolFailure(fields.failure()?),
+            3 => GovCertFailure(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for conway::CertsPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::CertsPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::CertsPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => WithdrawalsNotInRewardsCERTS {
+                withdrawals: fields.field()?,
+            },
+            1 => CertFailure(fields.failure()?),
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl FromTaggedTree for conway::GovPredicateFailure {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::GovPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::GovPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => GovActionsDoNotExist {
+                missing: fields.field()?,
+            },
+            1 => MalformedProposal {
+                proposal: fields.field()?,
+            },
+            2 => ProposalProcedureNetworkIdMismatch {
+                reward_account: fields.field()?,
+                expected_network: fields.field()?,
+            },
+            3 => TreasuryWithdrawalsNetworkIdMismatch {
+                offending_accounts: fields.field()?,
+                expected_network: fields.field()?,
+            },
+            4 => ProposalDepositIncorrect {
+                mismatch: fields.grouped_mismatch()?,
+            },
+            5 => DisallowedVoters {
+                voters: fields.field()?,
+            },
+            6 => ConflictingCommitteeUpdate {
+                members: fields.field()?,
+            },
+            7 => ExpirationEpochTooSmall {
+                expired: fields.field()?,
+            },
+            8 => InvalidPrevGovActionId {
+                proposal: fields.field()?,
+            },
+            9 => VotingOnExpiredGovAction {
+                votes: fields.field()?,
+            },
+            10 => ProposalCantFollow {
+                previous: fields
+                    .field::<Option<GovActionId>>()?
+                    .map(|id| GovPurposeIdStruct {
+                        purpose: GovActionPurpose::HardFork,
+                        id,
+                    }),
+                version_mismatch: fields.grouped_mismatch()?,
+            },
+            11 => InvalidPolicyHash {
+                provided: fields.field()?,
+                expected: fields.field()?,
+            },
+            12 => DisallowedProposalDuringBootstrap {
+                proposal: fields.field()?,
+            },
+            13 => DisallowedVotesDuringBootstrap {
+                votes: fields.field()?,
+            },
+            14 => VotersDoNotExist {
+                voters: fields.field()?,
+            },
+            15 => ZeroTreasuryWithdrawals {
+                action: fields.field()?,
+            },
+            16 => ProposalReturnAccountDoesNotExist {
+                reward_account: fields.field()?,
+            },
+            17 => TreasuryWithdrawalReturnAccountsDoNotExist {
+                reward_accounts: fields.field()?,
+            },
+            18 => UnelectedCommitteeVoters {
+                voters: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for conway::LedgerPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::LedgerPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::LedgerPredicateFailure")?;
+        let failure = match fields.tag {
+            1 => ConwayUtxowFailure(fields.failure()?),
+            2 => ConwayCertsFailure(fields.failure()?),
+            3 => ConwayGovFailure(fields.failure()?),
+            4 => ConwayWdrlNotDelegatedToDRep {
+                withdrawals: fields.field()?,
+            },
+            5 => ConwayTreasuryValueMismatch {
+                mismatch: fields.swapped_mismatch()?,
+            },
+            6 => ConwayTxRefScriptsSizeTooBig {
+                size_mismatch: fields.grouped_mismatch()?,
+            },
+            7 => ConwayMempoolFailure {
+                reason: fields.field()?,
+            },
+            8 => ConwayWithdrawalsMissingAccounts {
+                withdrawals: fields.field()?,
+            },
+            9 => ConwayIncompleteWithdrawals {
+                withdrawals: fields.field()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+impl<E: PlutusEra> FromTaggedTree for conway::BbodyPredicateFailure<E> {
+    fn from_tagged_tree(tree: &TaggedTree) -> Result<Self, ParseError> {
+        use conway::BbodyPredicateFailure::*;
+        let mut fields = Fields::of_tree(tree, "conway::BbodyPredicateFailure")?;
+        let failure = match fields.tag {
+            0 => WrongBlockBodySizeBBODY {
+                mismatch: fields.grouped_mismatch()?,
+            },
+            1 => InvalidBodyHashBBODY {
+                mismatch: fields.grouped_mismatch()?,
+            },
+            2 => LedgersFailure(fields.failure()?),
+            3 => TooManyExUnits {
+                bound: fields.grouped_mismatch()?,
+            },
+            4 => BodyRefScriptsSizeTooBig {
+                size_mismatch: fields.grouped_mismatch()?,
+            },
+            _ => return fields.unknown(),
+        };
+        fields.finish(failure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failure_encoder::{ToTerm, Version};
+    use ciborium::{ser::into_writer, value::Value};
+
+    fn uint(n: u64) -> Value {
+        Value::Integer(n.into())
+    }
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        into_writer(value, &mut buffer).expect("encode test value");
+        buffer
+    }
+
+    #[test]
+    fn decodes_nested_conway_fee_failure() {
+        // ConwayUtxowFailure (UtxoFailure (FeeTooSmallUTxO (Mismatch 170000 172345)))
+        // with the mismatch flattened and swapped as `[5, expected, supplied]`.
+        let message = Value::Array(vec![
+            uint(1),
+            Value::Array(vec![
+                uint(0),
+                Value::Array(vec![uint(5), uint(172_345), uint(170_000)]),
+            ]),
+        ]);
+
+        let failure: conway::LedgerPredicateFailure<Conway> =
+            decode_failure_bytes(&encode(&message)).expect("decode failure");
+        match failure {
+            conway::LedgerPredicateFailure::ConwayUtxowFailure(
+                conway::UtxowPredicateFailure::UtxoFailure(
+                    conway::UtxoPredicateFailure::FeeTooSmallUTxO { fee_mismatch },
+                ),
+            ) => {
+                assert_eq!(fee_mismatch.supplied, Coin(170_000));
+                assert_eq!(fee_mismatch.expected, Coin(172_345));
+                assert_eq!(fee_mismatch.kind(), RelationKind::Gteq);
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fills_in_relation_from_field_type() {
+        // MaxTxSizeUTxO (Mismatch 17000 16384)
+        let message = Value::Array(vec![
+            uint(2),
+            Value::Array(vec![uint(17_000), uint(16_384)]),
+        ]);
+
+        let failure: shelley::UtxoPredicateFailure<Shelley> =
+            decode_failure_bytes(&encode(&message)).expect("decode failure");
+        match failure {
+            shelley::UtxoPredicateFailure::MaxTxSizeUTxO { size_mismatch } => {
+                assert_eq!(size_mismatch.kind(), RelationKind::Lteq);
+                assert!(!size_mismatch.holds());
+                assert_eq!(size_mismatch.to_string(), "supplied 17000 must be ≤ 16384");
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+        assert!(Mismatch::<RelLTEQ, _>::new(16_384, 16_384).holds());
+    }
+
+    #[test]
+    fn rejects_empty_offender_list() {
+        // GovActionsDoNotExist (NonEmpty GovActionId)
+        let action = Value::Array(vec![Value::Bytes(vec![9; 32]), uint(0)]);
+        let missing = Value::Array(vec![uint(0), Value::Array(vec![action])]);
+        let empty = Value::Array(vec![uint(0), Value::Array(Vec::new())]);
+
+        let failure: conway::GovPredicateFailure =
+            decode_failure_bytes(&encode(&missing)).expect("decode failure");
+        match failure {
+            conway::GovPredicateFailure::GovActionsDoNotExist { missing } => {
+                assert_eq!(missing.head().action_index, GovActionIx(0));
+                assert!(missing.tail().is_empty());
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+        assert!(decode_failure_bytes::<conway::GovPredicateFailure>(&encode(&empty)).is_err());
+    }
+
+    #[test]
+    fn decodes_shelley_set_payload() {
+        let tx_in = Value::Array(vec![Value::Bytes(vec![7; 32]), uint(3)]);
+        let message = Value::Array(vec![
+            uint(0),
+            Value::Array(vec![
+                uint(4),
+                Value::Array(vec![uint(0), Value::Array(vec![tx_in])]),
+            ]),
+        ]);
+
+        let failure: shelley::LedgerPredicateFailure<shelley::UtxoPredicateFailure<Shelley>> =
+            decode_failure_bytes(&encode(&message)).expect("decode failure");
+        let expected = TxIn {
+            transaction_id: TxId(Hash32([7; 32])),
+            index: TxIx(3),
+        };
+        assert_eq!(
+            failure,
+            shelley::LedgerPredicateFailure::UtxowFailure(
+                shelley::UtxowPredicateFailure::UtxoFailure(
+                    shelley::UtxoPredicateFailure::BadInputsUTxO {
+                        invalid_inputs: BTreeSet::from([expected]),
+                    }
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn decodes_shelley_expired_ttl() {
+        // UtxowFailure (UtxoFailure (ExpiredUTxO (Mismatch 1000 2000))), as
+        // sent by a Shelley node: the mismatch is a nested two-element record.
+        let bytes = [
+            0x82, 0x00, 0x82, 0x04, 0x82, 0x01, 0x82, 0x19, 0x03, 0xe8, 0x19, 0x07, 0xd0,
+        ];
+
+        let failure: shelley::LedgerPredicateFailure<shelley::UtxoPredicateFailure<Shelley>> =
+            decode_failure_bytes(&bytes).expect("decode failure");
+        match failure {
+            shelley::LedgerPredicateFailure::UtxowFailure(
+                shelley::UtxowPredicateFailure::UtxoFailure(
+                    shelley::UtxoPredicateFailure::ExpiredUTxO { ttl_mismatch },
+                ),
+            ) => {
+                assert_eq!(ttl_mismatch.supplied, SlotNo(1000));
+                assert_eq!(ttl_mismatch.expected, SlotNo(2000));
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decodes_conway_translation_failure() {
+        // CollectErrors [BadTranslation (BabbageContextError
+        //   (AlonzoContextError (TranslationLogicMissingInput txIn)))]
+        let tx_in = Value::Array(vec![Value::Bytes(vec![1; 32]), uint(0)]);
+        let message = Value::Array(vec![
+            uint(1),
+            Value::Array(vec![Value::Array(vec![
+                uint(3),
+                Value::Array(vec![uint(8), Value::Array(vec![uint(1), tx_in])]),
+            ])]),
+        ]);
+
+        let failure: conway::UtxosPredicateFailure<Conway> =
+            decode_failure_bytes(&encode(&message)).expect("decode failure");
+        let missing = alonzo::ContextError::TranslationLogicMissingInput {
+            input: TxIn {
+                transaction_id: TxId(Hash32([1; 32])),
+                index: TxIx(0),
+            },
+        };
+        assert_eq!(
+            failure,
+            conway::UtxosPredicateFailure::CollectErrors {
+                errors: vec![alonzo::CollectError::BadTranslation(
+                    conway::ContextError::BabbageContextError(
+                        babbage::ContextError::AlonzoContextError(missing)
+                    )
+                )],
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_babbage_translation_failure() {
+        // AlonzoInBabbageUtxoPredFailure (UtxosFailure (CollectErrors
+        //   [BadTranslation (ReferenceInputsNotSupported {txIn})]))
+        let tx_in = Value::Array(vec![Value::Bytes(vec![1; 32]), uint(0)]);
+        let message = Value::Array(vec![
+            uint(1),
+            Value::Array(vec![
+                uint(7),
+                Value::Array(vec![
+                    uint(1),
+                    Value::Array(vec![Value::Array(vec![
+                        uint(3),
+                        Value::Array(vec![uint(6), Value::Array(vec![tx_in])]),
+                    ])]),
+                ]),
+            ]),
+        ]);
+
+        let failure: babbage::UtxoPredicateFailure<Babbage> =
+            decode_failure_bytes(&encode(&message)).expect("decode failure");
+        let inputs = BTreeSet::from([TxIn {
+            transaction_id: TxId(Hash32([1; 32])),
+            index: TxIx(0),
+        }]);
+        assert_eq!(
+            failure,
+            babbage::UtxoPredicateFailure::AlonzoInBabbage(
+                alonzo::UtxoPredicateFailure::UtxosFailure(
+                    alonzo::UtxosPredicateFailure::CollectErrors {
+                        errors: vec![alonzo::CollectError::BadTranslation(
+                            babbage::ContextError::ReferenceInputsNotSupported { inputs }
+                        )],
+                    }
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_constructor() {
+        let message = Value::Array(vec![uint(42)]);
+        let err = decode_failure_bytes::<conway::LedgerPredicateFailure<Conway>>(&encode(&message))
+            .expect_err("tag 42 is not a ledger failure");
+        assert!(matches!(
+            err.cause(),
+            ParseError::UnknownConstructor { tag: 42, .. }
+        ));
+        assert_eq!(
+            err.context().map(|context| context.path_string()),
+            Some("LedgerPredicateFailure.tag".to_owned())
+        );
+    }
+
+    #[test]
+    fn decodes_inline_plutus_datum() {
+        // Constr 1 [I -3, B <70 bytes>, Map [(I 2^64, List [])]] as an inline
+        // datum; the byte string is split into chunks of 64 and 6 bytes.
+        let mut datum = vec![0xd8, 0x7a, 0x9f, 0x22, 0x5f, 0x58, 0x40];
+        datum.extend([0xab; 64]);
+        datum.extend([0x46, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xff]);
+        datum.extend([0xa1, 0xc2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0x80, 0xff]);
+        let option = Value::Array(vec![uint(1), Value::Tag(24, Box::new(Value::Bytes(datum)))]);
+        let term = decode_term(encode(&option).as_slice()).expect("decode term");
+
+        let expected = PlutusData::Constr {
+            tag: 1,
+            fields: vec![
+                PlutusData::Integer(BigInt::Int(-3)),
+                PlutusData::Bytes(vec![0xab; 70]),
+                PlutusData::Map(BTreeMap::from([(
+                    PlutusData::Integer(BigInt::BigUInt(BoundedBytes(vec![
+                        1, 0, 0, 0, 0, 0, 0, 0, 0,
+                    ]))),
+                    PlutusData::List(vec![]),
+                )])),
+            ],
+        };
+        assert_eq!(
+            DatumOption::from_term(&term).unwrap(),
+            DatumOption::Inline(expected)
+        );
+
+        // Constructor 200 needs the general form 102([200, []]).
+        let general = Value::Tag(
+            102,
+            Box::new(Value::Array(vec![uint(200), Value::Array(vec![])])),
+        );
+        let term = decode_term(encode(&general).as_slice()).expect("decode term");
+        assert_eq!(
+            PlutusData::from_term(&term).unwrap(),
+            PlutusData::Constr {
+                tag: 200,
+                fields: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn checks_interval_bounds() {
+        let rational = |numerator, denominator| {
+            Term::Tagged(
+                30,
+                Box::new(Term::Array(vec![
+                    Term::Unsigned(numerator),
+                    Term::Unsigned(denominator),
+                ])),
+            )
+        };
+
+        let quorum = UnitInterval::from_term(&rational(2, 3)).expect("two thirds");
+        assert_eq!(quorum.to_string(), "0.666666…");
+        assert!(quorum > UnitInterval::HALF);
+        assert!(UnitInterval::from_term(&rational(3, 2)).is_err());
+        assert!(UnitInterval::from_term(&rational(1, 0)).is_err());
+
+        let price = NonNegativeInterval::from_term(&rational(3, 2)).expect("three halves");
+        assert_eq!(price.to_string(), "1.5");
+        assert_eq!((price.numerator(), price.denominator()), (3, 2));
+        assert!(NonNegativeInterval::from(quorum) < price);
+    }
+
+    #[test]
+    fn decodes_purposes_and_certificates_per_era() {
+        let hash = |byte, len| Term::Bytes(vec![byte; len]);
+        let key = Term::Array(vec![Term::Unsigned(0), hash(0x11, 28)]);
+
+        // Voting and proposing only exist from Conway on.
+        let voting = Term::Array(vec![Term::Unsigned(4), key.clone()]);
+        assert!(PlutusPurposeItemStruct::from_term(&voting).is_ok());
+        assert!(AlonzoPlutusPurposeItem::from_term(&voting).is_err());
+        let pointer = Term::Array(vec![Term::Unsigned(5), Term::Unsigned(0)]);
+        assert!(PlutusPurposeStruct::from_term(&pointer).is_ok());
+        assert!(AlonzoPlutusPurpose::from_term(&pointer).is_err());
+
+        // GenesisDelegCert and MIRCert, which Conway dropped.
+        let genesis = Term::Array(vec![
+            Term::Unsigned(5),
+            hash(0x22, 28),
+            hash(0x33, 28),
+            hash(0x44, 32),
+        ]);
+        assert!(matches!(
+            ShelleyTxCert::from_term(&genesis),
+            Ok(ShelleyTxCert::GenesisDelegation { .. })
+        ));
+        assert!(PoolCert::from_term(&genesis).is_err());
+        let mir = Term::Array(vec![
+            Term::Unsigned(6),
+            Term::Array(vec![
+                Term::Unsigned(1),
+                Term::Map(vec![(key.clone(), Term::Unsigned(500))]),
+            ]),
+        ]);
+        let cert = ShelleyTxCert::from_term(&mir).expect("MIR certificate");
+        match &cert {
+            ShelleyTxCert::Mir(MIRCert {
+                pot: MIRPot::Treasury,
+                target: MIRTarget::StakeAddressesMIR(rewards),
+            }) => assert_eq!(rewards.values().collect::<Vec<_>>(), [&DeltaCoin(500)]),
+            other => panic!("unexpected certificate {other:?}"),
+        }
+        assert_eq!(cert.to_term(Version::BABBAGE).unwrap(), mir);
+        let to_pot = Term::Array(vec![
+            Term::Unsigned(6),
+            Term::Array(vec![Term::Unsigned(0), Term::Unsigned(1_000)]),
+        ]);
+        assert!(matches!(
+            ShelleyTxCert::from_term(&to_pot),
+            Ok(ShelleyTxCert::Mir(MIRCert {
+                target: MIRTarget::SendToOppositePotMIR(Coin(1_000)),
+                ..
+            }))
+        ));
+
+        // RegCert is Conway's.
+        let registration = Term::Array(vec![Term::Unsigned(7), key, Term::Unsigned(2_000_000)]);
+        assert!(PoolCert::from_term(&registration).is_ok());
+        assert!(ShelleyTxCert::from_term(&registration).is_err());
+    }
+
+    #[test]
+    fn decodes_dijkstra_payloads() {
+        let hash = || Term::Bytes(vec![0x42; 28]);
+        let script = Term::Array(vec![Term::Unsigned(1), hash()]);
+
+        let registration = Term::Array(vec![Term::Unsigned(0), script.clone()]);
+        assert!(PoolCert::from_term(&registration).is_ok());
+        assert!(matches!(
+            dijkstra::TxCert::from_term(&registration),
+            Err(ParseError::Malformed(
+                "certificates without deposits are no longer supported"
+            ))
+        ));
+        let delegation = Term::Array(vec![Term::Unsigned(2), script.clone(), hash()]);
+        let cert = dijkstra::TxCert::from_term(&delegation).expect("stake delegation");
+        assert!(matches!(
+            cert.as_pool_cert(),
+            PoolCert::StakeDelegation { .. }
+        ));
+
+        // Guards only exist from Dijkstra on; earlier eras reject tag 6.
+        let guarding = Term::Array(vec![Term::Unsigned(6), hash()]);
+        assert_eq!(
+            dijkstra::PlutusPurposeItem::from_term(&guarding).expect("guarding purpose"),
+            dijkstra::PlutusPurposeItem::Guarding(ScriptHash(Hash28([0x42; 28])))
+        );
+        assert!(PlutusPurposeItemStruct::from_term(&guarding).is_err());
+        let pointer = Term::Array(vec![Term::Unsigned(6), Term::Unsigned(0)]);
+        assert!(dijkstra::PlutusPurpose::from_term(&pointer).is_ok());
+        assert!(PlutusPurposeStruct::from_term(&pointer).is_err());
+        let guard = Term::Array(vec![Term::Unsigned(6), script]);
+        assert_eq!(
+            dijkstra::NativeScript::from_term(&guard).expect("guard script"),
+            dijkstra::NativeScript::RequireGuard(Credential::ScriptHash(ScriptHash(Hash28(
+                [0x42; 28]
+            ))))
+        );
+        assert!(NativeScript::from_term(&guard).is_err());
+
+        let v4 = Term::Array(vec![Term::Unsigned(4), Term::Bytes(vec![0x4d; 4])]);
+        assert_eq!(
+            dijkstra::Script::from_term(&v4).expect("Plutus V4 script"),
+            dijkstra::Script::PlutusV4(vec![0x4d; 4])
+        );
+        assert!(Script::from_term(&v4).is_err());
+        let v3 = Term::Array(vec![Term::Unsigned(3), Term::Bytes(vec![0x4d; 4])]);
+        assert_eq!(
+            dijkstra::Script::from_term(&v3).expect("Plutus V3 script"),
+            dijkstra::Script::Plutus(PlutusScript::V3(vec![0x4d; 4]))
+        );
+        assert_eq!(
+            Language::from_term(&Term::Unsigned(3)).expect("Plutus V4"),
+            Language::PlutusV4
+        );
+    }
+
+    #[test]
+    fn decodes_dijkstra_context_errors() {
+        // CertificateNotSupported, once with a certificate Dijkstra dropped.
+        let script = || Value::Array(vec![uint(1), Value::Bytes(vec![0x42; 28])]);
+        let delegation = Value::Array(vec![
+            uint(9),
+            Value::Array(vec![uint(2), script(), Value::Bytes(vec![0x42; 28])]),
+        ]);
+        let registration = Value::Array(vec![uint(9), Value::Array(vec![uint(0), script()])]);
+
+        let error: dijkstra::ContextError<Dijkstra> =
+            decode_failure_bytes(&encode(&delegation)).expect("decode context error");
+        match error {
+            conway::ContextError::CertificateNotSupported { certificate } => assert!(matches!(
+                certificate.as_pool_cert(),
+                PoolCert::StakeDelegation { .. }
+            )),
+            other => panic!("unexpected error {other:?}"),
+        }
+        assert!(
+            decode_failure_bytes::<dijkstra::ContextError<Dijkstra>>(&encode(&registration))
+                .is_err()
+        );
+        assert!(
+            decode_failure_bytes::<conway::ContextError<Conway>>(&encode(&registration)).is_ok()
+        );
+    }
+
+    #[test]
+    fn decodes_conway_block_body_failures() {
+        // BodyRefScriptsSizeTooBig (Mismatch 1048577 1048576), flattened.
+        let oversized = Value::Array(vec![uint(4), uint(1_048_577), uint(1_048_576)]);
+        // LedgersFailure (LedgerFailure (ConwayUtxowFailure (UtxoFailure
+        // InputSetEmptyUTxO))), the LEDGERS layer adding no tag of its own.
+        let ledgers = Value::Array(vec![
+            uint(2),
+            Value::Array(vec![
+                uint(1),
+                Value::Array(vec![uint(0), Value::Array(vec![uint(4)])]),
+            ]),
+        ]);
+
+        let failure: conway::BbodyPredicateFailure<Conway> =
+            decode_failure_bytes(&encode(&oversized)).expect("decode size failure");
+        match failure {
+            conway::BbodyPredicateFailure::BodyRefScriptsSizeTooBig { size_mismatch } => {
+                assert_eq!(size_mismatch.supplied, 1_048_577);
+                assert!(!size_mismatch.holds());
+            }
+            other => panic!("unexpected failure {other:?}"),
+        }
+
+        let failure: conway::BbodyPredicateFailure<Conway> =
+            decode_failure_bytes(&encode(&ledgers)).expect("decode ledgers failure");
+        assert_eq!(
+            failure,
+            conway::BbodyPredicateFailure::LedgersFailure(Box::new(
+                shelley::LedgersPredicateFailure::LedgersFailure(
+                    conway::LedgerPredicateFailure::ConwayUtxowFailure(
+                        conway::UtxowPredicateFailure::UtxoFailure(
+                            conway::UtxoPredicateFailure::InputSetEmptyUTxO,
+                        ),
+                    ),
+                ),
+            ))
+        );
+    }
+}
@@ -0,0 +1,2205 @@
+//! CBOR encoding of the predicate failure enums defined in
+//! `rust_rule_errors.rs`.
+//!
+//! This is the inverse of `failure_decoder.rs`: every constructor is written
+//! as the tagged sum the Haskell node emits, using the same per-constructor
+//! `Mismatch` layout (nested, grouped or swapped).  Like the Haskell encoders,
+//! the output depends on the protocol version being encoded for, which decides
+//! for example whether sets carry the tag 258 introduced with Conway.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::era::*;
+use crate::error_parser::{magnitude, write_head, Term};
+use crate::rust_rule_errors::*;
+
+/// Major protocol version the encoders branch on, mirroring
+/// `ifEncodingVersionAtLeast` on the Haskell side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version(pub u16);
+
+impl Version {
+    pub const SHELLEY: Version = Version(2);
+    pub const ALLEGRA: Version = Version(3);
+    pub const MARY: Version = Version(4);
+    pub const ALONZO: Version = Version(5);
+    pub const BABBAGE: Version = Version(7);
+    pub const CONWAY: Version = Version(9);
+}
+
+/// Error returned while encoding a predicate failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The payload type has no CBOR encoder yet.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::Unsupported(what) => write!(f, "encoding {what} is not supported yet"),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Encoding of a value into a single CBOR term.
+pub trait ToTerm {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError>;
+}
+
+/// Encode a predicate failure into the bytes the Haskell node would send.
+///
+/// # Errors
+///
+/// Returns [`EncodeError::Unsupported`] if the failure carries a payload that
+/// cannot be encoded yet.
+pub fn encode_failure<T: ToTerm>(failure: &T, version: Version) -> Result<Vec<u8>, EncodeError> {
+    failure.to_term(version).map(|term| term.to_bytes())
+}
+
+// ---------------------------------------------------------------------------
+// Constructor builder
+// ---------------------------------------------------------------------------
+
+/// Builder for the `[tag, field, ...]` layout of one tagged-sum constructor.
+struct Sum {
+    version: Version,
+    items: Vec<Term>,
+}
+
+impl Sum {
+    fn new(tag: u64, version: Version) -> Self {
+        Sum {
+            version,
+            items: vec![Term::Unsigned(tag)],
+        }
+    }
+
+    fn field<T: ToTerm + ?Sized>(mut self, value: &T) -> Result<Self, EncodeError> {
+        self.items.push(value.to_term(self.version)?);
+        Ok(self)
+    }
+
+    /// A field written as `null` when absent (`encodeNullStrictMaybe`).
+    fn nullable<T: ToTerm>(mut self, value: &Option<T>) -> Result<Self, EncodeError> {
+        self.items.push(nullable(value, self.version)?);
+        Ok(self)
+    }
+
+    /// A `Mismatch` nested as a `[supplied, expected]` record.
+    fn mismatch<R, T: ToTerm>(self, mismatch: &Mismatch<R, T>) -> Result<Self, EncodeError> {
+        let record = Term::Array(vec![
+            mismatch.supplied.to_term(self.version)?,
+            mismatch.expected.to_term(self.version)?,
+        ]);
+        self.term(record)
+    }
+
+    /// A `Mismatch` flattened into the constructor as `supplied, expected`.
+    fn grouped_mismatch<R, T: ToTerm>(
+        self,
+        mismatch: &Mismatch<R, T>,
+    ) -> Result<Self, EncodeError> {
+        self.field(&mismatch.supplied)?.field(&mismatch.expected)
+    }
+
+    /// A `Mismatch` flattened into the constructor as `expected, supplied`.
+    fn swapped_mismatch<R, T: ToTerm>(
+        self,
+        mismatch: &Mismatch<R, T>,
+    ) -> Result<Self, EncodeError> {
+        self.field(&mismatch.expected)?.field(&mismatch.supplied)
+    }
+
+    fn term(mut self, term: Term) -> Result<Self, EncodeError> {
+        self.items.push(term);
+        Ok(self)
+    }
+
+    fn build(self) -> Result<Term, EncodeError> {
+        Ok(Term::Array(self.items))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Primitive and container payloads
+// ---------------------------------------------------------------------------
+
+fn nullable<T: ToTerm>(value: &Option<T>, version: Version) -> Result<Term, EncodeError> {
+    match value {
+        Some(value) => value.to_term(version),
+        None => Ok(Term::Null),
+    }
+}
+
+fn list<'a, T: ToTerm + 'a>(
+    items: impl IntoIterator<Item = &'a T>,
+    version: Version,
+) -> Result<Term, EncodeError> {
+    items
+        .into_iter()
+        .map(|item| item.to_term(version))
+        .collect::<Result<_, _>>()
+        .map(Term::Array)
+}
+
+macro_rules! unsigned_to_term {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ToTerm for $ty {
+                fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+                    Ok(Term::Unsigned(*self as u64))
+                }
+            }
+        )*
+    };
+}
+
+unsigned_to_term!(u16, u32, u64, usize);
+
+macro_rules! signed_to_term {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ToTerm for $ty {
+                fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+                    Ok(Term::integer(i128::from(*self)))
+                }
+            }
+        )*
+    };
+}
+
+signed_to_term!(i32, i64, i128);
+
+impl ToTerm for bool {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Bool(*self))
+    }
+}
+
+impl ToTerm for String {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Text(self.clone()))
+    }
+}
+
+impl ToTerm for Vec<u8> {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Bytes(self.clone()))
+    }
+}
+
+impl<const N: usize> ToTerm for [u8; N] {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Bytes(self.to_vec()))
+    }
+}
+
+/// `StrictMaybe` is encoded as a list of zero or one element.
+impl<T: ToTerm> ToTerm for Option<T> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        list(self.iter(), version)
+    }
+}
+
+impl<T: ToTerm> ToTerm for Box<T> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        T::to_term(self, version)
+    }
+}
+
+impl<T: ToTerm> ToTerm for Vec<T> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        list(self, version)
+    }
+}
+
+impl<T: ToTerm> ToTerm for NonEmpty<T> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        list(self.as_slice(), version)
+    }
+}
+
+/// Sets are prefixed with tag 258 from Conway onwards.
+impl<T: ToTerm> ToTerm for BTreeSet<T> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        let items = list(self, version)?;
+        if version >= Version::CONWAY {
+            Ok(Term::Tagged(258, Box::new(items)))
+        } else {
+            Ok(items)
+        }
+    }
+}
+
+/// Ordered sets keep their insertion order but are tagged like sets.
+impl<T: ToTerm> ToTerm for OSet<T> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        let items = list(&self.0, version)?;
+        if version >= Version::CONWAY {
+            Ok(Term::Tagged(258, Box::new(items)))
+        } else {
+            Ok(items)
+        }
+    }
+}
+
+impl<K: ToTerm, V: ToTerm> ToTerm for BTreeMap<K, V> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        self.iter()
+            .map(|(key, value)| Ok((key.to_term(version)?, value.to_term(version)?)))
+            .collect::<Result<_, _>>()
+            .map(Term::Map)
+    }
+}
+
+impl<A: ToTerm, B: ToTerm> ToTerm for (A, B) {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Array(vec![
+            self.0.to_term(version)?,
+            self.1.to_term(version)?,
+        ]))
+    }
+}
+
+impl<A: ToTerm, B: ToTerm, C: ToTerm> ToTerm for (A, B, C) {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Array(vec![
+            self.0.to_term(version)?,
+            self.1.to_term(version)?,
+            self.2.to_term(version)?,
+        ]))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helper stand-ins
+// ---------------------------------------------------------------------------
+
+macro_rules! newtype_to_term {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl ToTerm for $ty {
+                fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+                    self.0.to_term(version)
+                }
+            }
+        )*
+    };
+}
+
+newtype_to_term!(
+    Coin,
+    DeltaCoin,
+    SlotNo,
+    EpochNo,
+    TxIx,
+    EpochInterval,
+    GovActionIx,
+    Address,
+    RewardAccount,
+    Hash28,
+    Hash32,
+    ScriptHash,
+    DataHash,
+    ScriptIntegrityHash,
+    TxAuxDataHash,
+    TxId,
+    AddrKeyHash,
+    KeyHash,
+    PoolKeyHash,
+    VRFKeyHash,
+    VerificationKey,
+    Withdrawals,
+    AssetName,
+    MultiAsset,
+);
+
+impl ToTerm for NetworkId {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Unsigned(u64::from(self.0)))
+    }
+}
+
+impl ToTerm for TxIn {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (&self.transaction_id, &self.index).to_term(version)
+    }
+}
+
+impl<T: ToTerm + ?Sized> ToTerm for &T {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (**self).to_term(version)
+    }
+}
+
+impl ToTerm for ProtVer {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (self.major, self.minor).to_term(version)
+    }
+}
+
+impl ToTerm for ExUnits {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (self.mem, self.steps).to_term(version)
+    }
+}
+
+impl ToTerm for ValidityInterval {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (&self.invalid_before, &self.invalid_hereafter).to_term(version)
+    }
+}
+
+impl ToTerm for MIRPot {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Unsigned(match self {
+            MIRPot::Reserves => 0,
+            MIRPot::Treasury => 1,
+        }))
+    }
+}
+
+impl ToTerm for MIRTarget {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            MIRTarget::StakeAddressesMIR(rewards) => rewards.to_term(version),
+            MIRTarget::SendToOppositePotMIR(coin) => coin.to_term(version),
+        }
+    }
+}
+
+impl ToTerm for MIRCert {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Array(vec![
+            self.pot.to_term(version)?,
+            self.target.to_term(version)?,
+        ]))
+    }
+}
+
+impl ToTerm for Credential {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            Credential::KeyHash(hash) => Sum::new(0, version).field(hash)?.build(),
+            Credential::ScriptHash(hash) => Sum::new(1, version).field(hash)?.build(),
+        }
+    }
+}
+
+impl ToTerm for VoterEnum {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            VoterEnum::CommitteeKey(hash) => Sum::new(0, version).field(hash)?.build(),
+            VoterEnum::CommitteeScript(hash) => Sum::new(1, version).field(hash)?.build(),
+            VoterEnum::DRepKey(hash) => Sum::new(2, version).field(hash)?.build(),
+            VoterEnum::DRepScript(hash) => Sum::new(3, version).field(hash)?.build(),
+            VoterEnum::StakePool(hash) => Sum::new(4, version).field(hash)?.build(),
+        }
+    }
+}
+
+impl ToTerm for DRep {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            DRep::KeyHash(hash) => Sum::new(0, version).field(hash)?.build(),
+            DRep::ScriptHash(hash) => Sum::new(1, version).field(hash)?.build(),
+            DRep::Abstain => Sum::new(2, version).build(),
+            DRep::NoConfidence => Sum::new(3, version).build(),
+        }
+    }
+}
+
+impl ToTerm for Relay {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            Relay::SingleHostAddr { port, ipv4, ipv6 } => Sum::new(0, version)
+                .nullable(port)?
+                .nullable(ipv4)?
+                .nullable(ipv6)?
+                .build(),
+            Relay::SingleHostName { port, dns_name } => Sum::new(1, version)
+                .nullable(port)?
+                .field(dns_name)?
+                .build(),
+            Relay::MultiHostName { dns_name } => Sum::new(2, version).field(dns_name)?.build(),
+        }
+    }
+}
+
+impl ToTerm for PoolMetadata {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (&self.url, &self.metadata_hash).to_term(version)
+    }
+}
+
+/// Certificates are flat sums; pool registrations inline the pool parameters.
+impl ToTerm for PoolCert {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            PoolCert::StakeRegistration { credential } => {
+                Sum::new(0, version).field(credential)?.build()
+            }
+            PoolCert::StakeDeregistration { credential } => {
+                Sum::new(1, version).field(credential)?.build()
+            }
+            PoolCert::StakeDelegation { credential, pool } => {
+                Sum::new(2, version).field(credential)?.field(pool)?.build()
+            }
+            PoolCert::PoolRegistration { params } => Sum::new(3, version)
+                .field(&params.operator)?
+                .field(&params.vrf_keyhash)?
+                .field(&params.pledge)?
+                .field(&params.cost)?
+                .field(&params.margin)?
+                .field(&params.reward_account)?
+                .field(&params.owners)?
+                .field(&params.relays)?
+                .nullable(&params.metadata)?
+                .build(),
+            PoolCert::PoolRetirement { pool, epoch } => {
+                Sum::new(4, version).field(pool)?.field(epoch)?.build()
+            }
+            PoolCert::RegCert { credential, coin } => {
+                Sum::new(7, version).field(credential)?.field(coin)?.build()
+            }
+            PoolCert::UnregCert { credential, coin } => {
+                Sum::new(8, version).field(credential)?.field(coin)?.build()
+            }
+            PoolCert::VoteDelegCert { credential, drep } => {
+                Sum::new(9, version).field(credential)?.field(drep)?.build()
+            }
+            PoolCert::StakeVoteDelegCert {
+                credential,
+                pool,
+                drep,
+            } => Sum::new(10, version)
+                .field(credential)?
+                .field(pool)?
+                .field(drep)?
+                .build(),
+            PoolCert::StakeRegDelegCert {
+                credential,
+                pool,
+                deposit,
+            } => Sum::new(11, version)
+                .field(credential)?
+                .field(pool)?
+                .field(deposit)?
+                .build(),
+            PoolCert::VoteRegDelegCert {
+                credential,
+                drep,
+                deposit,
+            } => Sum::new(12, version)
+                .field(credential)?
+                .field(drep)?
+                .field(deposit)?
+                .build(),
+            PoolCert::StakeVoteRegDelegCert {
+                credential,
+                pool,
+                drep,
+                deposit,
+            } => Sum::new(13, version)
+                .field(credential)?
+                .field(pool)?
+                .field(drep)?
+                .field(deposit)?
+                .build(),
+            PoolCert::AuthCommitteeHotCert { cold, hot } => {
+                Sum::new(14, version).field(cold)?.field(hot)?.build()
+            }
+            PoolCert::ResignCommitteeColdCert { cold, anchor } => {
+                Sum::new(15, version).field(cold)?.nullable(anchor)?.build()
+            }
+            PoolCert::RegDRepCert {
+                credential,
+                deposit,
+                anchor,
+            } => Sum::new(16, version)
+                .field(credential)?
+                .field(deposit)?
+                .nullable(anchor)?
+                .build(),
+            PoolCert::UnregDRepCert {
+                credential,
+                deposit,
+            } => Sum::new(17, version)
+                .field(credential)?
+                .field(deposit)?
+                .build(),
+            PoolCert::UpdateDRepCert { credential, anchor } => Sum::new(18, version)
+                .field(credential)?
+                .nullable(anchor)?
+                .build(),
+        }
+    }
+}
+
+impl ToTerm for ShelleyTxCert {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            ShelleyTxCert::Shared(cert) => cert.to_term(version),
+            ShelleyTxCert::GenesisDelegation {
+                genesis,
+                delegate,
+                vrf,
+            } => Sum::new(5, version)
+                .field(genesis)?
+                .field(delegate)?
+                .field(vrf)?
+                .build(),
+            ShelleyTxCert::Mir(mir) => Sum::new(6, version).field(mir)?.build(),
+        }
+    }
+}
+
+impl ToTerm for dijkstra::TxCert {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        self.as_pool_cert().to_term(version)
+    }
+}
+
+impl ToTerm for VotingProcedure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Array(vec![
+            Term::Unsigned(u64::from(self.vote)),
+            nullable(&self.anchor, version)?,
+        ]))
+    }
+}
+
+impl ToTerm for VotingProceduresStruct {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        self.0.to_term(version)
+    }
+}
+
+impl ToTerm for GovActionId {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (&self.tx_id, &self.action_index).to_term(version)
+    }
+}
+
+impl ToTerm for Anchor {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (&self.url, &self.data_hash).to_term(version)
+    }
+}
+
+impl ToTerm for Constitution {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Array(vec![
+            self.anchor.to_term(version)?,
+            nullable(&self.script_hash, version)?,
+        ]))
+    }
+}
+
+impl ToTerm for CostModels {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        self.0
+            .iter()
+            .map(|(language, model)| {
+                Ok((
+                    Term::Unsigned(u64::from(*language)),
+                    model.to_term(version)?,
+                ))
+            })
+            .collect::<Result<_, _>>()
+            .map(Term::Map)
+    }
+}
+
+impl ToTerm for ExUnitPrices {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        (&self.mem_price, &self.step_price).to_term(version)
+    }
+}
+
+/// Bounded ratios are always written with tag 30.
+fn ratio(value: Rational) -> Term {
+    Term::Tagged(
+        30,
+        Box::new(Term::Array(vec![
+            Term::Unsigned(value.numerator()),
+            Term::Unsigned(value.denominator()),
+        ])),
+    )
+}
+
+impl ToTerm for UnitInterval {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(ratio(self.to_rational()))
+    }
+}
+
+impl ToTerm for NonNegativeInterval {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(ratio(self.to_rational()))
+    }
+}
+
+impl ToTerm for PoolVotingThresholds {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        list(
+            [
+                &self.motion_no_confidence,
+                &self.committee_normal,
+                &self.committee_no_confidence,
+                &self.hard_fork_initiation,
+                &self.security_parameter,
+            ],
+            version,
+        )
+    }
+}
+
+impl ToTerm for DRepVotingThresholds {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        list(
+            [
+                &self.motion_no_confidence,
+                &self.committee_normal,
+                &self.committee_no_confidence,
+                &self.update_constitution,
+                &self.hard_fork_initiation,
+                &self.pparam_network,
+                &self.pparam_economic,
+                &self.pparam_technical,
+                &self.pparam_governance,
+                &self.treasury_withdrawal,
+            ],
+            version,
+        )
+    }
+}
+
+/// `protocol_param_update` is a map keyed by the CDDL field numbers.
+impl ToTerm for ProtocolParamUpdate {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        fn entry<T: ToTerm>(
+            entries: &mut Vec<(Term, Term)>,
+            key: u64,
+            value: &Option<T>,
+            version: Version,
+        ) -> Result<(), EncodeError> {
+            if let Some(value) = value {
+                entries.push((Term::Unsigned(key), value.to_term(version)?));
+            }
+            Ok(())
+        }
+
+        let mut entries = Vec::new();
+        entry(&mut entries, 0, &self.minfee_a, version)?;
+        entry(&mut entries, 1, &self.minfee_b, version)?;
+        entry(&mut entries, 2, &self.max_block_body_size, version)?;
+        entry(&mut entries, 3, &self.max_tx_size, version)?;
+        entry(&mut entries, 4, &self.max_block_header_size, version)?;
+        entry(&mut entries, 5, &self.key_deposit, version)?;
+        entry(&mut entries, 6, &self.pool_deposit, version)?;
+        entry(&mut entries, 7, &self.max_epoch, version)?;
+        entry(&mut entries, 8, &self.desired_number_of_pools, version)?;
+        entry(&mut entries, 9, &self.pool_pledge_influence, version)?;
+        entry(&mut entries, 10, &self.expansion_rate, version)?;
+        entry(&mut entries, 11, &self.treasury_growth_rate, version)?;
+        entry(&mut entries, 16, &self.min_pool_cost, version)?;
+        entry(&mut entries, 17, &self.ada_per_utxo_byte, version)?;
+        entry(&mut entries, 18, &self.cost_models, version)?;
+        entry(&mut entries, 19, &self.ex_unit_prices, version)?;
+        entry(&mut entries, 20, &self.max_tx_ex_units, version)?;
+        entry(&mut entries, 21, &self.max_block_ex_units, version)?;
+        entry(&mut entries, 22, &self.max_value_size, version)?;
+        entry(&mut entries, 23, &self.collateral_percentage, version)?;
+        entry(&mut entries, 24, &self.max_collateral_inputs, version)?;
+        entry(&mut entries, 25, &self.pool_voting_thresholds, version)?;
+        entry(&mut entries, 26, &self.drep_voting_thresholds, version)?;
+        entry(&mut entries, 27, &self.min_committee_size, version)?;
+        entry(&mut entries, 28, &self.committee_term_limit, version)?;
+        entry(
+            &mut entries,
+            29,
+            &self.governance_action_validity_period,
+            version,
+        )?;
+        entry(&mut entries, 30, &self.governance_action_deposit, version)?;
+        entry(&mut entries, 31, &self.drep_deposit, version)?;
+        entry(&mut entries, 32, &self.drep_inactivity_period, version)?;
+        entry(&mut entries, 33, &self.ref_script_coins_per_byte, version)?;
+        Ok(Term::Map(entries))
+    }
+}
+
+fn previous_action(
+    previous: &Option<GovPurposeIdStruct>,
+    version: Version,
+) -> Result<Term, EncodeError> {
+    nullable(&previous.as_ref().map(|previous| &previous.id), version)
+}
+
+impl ToTerm for GovActionStruct {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            GovActionStruct::ParameterChange {
+                previous,
+                update,
+                policy_hash,
+            } => Sum::new(0, version)
+                .term(previous_action(previous, version)?)?
+                .field(update)?
+                .nullable(policy_hash)?
+                .build(),
+            GovActionStruct::HardForkInitiation {
+                previous,
+                protocol_version,
+            } => Sum::new(1, version)
+                .term(previous_action(previous, version)?)?
+                .field(protocol_version)?
+                .build(),
+            GovActionStruct::TreasuryWithdrawals {
+                withdrawals,
+                policy_hash,
+            } => Sum::new(2, version)
+                .field(withdrawals)?
+                .nullable(policy_hash)?
+                .build(),
+            GovActionStruct::NoConfidence { previous } => Sum::new(3, version)
+                .term(previous_action(previous, version)?)?
+                .build(),
+            GovActionStruct::UpdateCommittee {
+                previous,
+                removals,
+                additions,
+                new_quorum,
+            } => Sum::new(4, version)
+                .term(previous_action(previous, version)?)?
+                .field(removals)?
+                .field(additions)?
+                .field(new_quorum)?
+                .build(),
+            GovActionStruct::NewConstitution {
+                previous,
+                constitution,
+            } => Sum::new(5, version)
+                .term(previous_action(previous, version)?)?
+                .field(constitution)?
+                .build(),
+            GovActionStruct::InfoAction => Sum::new(6, version).build(),
+        }
+    }
+}
+
+impl ToTerm for ProposalProcedureStruct {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Array(vec![
+            self.deposit.to_term(version)?,
+            self.reward_account.to_term(version)?,
+            self.action.to_term(version)?,
+            self.anchor.to_term(version)?,
+        ]))
+    }
+}
+
+impl ToTerm for PlutusPurposeStruct {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        let tag: u64 = match self.tag {
+            RedeemerTag::Spend => 0,
+            RedeemerTag::Mint => 1,
+            RedeemerTag::Cert => 2,
+            RedeemerTag::Reward => 3,
+            RedeemerTag::Voting => 4,
+            RedeemerTag::Proposing => 5,
+        };
+        (tag, self.index).to_term(version)
+    }
+}
+
+impl ToTerm for AlonzoPlutusPurpose {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        let tag: u64 = match self.tag {
+            AlonzoRedeemerTag::Spend => 0,
+            AlonzoRedeemerTag::Mint => 1,
+            AlonzoRedeemerTag::Cert => 2,
+            AlonzoRedeemerTag::Reward => 3,
+        };
+        (tag, self.index).to_term(version)
+    }
+}
+
+impl ToTerm for dijkstra::PlutusPurpose {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use dijkstra::RedeemerTag;
+        let tag: u64 = match self.tag {
+            RedeemerTag::Spend => 0,
+            RedeemerTag::Mint => 1,
+            RedeemerTag::Cert => 2,
+            RedeemerTag::Reward => 3,
+            RedeemerTag::Voting => 4,
+            RedeemerTag::Proposing => 5,
+            RedeemerTag::Guarding => 6,
+        };
+        (tag, self.index).to_term(version)
+    }
+}
+
+impl ToTerm for PlutusPurposeItemStruct {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            PlutusPurposeItemStruct::Spending(input) => Sum::new(0, version).field(input)?.build(),
+            PlutusPurposeItemStruct::Minting(policy) => Sum::new(1, version).field(policy)?.build(),
+            PlutusPurposeItemStruct::Certifying(cert) => Sum::new(2, version).field(cert)?.build(),
+            PlutusPurposeItemStruct::Rewarding(account) => {
+                Sum::new(3, version).field(account)?.build()
+            }
+            PlutusPurposeItemStruct::Voting(voter) => Sum::new(4, version).field(voter)?.build(),
+            PlutusPurposeItemStruct::Proposing(proposal) => {
+                Sum::new(5, version).field(proposal)?.build()
+            }
+        }
+    }
+}
+
+impl ToTerm for AlonzoPlutusPurposeItem {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use AlonzoPlutusPurposeItem::*;
+        match self {
+            Spending(input) => Sum::new(0, version).field(input)?.build(),
+            Minting(policy) => Sum::new(1, version).field(policy)?.build(),
+            Certifying(cert) => Sum::new(2, version).field(cert)?.build(),
+            Rewarding(account) => Sum::new(3, version).field(account)?.build(),
+        }
+    }
+}
+
+impl ToTerm for dijkstra::PlutusPurposeItem {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use dijkstra::PlutusPurposeItem::*;
+        match self {
+            Spending(input) => Sum::new(0, version).field(input)?.build(),
+            Minting(policy) => Sum::new(1, version).field(policy)?.build(),
+            Certifying(cert) => Sum::new(2, version).field(cert)?.build(),
+            Rewarding(account) => Sum::new(3, version).field(account)?.build(),
+            Voting(voter) => Sum::new(4, version).field(voter)?.build(),
+            Proposing(proposal) => Sum::new(5, version).field(proposal)?.build(),
+            Guarding(script) => Sum::new(6, version).field(script)?.build(),
+        }
+    }
+}
+
+impl ToTerm for TxOutSource {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            TxOutSource::FromInput(input) => Sum::new(0, version).field(input)?.build(),
+            TxOutSource::FromOutput(index) => Sum::new(1, version).field(index)?.build(),
+        }
+    }
+}
+
+impl ToTerm for Language {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Unsigned(match self {
+            Language::PlutusV1 => 0,
+            Language::PlutusV2 => 1,
+            Language::PlutusV3 => 2,
+            Language::PlutusV4 => 3,
+        }))
+    }
+}
+
+/// Plutus data is serialised the way `plutus-core` does it rather than the
+/// way the ledger encoders do: non-empty lists always use the
+/// indefinite-length encoding, integers beyond 64 bits become bignums and
+/// byte strings over 64 bytes are split into 64-byte chunks.  The result is
+/// written as raw bytes since a [`Term`] cannot express those choices.
+fn plutus_data_bytes(data: &PlutusData) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_plutus_data(data, &mut out);
+    out
+}
+
+fn write_plutus_data(data: &PlutusData, out: &mut Vec<u8>) {
+    match data {
+        PlutusData::Constr { tag, fields } => {
+            match u64::from(*tag) {
+                index @ 0..=6 => write_head(out, 6, 121 + index),
+                index @ 7..=127 => write_head(out, 6, 1280 + index - 7),
+                index => {
+                    write_head(out, 6, 102);
+                    write_head(out, 4, 2);
+                    write_head(out, 0, index);
+                }
+            }
+            write_plutus_list(fields, out);
+        }
+        PlutusData::Map(entries) => {
+            write_head(out, 5, entries.len() as u64);
+            for (key, value) in entries {
+                write_plutus_data(key, out);
+                write_plutus_data(value, out);
+            }
+        }
+        PlutusData::List(items) => write_plutus_list(items, out),
+        PlutusData::Integer(BigInt::Int(value)) => {
+            match (u64::try_from(*value), u64::try_from(-1 - *value)) {
+                (Ok(value), _) => write_head(out, 0, value),
+                (_, Ok(value)) => write_head(out, 1, value),
+                _ if *value < 0 => {
+                    write_head(out, 6, 3);
+                    write_bounded_bytes(&magnitude((-1 - *value) as u128), out);
+                }
+                _ => {
+                    write_head(out, 6, 2);
+                    write_bounded_bytes(&magnitude(*value as u128), out);
+                }
+            }
+        }
+        PlutusData::Integer(BigInt::BigUInt(bytes)) => {
+            write_head(out, 6, 2);
+            write_bounded_bytes(&bytes.0, out);
+        }
+        PlutusData::Integer(BigInt::BigNInt(bytes)) => {
+            write_head(out, 6, 3);
+            write_bounded_bytes(&bytes.0, out);
+        }
+        PlutusData::Bytes(bytes) => write_bounded_bytes(bytes, out),
+    }
+}
+
+fn write_plutus_list(items: &[PlutusData], out: &mut Vec<u8>) {
+    if items.is_empty() {
+        write_head(out, 4, 0);
+    } else {
+        out.push(0x9f);
+        items.iter().for_each(|item| write_plutus_data(item, out));
+        out.push(0xff);
+    }
+}
+
+fn write_bounded_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    if bytes.len() <= 64 {
+        write_head(out, 2, bytes.len() as u64);
+        out.extend_from_slice(bytes);
+    } else {
+        out.push(0x5f);
+        for chunk in bytes.chunks(64) {
+            write_head(out, 2, chunk.len() as u64);
+            out.extend_from_slice(chunk);
+        }
+        out.push(0xff);
+    }
+}
+
+impl ToTerm for DatumOption {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            DatumOption::Hash(hash) => Sum::new(0, version).field(hash)?.build(),
+            DatumOption::Inline(data) => {
+                let embedded = Term::Tagged(24, Box::new(Term::Bytes(plutus_data_bytes(data))));
+                Sum::new(1, version).term(embedded)?.build()
+            }
+        }
+    }
+}
+
+impl ToTerm for NativeScript {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            NativeScript::ScriptPubkey(hash) => Sum::new(0, version).field(hash)?.build(),
+            NativeScript::ScriptAll(scripts) => Sum::new(1, version).field(scripts)?.build(),
+            NativeScript::ScriptAny(scripts) => Sum::new(2, version).field(scripts)?.build(),
+            NativeScript::ScriptNOfK { required, scripts } => Sum::new(3, version)
+                .field(required)?
+                .field(scripts)?
+                .build(),
+            NativeScript::InvalidBefore(slot) => Sum::new(4, version).field(slot)?.build(),
+            NativeScript::InvalidHereafter(slot) => Sum::new(5, version).field(slot)?.build(),
+        }
+    }
+}
+
+impl ToTerm for dijkstra::NativeScript {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use dijkstra::NativeScript::*;
+        match self {
+            ScriptPubkey(hash) => Sum::new(0, version).field(hash)?.build(),
+            ScriptAll(scripts) => Sum::new(1, version).field(scripts)?.build(),
+            ScriptAny(scripts) => Sum::new(2, version).field(scripts)?.build(),
+            ScriptNOfK { required, scripts } => Sum::new(3, version)
+                .field(required)?
+                .field(scripts)?
+                .build(),
+            InvalidBefore(slot) => Sum::new(4, version).field(slot)?.build(),
+            InvalidHereafter(slot) => Sum::new(5, version).field(slot)?.build(),
+            RequireGuard(credential) => Sum::new(6, version).field(credential)?.build(),
+        }
+    }
+}
+
+impl ToTerm for Script {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            Script::Native(script) => Sum::new(0, version).field(script)?.build(),
+            Script::Plutus(PlutusScript::V1(bytes)) => Sum::new(1, version).field(bytes)?.build(),
+            Script::Plutus(PlutusScript::V2(bytes)) => Sum::new(2, version).field(bytes)?.build(),
+            Script::Plutus(PlutusScript::V3(bytes)) => Sum::new(3, version).field(bytes)?.build(),
+        }
+    }
+}
+
+impl ToTerm for dijkstra::Script {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            dijkstra::Script::Native(script) => Sum::new(0, version).field(script)?.build(),
+            dijkstra::Script::Plutus(script) => Script::Plutus(script.clone()).to_term(version),
+            dijkstra::Script::PlutusV4(bytes) => Sum::new(4, version).field(bytes)?.build(),
+        }
+    }
+}
+
+impl<S: ToTerm> ToTerm for ScriptRef<S> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        let script = self.0.to_term(version)?.to_bytes();
+        Ok(Term::Tagged(24, Box::new(Term::Bytes(script))))
+    }
+}
+
+impl ToTerm for ValueStruct {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            ValueStruct::Coin(coin) => coin.to_term(version),
+            ValueStruct::MultiAsset { coin, assets } => (coin, assets).to_term(version),
+        }
+    }
+}
+
+/// `Shelley` outputs use the legacy array form and `Babbage` outputs the map
+/// form, so that decoded outputs are written back the way they came.
+impl<E: Era> ToTerm for TxOutStruct<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            TxOutStruct::Shelley {
+                address,
+                amount,
+                datum_hash,
+            } => {
+                let mut items = vec![address.to_term(version)?, amount.to_term(version)?];
+                if let Some(hash) = datum_hash {
+                    items.push(hash.to_term(version)?);
+                }
+                Ok(Term::Array(items))
+            }
+            TxOutStruct::Babbage {
+                address,
+                amount,
+                datum_option,
+                script_ref,
+            } => {
+                let mut entries = vec![
+                    (Term::Unsigned(0), address.to_term(version)?),
+                    (Term::Unsigned(1), amount.to_term(version)?),
+                ];
+                if let Some(datum) = datum_option {
+                    entries.push((Term::Unsigned(2), datum.to_term(version)?));
+                }
+                if let Some(script) = script_ref {
+                    entries.push((Term::Unsigned(3), script.to_term(version)?));
+                }
+                Ok(Term::Map(entries))
+            }
+        }
+    }
+}
+
+impl ToTerm for UTxOStruct {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        self.0.to_term(version)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shelley era predicate failures
+// ---------------------------------------------------------------------------
+
+impl ToTerm for shelley::VotingPeriod {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Ok(Term::Unsigned(match self {
+            shelley::VotingPeriod::VoteForThisEpoch => 0,
+            shelley::VotingPeriod::VoteForNextEpoch => 1,
+        }))
+    }
+}
+
+impl ToTerm for shelley::PpupPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use shelley::PpupPredicateFailure::*;
+        match self {
+            NonGenesisUpdatePPUP { offending_keys } => {
+                Sum::new(0, version).mismatch(offending_keys)?.build()
+            }
+            PPUpdateWrongEpoch {
+                current_epoch,
+                declared_epoch,
+                voting_period,
+            } => Sum::new(1, version)
+                .field(current_epoch)?
+                .field(declared_epoch)?
+                .field(voting_period)?
+                .build(),
+            PVCannotFollowPPUP { proposed_version } => {
+                Sum::new(2, version).field(proposed_version)?.build()
+            }
+        }
+    }
+}
+
+impl<E: Era> ToTerm for shelley::UtxoPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use shelley::UtxoPredicateFailure::*;
+        match self {
+            BadInputsUTxO { invalid_inputs } => Sum::new(0, version).field(invalid_inputs)?.build(),
+            ExpiredUTxO { ttl_mismatch } => Sum::new(1, version).mismatch(ttl_mismatch)?.build(),
+            MaxTxSizeUTxO { size_mismatch } => {
+                Sum::new(2, version).mismatch(size_mismatch)?.build()
+            }
+            InputSetEmptyUTxO => Sum::new(3, version).build(),
+            FeeTooSmallUTxO { fee_mismatch } => {
+                Sum::new(4, version).mismatch(fee_mismatch)?.build()
+            }
+            ValueNotConservedUTxO { balance_mismatch } => {
+                Sum::new(5, version).mismatch(balance_mismatch)?.build()
+            }
+            OutputTooSmallUTxO { tiny_outputs } => {
+                Sum::new(6, version).field(tiny_outputs)?.build()
+            }
+            UpdateFailure(failure) => Sum::new(7, version).field(failure)?.build(),
+            WrongNetwork {
+                expected,
+                offending,
+            } => Sum::new(8, version)
+                .field(expected)?
+                .field(offending)?
+                .build(),
+            WrongNetworkWithdrawal {
+                expected,
+                offending,
+            } => Sum::new(9, version)
+                .field(expected)?
+                .field(offending)?
+                .build(),
+            OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs,
+            } => Sum::new(10, version)
+                .field(oversized_bootstrap_outputs)?
+                .build(),
+        }
+    }
+}
+
+impl<Utxo: ToTerm> ToTerm for shelley::UtxowPredicateFailure<Utxo> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use shelley::UtxowPredicateFailure::*;
+        match self {
+            InvalidWitnessesUTXOW { invalid_witnesses } => {
+                Sum::new(0, version).field(invalid_witnesses)?.build()
+            }
+            MissingVKeyWitnessesUTXOW { missing_signers } => {
+                Sum::new(1, version).field(missing_signers)?.build()
+            }
+            MissingScriptWitnessesUTXOW { missing_scripts } => {
+                Sum::new(2, version).field(missing_scripts)?.build()
+            }
+            ScriptWitnessNotValidatingUTXOW { failed_scripts } => {
+                Sum::new(3, version).field(failed_scripts)?.build()
+            }
+            UtxoFailure(failure) => Sum::new(4, version).field(failure)?.build(),
+            MIRInsufficientGenesisSigsUTXOW { missing_signatures } => {
+                Sum::new(5, version).field(missing_signatures)?.build()
+            }
+            MissingTxBodyMetadataHash { expected } => Sum::new(6, version).field(expected)?.build(),
+            MissingTxMetadata { referenced } => Sum::new(7, version).field(referenced)?.build(),
+            ConflictingMetadataHash { mismatch } => {
+                Sum::new(8, version).mismatch(mismatch)?.build()
+            }
+            InvalidMetadata => Sum::new(9, version).build(),
+            ExtraneousScriptWitnessesUTXOW { extra_scripts } => {
+                Sum::new(10, version).field(extra_scripts)?.build()
+            }
+        }
+    }
+}
+
+impl ToTerm for shelley::DelegPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use shelley::DelegPredicateFailure::*;
+        match self {
+            StakeKeyAlreadyRegistered { credential } => {
+                Sum::new(0, version).field(credential)?.build()
+            }
+            StakeKeyNotRegistered { credential } => Sum::new(1, version).field(credential)?.build(),
+            StakeKeyNonZeroAccountBalance { remaining_balance } => {
+                Sum::new(2, version).field(remaining_balance)?.build()
+            }
+            StakeDelegationImpossible { credential } => {
+                Sum::new(3, version).field(credential)?.build()
+            }
+            WrongCertificateType => Sum::new(4, version).build(),
+            GenesisKeyNotInMapping { genesis_key } => {
+                Sum::new(5, version).field(genesis_key)?.build()
+            }
+            DuplicateGenesisDelegate { delegate } => Sum::new(6, version).field(delegate)?.build(),
+            InsufficientForInstantaneousRewards { pot, bound } => {
+                Sum::new(7, version).field(pot)?.mismatch(bound)?.build()
+            }
+            MIRCertificateTooLateInEpoch { cutoff } => {
+                Sum::new(8, version).mismatch(cutoff)?.build()
+            }
+            DuplicateGenesisVRF { vrf } => Sum::new(9, version).field(vrf)?.build(),
+            MIRTransferNotCurrentlyAllowed => Sum::new(11, version).build(),
+            MIRNegativesNotCurrentlyAllowed => Sum::new(12, version).build(),
+            InsufficientForTransfer { pot, bound } => {
+                Sum::new(13, version).field(pot)?.mismatch(bound)?.build()
+            }
+            MIRProducesNegativeUpdate => Sum::new(14, version).build(),
+            MIRNegativeTransfer { pot, attempted } => {
+                Sum::new(15, version).field(pot)?.field(attempted)?.build()
+            }
+        }
+    }
+}
+
+impl ToTerm for shelley::PoolPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use shelley::PoolPredicateFailure::*;
+        match self {
+            StakePoolNotRegisteredOnKey { pool_id } => Sum::new(0, version).field(pool_id)?.build(),
+            // The shared supplied epoch is sent once, from the `RelLTEQ` side.
+            StakePoolRetirementWrongEpoch {
+                retirement_too_early,
+                retirement_too_late,
+            } => Sum::new(1, version)
+                .field(&retirement_too_early.expected)?
+                .field(&retirement_too_late.supplied)?
+                .field(&retirement_too_late.expected)?
+                .build(),
+            StakePoolCostTooLow { cost_bound } => {
+                Sum::new(3, version).grouped_mismatch(cost_bound)?.build()
+            }
+            WrongNetwork {
+                network_mismatch,
+                pool_id,
+            } => Sum::new(4, version)
+                .swapped_mismatch(network_mismatch)?
+                .field(pool_id)?
+                .build(),
+            PoolMetadataHashTooBig { pool_id, hash_size } => Sum::new(5, version)
+                .field(pool_id)?
+                .field(hash_size)?
+                .build(),
+            VRFKeyHashAlreadyRegistered { pool_id, vrf } => {
+                Sum::new(6, version).field(pool_id)?.field(vrf)?.build()
+            }
+        }
+    }
+}
+
+impl ToTerm for shelley::DelegsPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use shelley::DelegsPredicateFailure::*;
+        match self {
+            DelegateeNotRegistered { pool_id } => Sum::new(0, version).field(pool_id)?.build(),
+            WithdrawalsNotInRewards { withdrawals } => {
+                Sum::new(1, version).field(withdrawals)?.build()
+            }
+            DelplFailure(failure) => Sum::new(2, version).field(failure)?.build(),
+        }
+    }
+}
+
+impl ToTerm for shelley::DelplPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use shelley::DelplPredicateFailure::*;
+        match self {
+            PoolFailure(failure) => Sum::new(0, version).field(failure)?.build(),
+            DelegFailure(failure) => Sum::new(1, version).field(failure)?.build(),
+        }
+    }
+}
+
+impl<Utxo: ToTerm> ToTerm for shelley::LedgerPredicateFailure<Utxo> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use shelley::LedgerPredicateFailure::*;
+        match self {
+            UtxowFailure(failure) => Sum::new(0, version).field(failure)?.build(),
+            DelegsFailure(failure) => Sum::new(1, version).field(failure)?.build(),
+        }
+    }
+}
+
+impl<Ledger: ToTerm> ToTerm for shelley::LedgersPredicateFailure<Ledger> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        match self {
+            shelley::LedgersPredicateFailure::LedgersFailure(failure) => failure.to_term(version),
+        }
+    }
+}
+
+impl<E: Era> ToTerm for shelley::BbodyPredicateFailure<E> {
+    fn to_term(&self, _version: Version) -> Result<Term, EncodeError> {
+        Err(EncodeError::Unsupported("Shelley BBODY failures"))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Allegra & Mary era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: Era> ToTerm for allegra::UtxoPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use allegra::UtxoPredicateFailure::*;
+        match self {
+            BadInputsUTxO { invalid_inputs } => Sum::new(0, version).field(invalid_inputs)?.build(),
+            OutsideValidityIntervalUTxO {
+                interval,
+                current_slot,
+            } => Sum::new(1, version)
+                .field(interval)?
+                .field(current_slot)?
+                .build(),
+            MaxTxSizeUTxO { size_mismatch } => {
+                Sum::new(2, version).mismatch(size_mismatch)?.build()
+            }
+            InputSetEmptyUTxO => Sum::new(3, version).build(),
+            FeeTooSmallUTxO { fee_mismatch } => {
+                Sum::new(4, version).mismatch(fee_mismatch)?.build()
+            }
+            ValueNotConservedUTxO { balance_mismatch } => {
+                Sum::new(5, version).mismatch(balance_mismatch)?.build()
+            }
+            OutputTooSmallUTxO { tiny_outputs } => {
+                Sum::new(6, version).field(tiny_outputs)?.build()
+            }
+            UpdateFailure(failure) => Sum::new(7, version).field(failure)?.build(),
+            WrongNetwork {
+                expected,
+                offending,
+            } => Sum::new(8, version)
+                .field(expected)?
+                .field(offending)?
+                .build(),
+            WrongNetworkWithdrawal {
+                expected,
+                offending,
+            } => Sum::new(9, version)
+                .field(expected)?
+                .field(offending)?
+                .build(),
+            OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs,
+            } => Sum::new(10, version)
+                .field(oversized_bootstrap_outputs)?
+                .build(),
+            TriesToForgeADA => Sum::new(11, version).build(),
+            OutputTooBigUTxO { oversized_outputs } => {
+                Sum::new(12, version).field(oversized_outputs)?.build()
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Alonzo era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> ToTerm for alonzo::BbodyPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use alonzo::BbodyPredicateFailure::*;
+        match self {
+            ShelleyInAlonzo(failure) => Sum::new(0, version).field(failure)?.build(),
+            TooManyExUnits { bound } => Sum::new(1, version).mismatch(bound)?.build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for alonzo::UtxoPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use alonzo::UtxoPredicateFailure::*;
+        match self {
+            BadInputsUTxO { invalid_inputs } => Sum::new(0, version).field(invalid_inputs)?.build(),
+            OutsideValidityIntervalUTxO {
+                interval,
+                current_slot,
+            } => Sum::new(1, version)
+                .field(interval)?
+                .field(current_slot)?
+                .build(),
+            MaxTxSizeUTxO { bound } => Sum::new(2, version).mismatch(bound)?.build(),
+            InputSetEmptyUTxO => Sum::new(3, version).build(),
+            FeeTooSmallUTxO { bound } => Sum::new(4, version).mismatch(bound)?.build(),
+            ValueNotConservedUTxO { mismatch } => Sum::new(5, version).mismatch(mismatch)?.build(),
+            OutputTooSmallUTxO { outputs } => Sum::new(6, version).field(outputs)?.build(),
+            UtxosFailure(failure) => Sum::new(7, version).field(failure)?.build(),
+            WrongNetwork {
+                expected,
+                offending,
+            } => Sum::new(8, version)
+                .field(expected)?
+                .field(offending)?
+                .build(),
+            WrongNetworkWithdrawal {
+                expected,
+                offending,
+            } => Sum::new(9, version)
+                .field(expected)?
+                .field(offending)?
+                .build(),
+            OutputBootAddrAttrsTooBig { outputs } => Sum::new(10, version).field(outputs)?.build(),
+            OutputTooBigUTxO { oversized_outputs } => {
+                Sum::new(12, version).field(oversized_outputs)?.build()
+            }
+            InsufficientCollateral { computed, required } => Sum::new(13, version)
+                .field(computed)?
+                .field(required)?
+                .build(),
+            ScriptsNotPaidUTxO { offending_utxo } => {
+                Sum::new(14, version).field(offending_utxo)?.build()
+            }
+            ExUnitsTooBigUTxO { bound } => Sum::new(15, version).mismatch(bound)?.build(),
+            CollateralContainsNonADA { value } => Sum::new(16, version).field(value)?.build(),
+            WrongNetworkInTxBody { mismatch } => Sum::new(17, version).mismatch(mismatch)?.build(),
+            OutsideForecast { slot } => Sum::new(18, version).field(slot)?.build(),
+            TooManyCollateralInputs { bound } => Sum::new(19, version).mismatch(bound)?.build(),
+            NoCollateralInputs => Sum::new(20, version).build(),
+        }
+    }
+}
+
+impl ToTerm for alonzo::FailureDescription {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use alonzo::FailureDescription::*;
+        match self {
+            PassedUnexpectedly => Sum::new(0, version).build(),
+            FailedUnexpectedly(descriptions) => Sum::new(1, version).field(descriptions)?.build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for alonzo::UtxosPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use alonzo::UtxosPredicateFailure::*;
+        match self {
+            ValidationTagMismatch {
+                is_valid,
+                description,
+            } => Sum::new(0, version)
+                .field(is_valid)?
+                .field(description)?
+                .build(),
+            CollectErrors { errors } => Sum::new(1, version).field(errors)?.build(),
+            UpdateFailure(failure) => Sum::new(2, version).field(failure)?.build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for alonzo::CollectError<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use alonzo::CollectError::*;
+        match self {
+            NoRedeemer { purpose } => Sum::new(0, version).field(purpose)?.build(),
+            NoWitness { script_hash } => Sum::new(1, version).field(script_hash)?.build(),
+            NoCostModel { language } => Sum::new(2, version).field(language)?.build(),
+            BadTranslation(error) => Sum::new(3, version).field(error)?.build(),
+        }
+    }
+}
+
+impl ToTerm for alonzo::ContextError {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use alonzo::ContextError::*;
+        match self {
+            TranslationLogicMissingInput { input } => Sum::new(1, version).field(input)?.build(),
+            TimeTranslationPastHorizon { message } => Sum::new(7, version).field(message)?.build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for alonzo::UtxowPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use alonzo::UtxowPredicateFailure::*;
+        match self {
+            ShelleyInAlonzo(failure) => Sum::new(0, version).field(failure)?.build(),
+            MissingRedeemers { missing } => Sum::new(1, version).field(missing)?.build(),
+            MissingRequiredDatums {
+                missing_hashes,
+                provided_hashes,
+            } => Sum::new(2, version)
+                .field(missing_hashes)?
+                .field(provided_hashes)?
+                .build(),
+            NotAllowedSupplementalDatums {
+                forbidden_hashes,
+                permitted,
+            } => Sum::new(3, version)
+                .field(forbidden_hashes)?
+                .field(permitted)?
+                .build(),
+            PPViewHashesDontMatch { mismatch } => Sum::new(4, version).mismatch(mismatch)?.build(),
+            UnspendableUTxONoDatumHash { inputs } => Sum::new(6, version).field(inputs)?.build(),
+            ExtraRedeemers { extra } => Sum::new(7, version).field(extra)?.build(),
+            ScriptIntegrityHashMismatch { mismatch, provided } => Sum::new(8, version)
+                .mismatch(mismatch)?
+                .field(provided)?
+                .build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for alonzo::LedgerPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use alonzo::LedgerPredicateFailure::*;
+        match self {
+            UtxowFailure(failure) => Sum::new(0, version).field(failure)?.build(),
+            DelegsFailure(failure) => Sum::new(1, version).field(failure)?.build(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Babbage era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> ToTerm for babbage::UtxoPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use babbage::UtxoPredicateFailure::*;
+        match self {
+            AlonzoInBabbage(failure) => Sum::new(1, version).field(failure)?.build(),
+            IncorrectTotalCollateralField { provided, declared } => Sum::new(2, version)
+                .field(provided)?
+                .field(declared)?
+                .build(),
+            OutputTooSmall { outputs } => Sum::new(3, version).field(outputs)?.build(),
+            NonDisjointReferenceInputs { overlapping } => {
+                Sum::new(4, version).field(overlapping)?.build()
+            }
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for babbage::UtxowPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use babbage::UtxowPredicateFailure::*;
+        match self {
+            AlonzoInBabbage(failure) => Sum::new(1, version).field(failure)?.build(),
+            UtxoFailure(failure) => Sum::new(2, version).field(failure)?.build(),
+            MalformedScriptWitnesses { witnesses } => {
+                Sum::new(3, version).field(witnesses)?.build()
+            }
+            MalformedReferenceScripts { scripts } => Sum::new(4, version).field(scripts)?.build(),
+            ScriptIntegrityHashMismatch { mismatch, provided } => Sum::new(5, version)
+                .mismatch(mismatch)?
+                .field(provided)?
+                .build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for babbage::ContextError<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use babbage::ContextError::*;
+        match self {
+            ByronTxOutInContext { source } => Sum::new(0, version).field(source)?.build(),
+            AlonzoContextError(error) => error.to_term(version),
+            RedeemerPointerPointsToNothing { purpose } => {
+                Sum::new(2, version).field(purpose)?.build()
+            }
+            InlineDatumsNotSupported { source } => Sum::new(4, version).field(source)?.build(),
+            ReferenceScriptsNotSupported { source } => Sum::new(5, version).field(source)?.build(),
+            ReferenceInputsNotSupported { inputs } => Sum::new(6, version).field(inputs)?.build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for babbage::LedgerPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use babbage::LedgerPredicateFailure::*;
+        match self {
+            UtxowFailure(failure) => Sum::new(0, version).field(failure)?.build(),
+            DelegsFailure(failure) => Sum::new(1, version).field(failure)?.build(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Conway era predicate failures
+// ---------------------------------------------------------------------------
+
+impl<E: PlutusEra> ToTerm for conway::UtxosPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::UtxosPredicateFailure::*;
+        match self {
+            ValidationTagMismatch { tag, description } => {
+                Sum::new(0, version).field(tag)?.field(description)?.build()
+            }
+            CollectErrors { errors } => Sum::new(1, version).field(errors)?.build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for conway::ContextError<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::ContextError::*;
+        match self {
+            BabbageContextError(error) => Sum::new(8, version).field(error)?.build(),
+            CertificateNotSupported { certificate } => {
+                Sum::new(9, version).field(certificate)?.build()
+            }
+            PlutusPurposeNotSupported { purpose } => Sum::new(10, version).field(purpose)?.build(),
+            CurrentTreasuryFieldNotSupported { treasury } => {
+                Sum::new(11, version).field(treasury)?.build()
+            }
+            VotingProceduresFieldNotSupported { procedures } => {
+                Sum::new(12, version).field(procedures)?.build()
+            }
+            ProposalProceduresFieldNotSupported { procedures } => {
+                Sum::new(13, version).field(procedures)?.build()
+            }
+            TreasuryDonationFieldNotSupported { donation } => {
+                Sum::new(14, version).field(donation)?.build()
+            }
+            ReferenceInputsNotDisjointFromInputs { common } => {
+                Sum::new(15, version).field(common)?.build()
+            }
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for conway::UtxoPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::UtxoPredicateFailure::*;
+        match self {
+            UtxosFailure(failure) => Sum::new(0, version).field(failure)?.build(),
+            BadInputsUTxO { invalid_inputs } => Sum::new(1, version).field(invalid_inputs)?.build(),
+            OutsideValidityIntervalUTxO {
+                validity_interval,
+                current_slot,
+            } => Sum::new(2, version)
+                .field(validity_interval)?
+                .field(current_slot)?
+                .build(),
+            MaxTxSizeUTxO { size_mismatch } => Sum::new(3, version)
+                .grouped_mismatch(size_mismatch)?
+                .build(),
+            InputSetEmptyUTxO => Sum::new(4, version).build(),
+            FeeTooSmallUTxO { fee_mismatch } => {
+                Sum::new(5, version).swapped_mismatch(fee_mismatch)?.build()
+            }
+            ValueNotConservedUTxO { balance_mismatch } => Sum::new(6, version)
+                .grouped_mismatch(balance_mismatch)?
+                .build(),
+            WrongNetwork {
+                expected,
+                offending,
+            } => Sum::new(7, version)
+                .field(expected)?
+                .field(offending)?
+                .build(),
+            WrongNetworkWithdrawal {
+                expected,
+                offending,
+            } => Sum::new(8, version)
+                .field(expected)?
+                .field(offending)?
+                .build(),
+            OutputTooSmallUTxO { tiny_outputs } => {
+                Sum::new(9, version).field(tiny_outputs)?.build()
+            }
+            OutputBootAddrAttrsTooBig {
+                oversized_bootstrap_outputs,
+            } => Sum::new(10, version)
+                .field(oversized_bootstrap_outputs)?
+                .build(),
+            OutputTooBigUTxO { outputs } => Sum::new(11, version).field(outputs)?.build(),
+            InsufficientCollateral { provided, required } => Sum::new(12, version)
+                .field(provided)?
+                .field(required)?
+                .build(),
+            ScriptsNotPaidUTxO { unpaid } => Sum::new(13, version).field(unpaid)?.build(),
+            ExUnitsTooBigUTxO { limit_mismatch } => Sum::new(14, version)
+                .swapped_mismatch(limit_mismatch)?
+                .build(),
+            CollateralContainsNonADA { offending_value } => {
+                Sum::new(15, version).field(offending_value)?.build()
+            }
+            WrongNetworkInTxBody { mismatch } => {
+                Sum::new(16, version).swapped_mismatch(mismatch)?.build()
+            }
+            OutsideForecast { slot } => Sum::new(17, version).field(slot)?.build(),
+            TooManyCollateralInputs { bound } => {
+                Sum::new(18, version).swapped_mismatch(bound)?.build()
+            }
+            NoCollateralInputs => Sum::new(19, version).build(),
+            IncorrectTotalCollateralField { provided, declared } => Sum::new(20, version)
+                .field(provided)?
+                .field(declared)?
+                .build(),
+            BabbageOutputTooSmallUTxO { outputs } => Sum::new(21, version).field(outputs)?.build(),
+            BabbageNonDisjointRefInputs { overlapping } => {
+                Sum::new(22, version).field(overlapping)?.build()
+            }
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for conway::UtxowPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::UtxowPredicateFailure::*;
+        match self {
+            UtxoFailure(failure) => Sum::new(0, version).field(failure)?.build(),
+            InvalidWitnessesUTXOW { witnesses } => Sum::new(1, version).field(witnesses)?.build(),
+            MissingVKeyWitnessesUTXOW { missing } => Sum::new(2, version).field(missing)?.build(),
+            MissingScriptWitnessesUTXOW { missing } => Sum::new(3, version).field(missing)?.build(),
+            ScriptWitnessNotValidatingUTXOW { failing } => {
+                Sum::new(4, version).field(failing)?.build()
+            }
+            MissingTxBodyMetadataHash { expected } => Sum::new(5, version).field(expected)?.build(),
+            MissingTxMetadata { expected } => Sum::new(6, version).field(expected)?.build(),
+            ConflictingMetadataHash { mismatch } => {
+                Sum::new(7, version).grouped_mismatch(mismatch)?.build()
+            }
+            InvalidMetadata => Sum::new(8, version).build(),
+            ExtraneousScriptWitnessesUTXOW { extraneous } => {
+                Sum::new(9, version).field(extraneous)?.build()
+            }
+            MissingRedeemers { missing } => Sum::new(10, version).field(missing)?.build(),
+            MissingRequiredDatums {
+                missing_hashes,
+                provided_hashes,
+            } => Sum::new(11, version)
+                .field(missing_hashes)?
+                .field(provided_hashes)?
+                .build(),
+            NotAllowedSupplementalDatums {
+                disallowed_hashes,
+                allowed,
+            } => Sum::new(12, version)
+                .field(disallowed_hashes)?
+                .field(allowed)?
+                .build(),
+            PPViewHashesDontMatch { mismatch } => {
+                Sum::new(13, version).grouped_mismatch(mismatch)?.build()
+            }
+            UnspendableUTxONoDatumHash { inputs } => Sum::new(14, version).field(inputs)?.build(),
+            ExtraRedeemers { extra } => Sum::new(15, version).field(extra)?.build(),
+            MalformedScriptWitnesses { scripts } => Sum::new(16, version).field(scripts)?.build(),
+            MalformedReferenceScripts { scripts } => Sum::new(17, version).field(scripts)?.build(),
+            ScriptIntegrityHashMismatch { mismatch, provided } => Sum::new(18, version)
+                .mismatch(mismatch)?
+                .field(provided)?
+                .build(),
+        }
+    }
+}
+
+impl ToTerm for conway::DelegPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::DelegPredicateFailure::*;
+        match self {
+            IncorrectDepositDELEG { deposit } => Sum::new(1, version).field(deposit)?.build(),
+            StakeKeyRegisteredDELEG { stake_credential } => {
+                Sum::new(2, version).field(stake_credential)?.build()
+            }
+            StakeKeyNotRegisteredDELEG { stake_credential } => {
+                Sum::new(3, version).field(stake_credential)?.build()
+            }
+            StakeKeyHasNonZeroRewardAccountBalanceDELEG { balance } => {
+                Sum::new(4, version).field(balance)?.build()
+            }
+            DelegateeDRepNotRegisteredDELEG { delegatee } => {
+                Sum::new(5, version).field(delegatee)?.build()
+            }
+            DelegateeStakePoolNotRegisteredDELEG { delegatee } => {
+                Sum::new(6, version).field(delegatee)?.build()
+            }
+            DepositIncorrectDELEG { mismatch } => Sum::new(7, version).mismatch(mismatch)?.build(),
+            RefundIncorrectDELEG { mismatch } => Sum::new(8, version).mismatch(mismatch)?.build(),
+        }
+    }
+}
+
+impl ToTerm for conway::GovCertPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::GovCertPredicateFailure::*;
+        match self {
+            ConwayDRepAlreadyRegistered { credential } => {
+                Sum::new(0, version).field(credential)?.build()
+            }
+            ConwayDRepNotRegistered { credential } => {
+                Sum::new(1, version).field(credential)?.build()
+            }
+            ConwayDRepIncorrectDeposit { mismatch } => {
+                Sum::new(2, version).grouped_mismatch(mismatch)?.build()
+            }
+            ConwayCommitteeHasPreviouslyResigned { cold_credential } => {
+                Sum::new(3, version).field(cold_credential)?.build()
+            }
+            ConwayDRepIncorrectRefund { mismatch } => {
+                Sum::new(4, version).grouped_mismatch(mismatch)?.build()
+            }
+            ConwayCommitteeIsUnknown { cold_credential } => {
+                Sum::new(5, version).field(cold_credential)?.build()
+            }
+        }
+    }
+}
+
+impl ToTerm for conway::CertPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::CertPredicateFailure::*;
+        match self {
+            DelegFailure(failure) => Sum::new(1, version).field(failure)?.build(),
+            PoolFailure(failure) => Sum::new(2, version).field(failure)?.build(),
+            GovCertFailure(failure) => Sum::new(3, version).field(failure)?.build(),
+        }
+    }
+}
+
+impl ToTerm for conway::CertsPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::CertsPredicateFailure::*;
+        match self {
+            WithdrawalsNotInRewardsCERTS { withdrawals } => {
+                Sum::new(0, version).field(withdrawals)?.build()
+            }
+            CertFailure(failure) => Sum::new(1, version).field(failure)?.build(),
+        }
+    }
+}
+
+impl ToTerm for conway::GovPredicateFailure {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::GovPredicateFailure::*;
+        match self {
+            GovActionsDoNotExist { missing } => Sum::new(0, version).field(missing)?.build(),
+            MalformedProposal { proposal } => Sum::new(1, version).field(proposal)?.build(),
+            ProposalProcedureNetworkIdMismatch {
+                reward_account,
+                expected_network,
+            } => Sum::new(2, version)
+                .field(reward_account)?
+                .field(expected_network)?
+                .build(),
+            TreasuryWithdrawalsNetworkIdMismatch {
+                offending_accounts,
+                expected_network,
+            } => Sum::new(3, version)
+                .field(offending_accounts)?
+                .field(expected_network)?
+                .build(),
+            ProposalDepositIncorrect { mismatch } => {
+                Sum::new(4, version).grouped_mismatch(mismatch)?.build()
+            }
+            DisallowedVoters { voters } => Sum::new(5, version).field(voters)?.build(),
+            ConflictingCommitteeUpdate { members } => Sum::new(6, version).field(members)?.build(),
+            ExpirationEpochTooSmall { expired } => Sum::new(7, version).field(expired)?.build(),
+            InvalidPrevGovActionId { proposal } => Sum::new(8, version).field(proposal)?.build(),
+            VotingOnExpiredGovAction { votes } => Sum::new(9, version).field(votes)?.build(),
+            ProposalCantFollow {
+                previous,
+                version_mismatch,
+            } => Sum::new(10, version)
+                .field(&previous.as_ref().map(|previous| &previous.id))?
+                .grouped_mismatch(version_mismatch)?
+                .build(),
+            InvalidPolicyHash { provided, expected } => Sum::new(11, version)
+                .field(provided)?
+                .field(expected)?
+                .build(),
+            DisallowedProposalDuringBootstrap { proposal } => {
+                Sum::new(12, version).field(proposal)?.build()
+            }
+            DisallowedVotesDuringBootstrap { votes } => Sum::new(13, version).field(votes)?.build(),
+            VotersDoNotExist { voters } => Sum::new(14, version).field(voters)?.build(),
+            ZeroTreasuryWithdrawals { action } => Sum::new(15, version).field(action)?.build(),
+            ProposalReturnAccountDoesNotExist { reward_account } => {
+                Sum::new(16, version).field(reward_account)?.build()
+            }
+            TreasuryWithdrawalReturnAccountsDoNotExist { reward_accounts } => {
+                Sum::new(17, version).field(reward_accounts)?.build()
+            }
+            UnelectedCommitteeVoters { voters } => Sum::new(18, version).field(voters)?.build(),
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for conway::LedgerPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::LedgerPredicateFailure::*;
+        match self {
+            ConwayUtxowFailure(failure) => Sum::new(1, version).field(failure)?.build(),
+            ConwayCertsFailure(failure) => Sum::new(2, version).field(failure)?.build(),
+            ConwayGovFailure(failure) => Sum::new(3, version).field(failure)?.build(),
+            ConwayWdrlNotDelegatedToDRep { withdrawals } => {
+                Sum::new(4, version).field(withdrawals)?.build()
+            }
+            ConwayTreasuryValueMismatch { mismatch } => {
+                Sum::new(5, version).swapped_mismatch(mismatch)?.build()
+            }
+            ConwayTxRefScriptsSizeTooBig { size_mismatch } => Sum::new(6, version)
+                .grouped_mismatch(size_mismatch)?
+                .build(),
+            ConwayMempoolFailure { reason } => Sum::new(7, version).field(reason)?.build(),
+            ConwayWithdrawalsMissingAccounts { withdrawals } => {
+                Sum::new(8, version).field(withdrawals)?.build()
+            }
+            ConwayIncompleteWithdrawals { withdrawals } => {
+                Sum::new(9, version).field(withdrawals)?.build()
+            }
+        }
+    }
+}
+
+impl<E: PlutusEra> ToTerm for conway::BbodyPredicateFailure<E> {
+    fn to_term(&self, version: Version) -> Result<Term, EncodeError> {
+        use conway::BbodyPredicateFailure::*;
+        match self {
+            WrongBlockBodySizeBBODY { mismatch } => {
+                Sum::new(0, version).grouped_mismatch(mismatch)?.build()
+            }
+            InvalidBodyHashBBODY { mismatch } => {
+                Sum::new(1, version).grouped_mismatch(mismatch)?.build()
+            }
+            LedgersFailure(failure) => Sum::new(2, version).field(failure)?.build(),
+            TooManyExUnits { bound } => Sum::new(3, version).grouped_mismatch(bound)?.build(),
+            BodyRefScriptsSizeTooBig { size_mismatch } => Sum::new(4, version)
+                .grouped_mismatch(size_mismatch)?
+                .build(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_parser::{decode_predicate_failure_bytes, decode_term};
+    use crate::failure_decoder::{decode_failure_bytes, FromTerm};
+    use ciborium::{ser::into_writer, value::Value};
+
+    fn uint(n: u64) -> Value {
+        Value::Integer(n.into())
+    }
+
+    #[test]
+    fn round_trips_node_bytes() {
+        // ConwayUtxowFailure (MissingVKeyWitnessesUTXOW (tag 258 set of one key)).
+        let message = Value::Array(vec![
+            uint(1),
+            Value::Array(vec![
+                uint(2),
+                Value::Tag(258, Box::new(Value::Array(vec![Value::Bytes(vec![9; 28])]))),
+            ]),
+        ]);
+        let mut bytes = Vec::new();
+        into_writer(&message, &mut bytes).expect("encode test value");
+
+        let failure: conway::LedgerPredicateFailure<Conway> =
+            decode_failure_bytes(&bytes).expect("decode failure");
+        let encoded = encode_failure(&failure, Version::CONWAY).expect("encode failure");
+        assert_eq!(encoded, bytes);
+        assert!(decode_predicate_failure_bytes(&encoded).is_ok());
+    }
+
+    #[test]
+    fn round_trips_bignums() {
+        // -2^96 - 1 does not fit 64 bits, 5 would but arrives as a bignum.
+        let large_negative = Value::Tag(
+            3,
+            Box::new(Value::Bytes([1].into_iter().chain([0; 12]).collect())),
+        );
+        let small = Value::Tag(2, Box::new(Value::Bytes(vec![5])));
+        for value in [large_negative.clone(), small] {
+            let mut bytes = Vec::new();
+            into_writer(&value, &mut bytes).expect("encode test value");
+            assert_eq!(
+                decode_term(bytes.as_slice())
+                    .expect("decode term")
+                    .to_bytes(),
+                bytes
+            );
+        }
+
+        // ValueNotConservedUTxO burning below -2^64 of one asset.
+        let message = Value::Array(vec![
+            uint(6),
+            Value::Array(vec![
+                uint(0),
+                Value::Map(vec![(
+                    Value::Bytes(vec![4; 28]),
+                    Value::Map(vec![(Value::Bytes(b"tok".to_vec()), large_negative)]),
+                )]),
+            ]),
+            uint(0),
+        ]);
+        let mut bytes = Vec::new();
+        into_writer(&message, &mut bytes).expect("encode test value");
+
+        let failure: conway::UtxoPredicateFailure<Conway> =
+            decode_failure_bytes(&bytes).expect("decode failure");
+        let conway::UtxoPredicateFailure::ValueNotConservedUTxO { balance_mismatch } = &failure
+        else {
+            panic!("expected ValueNotConservedUTxO");
+        };
+        let ValueStruct::MultiAsset { assets, .. } = &balance_mismatch.supplied else {
+            panic!("expected a multi-asset value");
+        };
+        let quantity = assets.0[&ScriptHash(Hash28([4; 28]))][&AssetName(b"tok".to_vec())];
+        assert_eq!(quantity, -(1 << 96) - 1);
+        assert_eq!(
+            encode_failure(&failure, Version::CONWAY).expect("encode failure"),
+            bytes
+        );
+    }
+
+    #[test]
+    fn round_trips_typed_failure() {
+        let failure: conway::LedgerPredicateFailure<Conway> =
+            conway::LedgerPredicateFailure::ConwayCertsFailure(
+                conway::CertsPredicateFailure::CertFailure(
+                    conway::CertPredicateFailure::PoolFailure(
+                        shelley::PoolPredicateFailure::StakePoolRetirementWrongEpoch {
+                            retirement_too_early: Mismatch {
+                                relation: RelGT,
+                                supplied: EpochNo(500),
+                                expected: EpochNo(510),
+                            },
+                            retirement_too_late: Mismatch {
+                                relation: RelLTEQ,
+                                supplied: EpochNo(500),
+                                expected: EpochNo(528),
+                            },
+                        },
+                    ),
+                ),
+            );
+
+        let bytes = encode_failure(&failure, Version::CONWAY).expect("encode failure");
+        let decoded: conway::LedgerPredicateFailure<Conway> =
+            decode_failure_bytes(&bytes).expect("decode failure");
+        assert_eq!(decoded, failure);
+        assert_eq!(
+            encode_failure(&decoded, Version::CONWAY).expect("re-encode failure"),
+            bytes
+        );
+    }
+
+    #[test]
+    fn round_trips_collect_errors() {
+        let failure: alonzo::UtxosPredicateFailure<Alonzo> =
+            alonzo::UtxosPredicateFailure::CollectErrors {
+                errors: vec![
+                    alonzo::CollectError::NoRedeemer {
+                        purpose: AlonzoPlutusPurposeItem::Spending(TxIn {
+                            transaction_id: TxId(Hash32([3; 32])),
+                            index: TxIx(1),
+                        }),
+                    },
+                    alonzo::CollectError::NoCostModel {
+                        language: Language::PlutusV2,
+                    },
+                    alonzo::CollectError::BadTranslation(
+                        alonzo::ContextError::TimeTranslationPastHorizon {
+                            message: "PastHorizon".into(),
+                        },
+                    ),
+                ],
+            };
+
+        let bytes = encode_failure(&failure, Version::ALONZO).expect("encode failure");
+        let decoded: alonzo::UtxosPredicateFailure<Alonzo> =
+            decode_failure_bytes(&bytes).expect("decode failure");
+        assert_eq!(decoded, failure);
+    }
+
+    #[test]
+    fn long_lists_use_indefinite_length() {
+        let term = vec![Coin(1); 24]
+            .to_term(Version::CONWAY)
+            .expect("encode list");
+        let bytes = term.to_bytes();
+        assert_eq!(bytes.first(), Some(&0x9f));
+        assert_eq!(bytes.last(), Some(&0xff));
+    }
+
+    #[test]
+    fn round_trips_both_output_forms() {
+        let legacy = Value::Array(vec![
+            Value::Bytes(vec![0x61; 29]),
+            Value::Array(vec![
+                uint(1_500_000),
+                Value::Map(vec![(
+                    Value::Bytes(vec![4; 28]),
+                    Value::Map(vec![(Value::Bytes(b"tok".to_vec()), uint(3))]),
+                )]),
+            ]),
+            Value::Bytes(vec![5; 32]),
+        ]);
+        let mut bytes = Vec::new();
+        into_writer(&legacy, &mut bytes).expect("encode test value");
+        let output: TxOutStruct<Babbage> =
+            FromTerm::from_term(&decode_term(bytes.as_slice()).unwrap()).unwrap();
+        assert!(matches!(
+            output,
+            TxOutStruct::Shelley {
+                datum_hash: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(output.to_term(Version::BABBAGE).unwrap().to_bytes(), bytes);
+
+        // Constr 0 [B <70 bytes>], with the byte string chunked as 64 + 6.
+        let mut datum = vec![0xd8, 0x79, 0x9f, 0x5f, 0x58, 0x40];
+        datum.extend([7; 64]);
+        datum.extend([0x46, 7, 7, 7, 7, 7, 7, 0xff, 0xff]);
+        let script = vec![0x82, 0x00, 0x82, 0x04, 0x19, 0x03, 0xe8]; // InvalidBefore 1000
+        let babbage = Value::Map(vec![
+            (uint(0), Value::Bytes(vec![0x71; 29])),
+            (uint(1), uint(2_000_000)),
+            (
+                uint(2),
+                Value::Array(vec![uint(1), Value::Tag(24, Box::new(Value::Bytes(datum)))]),
+            ),
+            (uint(3), Value::Tag(24, Box::new(Value::Bytes(script)))),
+        ]);
+        let mut bytes = Vec::new();
+        into_writer(&babbage, &mut bytes).expect("encode test value");
+        let output: TxOutStruct<Babbage> =
+            FromTerm::from_term(&decode_term(bytes.as_slice()).unwrap()).unwrap();
+        match &output {
+            TxOutStruct::Babbage {
+                datum_option: Some(DatumOption::Inline(PlutusData::Constr { tag: 0, fields })),
+                script_ref: Some(ScriptRef(Script::Native(NativeScript::InvalidBefore(slot)))),
+                ..
+            } => {
+                assert_eq!(fields, &[PlutusData::Bytes(vec![7; 70])]);
+                assert_eq!(slot, &SlotNo(1000));
+            }
+            other => panic!("unexpected output {other:?}"),
+        }
+        assert_eq!(output.to_term(Version::BABBAGE).unwrap().to_bytes(), bytes);
+    }
+
+    #[test]
+    fn writes_normalised_tagged_rationals() {
+        // 30([2, 4]) reads back as 1/2 and is written as 30([1, 2]).
+        let bytes = [0xd8, 0x1e, 0x82, 0x02, 0x04];
+        let term = decode_term(bytes.as_slice()).unwrap();
+        let half = UnitInterval::from_term(&term).unwrap();
+        assert_eq!(half, UnitInterval::HALF);
+        assert_eq!(
+            half.to_term(Version::CONWAY).unwrap().to_bytes(),
+            [0xd8, 0x1e, 0x82, 0x01, 0x02]
+        );
+    }
+}
@@ -0,0 +1,3423 @@
+//! JSON encoding of the predicate failure enums defined in
+//! `rust_rule_errors.rs`, matching the `ToJSON` instances of the Haskell
+//! ledger.
+//!
+//! The node reports rejected transactions as JSON through its tracers and
+//! through `cardano-cli`, and that output is what users paste into bug
+//! reports.  The shapes below follow the Haskell instances rather than a
+//! derived Rust layout:
+//!
+//! * predicate failures use Aeson's default `TaggedObject` encoding:
+//!   `{"tag": "Ctor"}` for nullary constructors, `{"tag": "Ctor", "contents":
+//!   x}` for a single field and `"contents": [x, y, ...]` for several;
+//! * sums whose constructors are all nullary encode as the bare name;
+//! * a `Mismatch` is `{"supplied": x, "expected": y}`;
+//! * a credential is `{"keyHash": hex}` or `{"scriptHash": hex}`;
+//! * hashes, keys and raw bytes are lowercase hex strings.
+//!
+//! Constructor names are the Haskell ones, which differ from the Rust variant
+//! names in a few places (the Shelley DELEG and POOL failures carry a rule
+//! suffix, for example).  The JSON does not say which relation a `Mismatch`
+//! checks, so the decoders attach it per constructor, as `failure_decoder.rs`
+//! does for CBOR.  `serde` impls are layered on top of [`ToJson`] and
+//! [`FromJson`] at the end of the file.
+//!
+//! Objects keep the field order of the Haskell encoding (`tag` before
+//! `contents`), which needs `serde_json`'s `preserve_order` feature.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use serde_json::{json, Map, Value};
+
+use crate::era::*;
+use crate::error_parser::{decode_term, Term};
+use crate::rust_rule_errors::*;
+
+/// Encoding of a value into the JSON the Haskell node would print.
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+
+    /// Encoding of a `Vec<Self>`; bytes override it to print hex.
+    fn list_to_json(items: &[Self]) -> Value
+    where
+        Self: Sized,
+    {
+        Value::Array(items.iter().map(ToJson::to_json).collect())
+    }
+}
+
+/// Decoding of a value from the JSON the Haskell node prints.
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, JsonError>;
+
+    /// Decoding of a `Vec<Self>`; bytes override it to read hex.
+    fn list_from_json(value: &Value) -> Result<Vec<Self>, JsonError> {
+        array(value)?.iter().map(Self::from_json).collect()
+    }
+}
+
+/// Encoding of a map key, following the Haskell `ToJSONKey` instances.
+pub trait ToJsonKey {
+    fn to_json_key(&self) -> String;
+}
+
+/// Decoding of a map key, following the Haskell `FromJSONKey` instances.
+pub trait FromJsonKey: Sized {
+    fn from_json_key(key: &str) -> Result<Self, JsonError>;
+}
+
+/// Error returned while decoding a predicate failure from JSON.
+#[derive(Debug)]
+pub enum JsonError {
+    /// The input is not valid JSON.
+    Syntax(serde_json::Error),
+    /// The JSON does not have the shape of the expected value.
+    Malformed(&'static str),
+    /// A required object field is absent.
+    MissingField(&'static str),
+    /// The `tag` does not name a constructor of the expected sum.
+    UnknownConstructor { family: &'static str, tag: String },
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Syntax(err) => write!(f, "JSON syntax error: {err}"),
+            JsonError::Malformed(msg) => write!(f, "malformed predicate failure JSON: {msg}"),
+            JsonError::MissingField(name) => write!(f, "missing JSON field {name:?}"),
+            JsonError::UnknownConstructor { family, tag } => {
+                write!(f, "unknown constructor {tag:?} for {family}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JsonError::Syntax(err) => Some(err),
+            JsonError::Malformed(_)
+            | JsonError::MissingField(_)
+            | JsonError::UnknownConstructor { .. } => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(err: serde_json::Error) -> Self {
+        JsonError::Syntax(err)
+    }
+}
+
+/// Decode a predicate failure from the JSON text the node prints.
+///
+/// # Errors
+///
+/// Returns [`JsonError::Syntax`] for invalid JSON and the other variants when
+/// the document does not have the shape of `T`.
+pub fn decode_failure_json<T: FromJson>(text: &str) -> Result<T, JsonError> {
+    let value: Value = serde_json::from_str(text)?;
+    T::from_json(&value)
+}
+
+// ---------------------------------------------------------------------------
+// Constructor builder and cursor
+// ---------------------------------------------------------------------------
+
+/// Builder for the `TaggedObject` layout of one constructor.
+struct Tagged {
+    tag: &'static str,
+    contents: Vec<Value>,
+}
+
+impl Tagged {
+    fn new(tag: &'static str) -> Self {
+        Tagged {
+            tag,
+            contents: Vec::new(),
+        }
+    }
+
+    fn field<T: ToJson + ?Sized>(mut self, value: &T) -> Self {
+        self.contents.push(value.to_json());
+        self
+    }
+
+    fn build(mut self) -> Value {
+        let mut object = Map::new();
+        object.insert("tag".into(), Value::String(self.tag.into()));
+        match self.contents.len() {
+            0 => {}
+            1 => {
+                object.insert("contents".into(), self.contents.remove(0));
+            }
+            _ => {
+                object.insert("contents".into(), Value::Array(self.contents));
+            }
+        }
+        Value::Object(object)
+    }
+}
+
+/// Reader over a `TaggedObject`; the arity is chosen by the constructor arm.
+struct Contents<'a> {
+    family: &'static str,
+    tag: &'a str,
+    contents: Option<&'a Value>,
+}
+
+impl<'a> Contents<'a> {
+    fn of(value: &'a Value, family: &'static str) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        let tag = member(object, "tag")?
+            .as_str()
+            .ok_or(JsonError::Malformed("expected a string tag"))?;
+        Ok(Contents {
+            family,
+            tag,
+            contents: object.get("contents"),
+        })
+    }
+
+    fn contents(&self) -> Result<&'a Value, JsonError> {
+        self.contents.ok_or(JsonError::MissingField("contents"))
+    }
+
+    /// The single field of a one-field constructor.
+    fn field<T: FromJson>(&self) -> Result<T, JsonError> {
+        T::from_json(self.contents()?)
+    }
+
+    /// The fields of a constructor of the given arity, in order.
+    fn values(&self, arity: usize) -> Result<Vec<&'a Value>, JsonError> {
+        match arity {
+            0 => Ok(Vec::new()),
+            1 => Ok(vec![self.contents()?]),
+            _ => {
+                let items = array(self.contents()?)?;
+                if items.len() != arity {
+                    return Err(JsonError::Malformed(
+                        "unexpected number of constructor fields",
+                    ));
+                }
+                Ok(items.iter().collect())
+            }
+        }
+    }
+
+    /// The fields of a constructor with `N > 1` fields.
+    fn fields<const N: usize>(&self) -> Result<[&'a Value; N], JsonError> {
+        let items = array(self.contents()?)?;
+        let items: Vec<&Value> = items.iter().collect();
+        items
+            .try_into()
+            .map_err(|_| JsonError::Malformed("unexpected number of constructor fields"))
+    }
+
+    fn unknown<T>(&self) -> Result<T, JsonError> {
+        Err(JsonError::UnknownConstructor {
+            family: self.family,
+            tag: self.tag.to_owned(),
+        })
+    }
+}
+
+fn object(value: &Value) -> Result<&Map<String, Value>, JsonError> {
+    value
+        .as_object()
+        .ok_or(JsonError::Malformed("expected an object"))
+}
+
+fn array(value: &Value) -> Result<&[Value], JsonError> {
+    value
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or(JsonError::Malformed("expected an array"))
+}
+
+fn string(value: &Value) -> Result<&str, JsonError> {
+    value
+        .as_str()
+        .ok_or(JsonError::Malformed("expected a string"))
+}
+
+fn member<'a>(object: &'a Map<String, Value>, key: &'static str) -> Result<&'a Value, JsonError> {
+    object.get(key).ok_or(JsonError::MissingField(key))
+}
+
+/// A required field of a record.
+fn field<T: FromJson>(object: &Map<String, Value>, key: &'static str) -> Result<T, JsonError> {
+    T::from_json(member(object, key)?)
+}
+
+/// A record field that may be absent or `null`.
+fn optional<T: FromJson>(object: &Map<String, Value>, key: &str) -> Result<Option<T>, JsonError> {
+    match object.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => T::from_json(value).map(Some),
+    }
+}
+
+/// Insert `key` only when the value is present, like Aeson's `.=?`.
+fn insert_optional<T: ToJson>(object: &mut Map<String, Value>, key: &str, value: &Option<T>) {
+    if let Some(value) = value {
+        object.insert(key.into(), value.to_json());
+    }
+}
+
+fn from_json<T: FromJson>(value: &Value) -> Result<T, JsonError> {
+    T::from_json(value)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut text = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        text.push(DIGITS[usize::from(byte >> 4)] as char);
+        text.push(DIGITS[usize::from(byte & 0x0f)] as char);
+    }
+    text
+}
+
+fn unhex(text: &str) -> Result<Vec<u8>, JsonError> {
+    fn digit(c: u8) -> Result<u8, JsonError> {
+        match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(JsonError::Malformed("invalid hex digit")),
+        }
+    }
+    if !text.len().is_multiple_of(2) {
+        return Err(JsonError::Malformed("odd-length hex string"));
+    }
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| Ok(digit(pair[0])? << 4 | digit(pair[1])?))
+        .collect()
+}
+
+fn unhex_fixed<const N: usize>(text: &str) -> Result<[u8; N], JsonError> {
+    unhex(text)?
+        .try_into()
+        .map_err(|_| JsonError::Malformed("unexpected hash length"))
+}
+
+// ---------------------------------------------------------------------------
+// Primitive and container payloads
+// ---------------------------------------------------------------------------
+
+macro_rules! number_json {
+    ($($ty:ty => $as:ident),* $(,)?) => {
+        $(
+            impl ToJson for $ty {
+                fn to_json(&self) -> Value {
+                    Value::from(*self)
+                }
+            }
+
+            impl FromJson for $ty {
+                fn from_json(value: &Value) -> Result<Self, JsonError> {
+                    value
+                        .$as()
+                        .and_then(|n| <$ty>::try_from(n).ok())
+                        .ok_or(JsonError::Malformed("integer out of range"))
+                }
+            }
+        )*
+    };
+}
+
+number_json!(
+    u16 => as_u64,
+    u32 => as_u64,
+    u64 => as_u64,
+    usize => as_u64,
+    i32 => as_i64,
+    i64 => as_i64,
+);
+
+/// Asset quantities are unbounded on the Haskell side; the few that fit
+/// neither `i64` nor `u64` are written as decimal strings.
+impl ToJson for i128 {
+    fn to_json(&self) -> Value {
+        match (i64::try_from(*self), u64::try_from(*self)) {
+            (Ok(n), _) => Value::from(n),
+            (_, Ok(n)) => Value::from(n),
+            _ => Value::from(self.to_string()),
+        }
+    }
+}
+
+impl FromJson for i128 {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match (value.as_i64(), value.as_u64(), value.as_str()) {
+            (Some(n), _, _) => Ok(i128::from(n)),
+            (_, Some(n), _) => Ok(i128::from(n)),
+            (_, _, Some(text)) => text
+                .parse()
+                .map_err(|_| JsonError::Malformed("expected an integer")),
+            _ => Err(JsonError::Malformed("expected an integer")),
+        }
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        value
+            .as_bool()
+            .ok_or(JsonError::Malformed("expected a boolean"))
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        string(value).map(str::to_owned)
+    }
+}
+
+/// Raw bytes are printed as hex, like the Haskell `ByteString` instances
+/// used by the ledger, so a `Vec<u8>` is a string rather than an array.
+impl ToJson for u8 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+
+    fn list_to_json(items: &[Self]) -> Value {
+        Value::String(hex(items))
+    }
+}
+
+impl FromJson for u8 {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        value
+            .as_u64()
+            .and_then(|n| u8::try_from(n).ok())
+            .ok_or(JsonError::Malformed("integer out of range"))
+    }
+
+    fn list_from_json(value: &Value) -> Result<Vec<Self>, JsonError> {
+        unhex(string(value)?)
+    }
+}
+
+impl<const N: usize> ToJson for [u8; N] {
+    fn to_json(&self) -> Value {
+        Value::String(hex(self))
+    }
+}
+
+impl<const N: usize> FromJson for [u8; N] {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        unhex_fixed(string(value)?)
+    }
+}
+
+/// `StrictMaybe` is `null` when absent.
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, ToJson::to_json)
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for Box<T> {
+    fn to_json(&self) -> Value {
+        T::to_json(self)
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+fn items_to_json<'a, T: ToJson + 'a>(items: impl IntoIterator<Item = &'a T>) -> Value {
+    Value::Array(items.into_iter().map(ToJson::to_json).collect())
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        T::list_to_json(self)
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        T::list_from_json(value)
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeSet<T> {
+    fn to_json(&self) -> Value {
+        items_to_json(self)
+    }
+}
+
+impl<T: FromJson + Ord> FromJson for BTreeSet<T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        array(value)?.iter().map(T::from_json).collect()
+    }
+}
+
+impl<T: ToJson> ToJson for NonEmpty<T> {
+    fn to_json(&self) -> Value {
+        items_to_json(self.as_slice())
+    }
+}
+
+impl<T: FromJson> FromJson for NonEmpty<T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        NonEmpty::new(Vec::from_json(value)?).ok_or(JsonError::Malformed("empty non-empty list"))
+    }
+}
+
+impl<T: ToJson> ToJson for OSet<T> {
+    fn to_json(&self) -> Value {
+        items_to_json(&self.0)
+    }
+}
+
+impl<T: FromJson> FromJson for OSet<T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Vec::from_json(value).map(OSet)
+    }
+}
+
+/// Maps whose keys have a `ToJSONKey` instance are objects.
+impl<K: ToJsonKey, V: ToJson> ToJson for BTreeMap<K, V> {
+    fn to_json(&self) -> Value {
+        Value::Object(
+            self.iter()
+                .map(|(key, value)| (key.to_json_key(), value.to_json()))
+                .collect(),
+        )
+    }
+}
+
+impl<K: FromJsonKey + Ord, V: FromJson> FromJson for BTreeMap<K, V> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        object(value)?
+            .iter()
+            .map(|(key, value)| Ok((K::from_json_key(key)?, V::from_json(value)?)))
+            .collect()
+    }
+}
+
+impl<A: ToJson, B: ToJson> ToJson for (A, B) {
+    fn to_json(&self) -> Value {
+        Value::Array(vec![self.0.to_json(), self.1.to_json()])
+    }
+}
+
+impl<A: FromJson, B: FromJson> FromJson for (A, B) {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match array(value)? {
+            [a, b] => Ok((A::from_json(a)?, B::from_json(b)?)),
+            _ => Err(JsonError::Malformed("expected a pair")),
+        }
+    }
+}
+
+impl<A: ToJson, B: ToJson, C: ToJson> ToJson for (A, B, C) {
+    fn to_json(&self) -> Value {
+        Value::Array(vec![self.0.to_json(), self.1.to_json(), self.2.to_json()])
+    }
+}
+
+impl<A: FromJson, B: FromJson, C: FromJson> FromJson for (A, B, C) {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match array(value)? {
+            [a, b, c] => Ok((A::from_json(a)?, B::from_json(b)?, C::from_json(c)?)),
+            _ => Err(JsonError::Malformed("expected a triple")),
+        }
+    }
+}
+
+/// `Mismatch` is a record of the two values; the relation is implied by the
+/// constructor that carries it.
+impl<R, T: ToJson> ToJson for Mismatch<R, T> {
+    fn to_json(&self) -> Value {
+        json!({
+            "supplied": self.supplied.to_json(),
+            "expected": self.expected.to_json(),
+        })
+    }
+}
+
+/// Also accepts the `mismatchSupplied`/`mismatchExpected` names of the
+/// generically derived Haskell instance.
+impl<R: Relation, T: FromJson> FromJson for Mismatch<R, T> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        let side = |short: &'static str, long: &'static str| {
+            object
+                .get(short)
+                .or_else(|| object.get(long))
+                .ok_or(JsonError::MissingField(short))
+                .and_then(T::from_json)
+        };
+        Ok(Mismatch::new(
+            side("supplied", "mismatchSupplied")?,
+            side("expected", "mismatchExpected")?,
+        ))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helper stand-ins
+// ---------------------------------------------------------------------------
+
+macro_rules! newtype_json {
+    ($($ty:ident($inner:ty)),* $(,)?) => {
+        $(
+            impl ToJson for $ty {
+                fn to_json(&self) -> Value {
+                    self.0.to_json()
+                }
+            }
+
+            impl FromJson for $ty {
+                fn from_json(value: &Value) -> Result<Self, JsonError> {
+                    <$inner>::from_json(value).map($ty)
+                }
+            }
+        )*
+    };
+}
+
+newtype_json!(
+    Coin(u64),
+    DeltaCoin(i64),
+    SlotNo(u64),
+    EpochNo(u64),
+    TxIx(u16),
+    EpochInterval(u32),
+    GovActionIx(u16),
+    Address(Vec<u8>),
+    Hash28([u8; 28]),
+    Hash32([u8; 32]),
+    VerificationKey([u8; 32]),
+    ScriptHash(Hash28),
+    DataHash(Hash32),
+    ScriptIntegrityHash(Hash32),
+    TxAuxDataHash(Hash32),
+    TxId(Hash32),
+    AddrKeyHash(Hash28),
+    KeyHash(Hash28),
+    PoolKeyHash(Hash28),
+    VRFKeyHash(Hash32),
+    AssetName(Vec<u8>),
+    Withdrawals(BTreeMap<RewardAccount, Coin>),
+);
+
+/// Map keys for types whose JSON value is already a string.
+macro_rules! string_json_key {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ToJsonKey for $ty {
+                fn to_json_key(&self) -> String {
+                    match self.to_json() {
+                        Value::String(key) => key,
+                        other => other.to_string(),
+                    }
+                }
+            }
+
+            impl FromJsonKey for $ty {
+                fn from_json_key(key: &str) -> Result<Self, JsonError> {
+                    <$ty>::from_json(&Value::String(key.to_owned()))
+                }
+            }
+        )*
+    };
+}
+
+string_json_key!(ScriptHash, AssetName, TxIn, DRep);
+
+impl ToJson for NetworkId {
+    fn to_json(&self) -> Value {
+        match self.0 {
+            0 => "Testnet".into(),
+            _ => "Mainnet".into(),
+        }
+    }
+}
+
+impl FromJson for NetworkId {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match string(value)? {
+            "Testnet" => Ok(NetworkId(0)),
+            "Mainnet" => Ok(NetworkId(1)),
+            _ => Err(JsonError::Malformed("unknown network")),
+        }
+    }
+}
+
+/// `txInToText`: the transaction id in hex, `#`, and the output index.
+impl ToJson for TxIn {
+    fn to_json(&self) -> Value {
+        Value::String(format!(
+            "{}#{}",
+            hex(&self.transaction_id.0 .0),
+            self.index.0
+        ))
+    }
+}
+
+impl FromJson for TxIn {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let (transaction_id, index) = split_id(string(value)?)?;
+        Ok(TxIn {
+            transaction_id,
+            index: TxIx(index),
+        })
+    }
+}
+
+/// Parse the `hex#index` text shared by `TxIn` and `GovActionId`.
+fn split_id(text: &str) -> Result<(TxId, u16), JsonError> {
+    let (id, index) = text
+        .split_once('#')
+        .ok_or(JsonError::Malformed("expected a \"txid#index\" reference"))?;
+    let index = index
+        .parse()
+        .map_err(|_| JsonError::Malformed("invalid output index"))?;
+    Ok((TxId(Hash32(unhex_fixed(id)?)), index))
+}
+
+impl ToJson for ProtVer {
+    fn to_json(&self) -> Value {
+        json!({ "major": self.major, "minor": self.minor })
+    }
+}
+
+impl FromJson for ProtVer {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(ProtVer {
+            major: field(object, "major")?,
+            minor: field(object, "minor")?,
+        })
+    }
+}
+
+impl ToJson for ExUnits {
+    fn to_json(&self) -> Value {
+        json!({ "memory": self.mem, "steps": self.steps })
+    }
+}
+
+impl FromJson for ExUnits {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(ExUnits {
+            mem: field(object, "memory")?,
+            steps: field(object, "steps")?,
+        })
+    }
+}
+
+impl ToJson for ValidityInterval {
+    fn to_json(&self) -> Value {
+        json!({
+            "invalidBefore": self.invalid_before.to_json(),
+            "invalidHereafter": self.invalid_hereafter.to_json(),
+        })
+    }
+}
+
+impl FromJson for ValidityInterval {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(ValidityInterval {
+            invalid_before: optional(object, "invalidBefore")?,
+            invalid_hereafter: optional(object, "invalidHereafter")?,
+        })
+    }
+}
+
+impl ToJson for MIRPot {
+    fn to_json(&self) -> Value {
+        match self {
+            MIRPot::Reserves => "reserves".into(),
+            MIRPot::Treasury => "treasury".into(),
+        }
+    }
+}
+
+impl FromJson for MIRPot {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match string(value)? {
+            "reserves" => Ok(MIRPot::Reserves),
+            "treasury" => Ok(MIRPot::Treasury),
+            _ => Err(JsonError::Malformed("unknown MIR pot")),
+        }
+    }
+}
+
+impl ToJson for MIRTarget {
+    fn to_json(&self) -> Value {
+        match self {
+            MIRTarget::StakeAddressesMIR(rewards) => {
+                Tagged::new("StakeAddressesMIR").field(rewards)
+            }
+            MIRTarget::SendToOppositePotMIR(coin) => {
+                Tagged::new("SendToOppositePotMIR").field(coin)
+            }
+        }
+        .build()
+    }
+}
+
+impl FromJson for MIRTarget {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let contents = Contents::of(value, "MIRTarget")?;
+        Ok(match contents.tag {
+            "StakeAddressesMIR" => MIRTarget::StakeAddressesMIR(contents.field()?),
+            "SendToOppositePotMIR" => MIRTarget::SendToOppositePotMIR(contents.field()?),
+            _ => return contents.unknown(),
+        })
+    }
+}
+
+impl ToJson for MIRCert {
+    fn to_json(&self) -> Value {
+        json!({ "pot": self.pot.to_json(), "target": self.target.to_json() })
+    }
+}
+
+impl FromJson for MIRCert {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(MIRCert {
+            pot: field(object, "pot")?,
+            target: field(object, "target")?,
+        })
+    }
+}
+
+/// `BoundedRatio` prints a number when the ratio has a finite decimal
+/// expansion, and Aeson's `{numerator, denominator}` rational otherwise.
+fn ratio_to_json(numerator: u64, denominator: u64) -> Value {
+    let mut rest = denominator;
+    for factor in [2, 5] {
+        while rest > 1 && rest.is_multiple_of(factor) {
+            rest /= factor;
+        }
+    }
+    if rest == 1 && numerator.is_multiple_of(denominator) {
+        Value::from(numerator / denominator)
+    } else if rest == 1 {
+        serde_json::Number::from_f64(numerator as f64 / denominator as f64)
+            .map_or(Value::Null, Value::Number)
+    } else {
+        json!({ "numerator": numerator, "denominator": denominator })
+    }
+}
+
+fn ratio_from_json(value: &Value) -> Result<(u64, u64), JsonError> {
+    if let Value::Object(object) = value {
+        let numerator: u64 = field(object, "numerator")?;
+        let denominator: u64 = field(object, "denominator")?;
+        return if denominator == 0 {
+            Err(JsonError::Malformed("zero denominator"))
+        } else {
+            Ok((numerator, denominator))
+        };
+    }
+    let Value::Number(number) = value else {
+        return Err(JsonError::Malformed("expected a rational number"));
+    };
+    // Read the decimal text rather than the `f64` so that `0.1` stays 1/10.
+    let text = number.to_string();
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (
+            mantissa,
+            exponent
+                .parse::<i32>()
+                .map_err(|_| JsonError::Malformed("invalid exponent"))?,
+        ),
+        None => (text.as_str(), 0),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits: u64 = format!("{whole}{fraction}")
+        .parse()
+        .map_err(|_| JsonError::Malformed("expected a non-negative rational"))?;
+    let scale = exponent - fraction.len() as i32;
+    let power = |n: i32| {
+        10u64
+            .checked_pow(n.unsigned_abs())
+            .ok_or(JsonError::Malformed("rational out of range"))
+    };
+    let (numerator, denominator) = if scale >= 0 {
+        let numerator = digits
+            .checked_mul(power(scale)?)
+            .ok_or(JsonError::Malformed("rational out of range"))?;
+        (numerator, 1)
+    } else {
+        (digits, power(scale)?)
+    };
+    let divisor = gcd(numerator, denominator);
+    Ok((numerator / divisor, denominator / divisor))
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+impl ToJson for UnitInterval {
+    fn to_json(&self) -> Value {
+        ratio_to_json(self.numerator(), self.denominator())
+    }
+}
+
+impl FromJson for UnitInterval {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let (numerator, denominator) = ratio_from_json(value)?;
+        UnitInterval::new(numerator, denominator)
+            .map_err(|_| JsonError::Malformed("unit interval above one"))
+    }
+}
+
+impl ToJson for NonNegativeInterval {
+    fn to_json(&self) -> Value {
+        ratio_to_json(self.numerator(), self.denominator())
+    }
+}
+
+impl FromJson for NonNegativeInterval {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let (numerator, denominator) = ratio_from_json(value)?;
+        NonNegativeInterval::new(numerator, denominator)
+            .map_err(|_| JsonError::Malformed("zero denominator"))
+    }
+}
+
+impl ToJson for Credential {
+    fn to_json(&self) -> Value {
+        match self {
+            Credential::ScriptHash(hash) => json!({ "scriptHash": hash.to_json() }),
+            Credential::KeyHash(hash) => json!({ "keyHash": hash.to_json() }),
+        }
+    }
+}
+
+impl FromJson for Credential {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        if let Some(hash) = object.get("scriptHash") {
+            ScriptHash::from_json(hash).map(Credential::ScriptHash)
+        } else if let Some(hash) = object.get("keyHash") {
+            AddrKeyHash::from_json(hash).map(Credential::KeyHash)
+        } else {
+            Err(JsonError::Malformed(
+                "expected a keyHash or scriptHash credential",
+            ))
+        }
+    }
+}
+
+/// `credToText`: `keyHash-<hex>` or `scriptHash-<hex>`.
+impl ToJsonKey for Credential {
+    fn to_json_key(&self) -> String {
+        match self {
+            Credential::ScriptHash(hash) => format!("scriptHash-{}", hex(&hash.0 .0)),
+            Credential::KeyHash(hash) => format!("keyHash-{}", hex(&hash.0 .0)),
+        }
+    }
+}
+
+impl FromJsonKey for Credential {
+    fn from_json_key(key: &str) -> Result<Self, JsonError> {
+        if let Some(hash) = key.strip_prefix("scriptHash-") {
+            Ok(Credential::ScriptHash(ScriptHash(Hash28(unhex_fixed(
+                hash,
+            )?))))
+        } else if let Some(hash) = key.strip_prefix("keyHash-") {
+            Ok(Credential::KeyHash(AddrKeyHash(Hash28(unhex_fixed(hash)?))))
+        } else {
+            Err(JsonError::Malformed(
+                "expected a keyHash- or scriptHash- credential",
+            ))
+        }
+    }
+}
+
+/// `dRepToText`: `drep-` followed by the credential text or the
+/// predefined DRep.
+impl ToJson for DRep {
+    fn to_json(&self) -> Value {
+        let text = match self {
+            DRep::KeyHash(hash) => Credential::KeyHash(hash.clone()).to_json_key(),
+            DRep::ScriptHash(hash) => Credential::ScriptHash(hash.clone()).to_json_key(),
+            DRep::Abstain => "alwaysAbstain".into(),
+            DRep::NoConfidence => "alwaysNoConfidence".into(),
+        };
+        Value::String(format!("drep-{text}"))
+    }
+}
+
+impl FromJson for DRep {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let text = string(value)?
+            .strip_prefix("drep-")
+            .ok_or(JsonError::Malformed("expected a drep- prefix"))?;
+        match text {
+            "alwaysAbstain" => Ok(DRep::Abstain),
+            "alwaysNoConfidence" => Ok(DRep::NoConfidence),
+            credential => match Credential::from_json_key(credential)? {
+                Credential::KeyHash(hash) => Ok(DRep::KeyHash(hash)),
+                Credential::ScriptHash(hash) => Ok(DRep::ScriptHash(hash)),
+            },
+        }
+    }
+}
+
+/// `RewardAccount` is printed as its network and credential, decoded from
+/// the header byte of the serialised account.  Bytes that are not a valid
+/// reward account fall back to hex.
+impl ToJson for RewardAccount {
+    fn to_json(&self) -> Value {
+        let credential = match self.0.as_slice() {
+            [header, hash @ ..] if hash.len() == 28 && header >> 5 == 0b111 => {
+                let hash = Hash28(hash.try_into().expect("length checked above"));
+                let credential = if header & 0x10 == 0 {
+                    Credential::KeyHash(AddrKeyHash(hash))
+                } else {
+                    Credential::ScriptHash(ScriptHash(hash))
+                };
+                Some((NetworkId(header & 0x0f), credential))
+            }
+            _ => None,
+        };
+        match credential {
+            Some((network, credential)) => json!({
+                "network": network.to_json(),
+                "credential": credential.to_json(),
+            }),
+            None => self.0.to_json(),
+        }
+    }
+}
+
+impl FromJson for RewardAccount {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = match value {
+            Value::String(_) => return Vec::from_json(value).map(RewardAccount),
+            value => object(value)?,
+        };
+        let network: NetworkId = field(object, "network")?;
+        let (script, hash) = match field(object, "credential")? {
+            Credential::KeyHash(hash) => (0, hash.0),
+            Credential::ScriptHash(hash) => (0x10, hash.0),
+        };
+        let mut bytes = vec![0xe0 | script | network.0];
+        bytes.extend_from_slice(&hash.0);
+        Ok(RewardAccount(bytes))
+    }
+}
+
+/// Reward accounts key maps by their serialised bytes in hex.
+impl ToJsonKey for RewardAccount {
+    fn to_json_key(&self) -> String {
+        hex(&self.0)
+    }
+}
+
+impl FromJsonKey for RewardAccount {
+    fn from_json_key(key: &str) -> Result<Self, JsonError> {
+        unhex(key).map(RewardAccount)
+    }
+}
+
+impl ToJson for Anchor {
+    fn to_json(&self) -> Value {
+        json!({ "url": self.url, "dataHash": self.data_hash.to_json() })
+    }
+}
+
+impl FromJson for Anchor {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(Anchor {
+            url: field(object, "url")?,
+            data_hash: field(object, "dataHash")?,
+        })
+    }
+}
+
+impl ToJson for PoolMetadata {
+    fn to_json(&self) -> Value {
+        json!({ "url": self.url, "hash": self.metadata_hash.to_json() })
+    }
+}
+
+impl FromJson for PoolMetadata {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(PoolMetadata {
+            url: field(object, "url")?,
+            metadata_hash: field(object, "hash")?,
+        })
+    }
+}
+
+impl ToJson for Relay {
+    fn to_json(&self) -> Value {
+        match self {
+            Relay::SingleHostAddr { port, ipv4, ipv6 } => json!({
+                "single host address": {
+                    "port": port.to_json(),
+                    "IPv4": ipv4.map(|ip| Ipv4Addr::from(ip).to_string()),
+                    "IPv6": ipv6.map(|ip| Ipv6Addr::from(ip).to_string()),
+                }
+            }),
+            Relay::SingleHostName { port, dns_name } => json!({
+                "single host name": { "port": port.to_json(), "dnsName": dns_name }
+            }),
+            Relay::MultiHostName { dns_name } => json!({
+                "multi host name": { "dnsName": dns_name }
+            }),
+        }
+    }
+}
+
+impl FromJson for Relay {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        fn ip<T: std::str::FromStr>(
+            relay: &Map<String, Value>,
+            key: &str,
+        ) -> Result<Option<T>, JsonError> {
+            optional::<String>(relay, key)?
+                .map(|text| {
+                    text.parse()
+                        .map_err(|_| JsonError::Malformed("invalid IP address"))
+                })
+                .transpose()
+        }
+        let object = object(value)?;
+        if let Some(relay) = object.get("single host address") {
+            let relay = self::object(relay)?;
+            Ok(Relay::SingleHostAddr {
+                port: optional(relay, "port")?,
+                ipv4: ip::<Ipv4Addr>(relay, "IPv4")?.map(|ip| ip.octets()),
+                ipv6: ip::<Ipv6Addr>(relay, "IPv6")?.map(|ip| ip.octets()),
+            })
+        } else if let Some(relay) = object.get("single host name") {
+            let relay = self::object(relay)?;
+            Ok(Relay::SingleHostName {
+                port: optional(relay, "port")?,
+                dns_name: field(relay, "dnsName")?,
+            })
+        } else if let Some(relay) = object.get("multi host name") {
+            Ok(Relay::MultiHostName {
+                dns_name: field(self::object(relay)?, "dnsName")?,
+            })
+        } else {
+            Err(JsonError::Malformed("unknown relay"))
+        }
+    }
+}
+
+impl ToJson for PoolParams {
+    fn to_json(&self) -> Value {
+        json!({
+            "publicKey": self.operator.to_json(),
+            "vrf": self.vrf_keyhash.to_json(),
+            "pledge": self.pledge.to_json(),
+            "cost": self.cost.to_json(),
+            "margin": self.margin.to_json(),
+            "rewardAccount": self.reward_account.to_json(),
+            "owners": self.owners.to_json(),
+            "relays": self.relays.to_json(),
+            "metadata": self.metadata.to_json(),
+        })
+    }
+}
+
+impl FromJson for PoolParams {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(PoolParams {
+            operator: field(object, "publicKey")?,
+            vrf_keyhash: field(object, "vrf")?,
+            pledge: field(object, "pledge")?,
+            cost: field(object, "cost")?,
+            margin: field(object, "margin")?,
+            reward_account: field(object, "rewardAccount")?,
+            owners: field(object, "owners")?,
+            relays: field(object, "relays")?,
+            metadata: optional(object, "metadata")?,
+        })
+    }
+}
+
+impl ToJson for PoolCert {
+    fn to_json(&self) -> Value {
+        match self {
+            PoolCert::StakeRegistration { credential } => {
+                Tagged::new("StakeRegistration").field(credential)
+            }
+            PoolCert::StakeDeregistration { credential } => {
+                Tagged::new("StakeDeregistration").field(credential)
+            }
+            PoolCert::StakeDelegation { credential, pool } => {
+                Tagged::new("StakeDelegation").field(credential).field(pool)
+            }
+            PoolCert::PoolRegistration { params } => Tagged::new("PoolRegistration").field(params),
+            PoolCert::PoolRetirement { pool, epoch } => {
+                Tagged::new("PoolRetirement").field(pool).field(epoch)
+            }
+            PoolCert::RegCert { credential, coin } => {
+                Tagged::new("RegCert").field(credential).field(coin)
+            }
+            PoolCert::UnregCert { credential, coin } => {
+                Tagged::new("UnregCert").field(credential).field(coin)
+            }
+            PoolCert::VoteDelegCert { credential, drep } => {
+                Tagged::new("VoteDelegCert").field(credential).field(drep)
+            }
+            PoolCert::StakeVoteDelegCert {
+                credential,
+                pool,
+                drep,
+            } => Tagged::new("StakeVoteDelegCert")
+                .field(credential)
+                .field(pool)
+                .field(drep),
+            PoolCert::StakeRegDelegCert {
+                credential,
+                pool,
+                deposit,
+            } => Tagged::new("StakeRegDelegCert")
+                .field(credential)
+                .field(pool)
+                .field(deposit),
+            PoolCert::VoteRegDelegCert {
+                credential,
+                drep,
+                deposit,
+            } => Tagged::new("VoteRegDelegCert")
+                .field(credential)
+                .field(drep)
+                .field(deposit),
+            PoolCert::StakeVoteRegDelegCert {
+                credential,
+                pool,
+                drep,
+                deposit,
+            } => Tagged::new("StakeVoteRegDelegCert")
+                .field(credential)
+                .field(pool)
+                .field(drep)
+                .field(deposit),
+            PoolCert::AuthCommitteeHotCert { cold, hot } => {
+                Tagged::new("AuthCommitteeHotCert").field(cold).field(hot)
+            }
+            PoolCert::ResignCommitteeColdCert { cold, anchor } => {
+                Tagged::new("ResignCommitteeColdCert")
+                    .field(cold)
+                    .field(anchor)
+            }
+            PoolCert::RegDRepCert {
+                credential,
+                deposit,
+                anchor,
+            } => Tagged::new("RegDRepCert")
+                .field(credential)
+                .field(deposit)
+                .field(anchor),
+            PoolCert::UnregDRepCert {
+                credential,
+                deposit,
+            } => Tagged::new("UnregDRepCert")
+                .field(credential)
+                .field(deposit),
+            PoolCert::UpdateDRepCert { credential, anchor } => Tagged::new("UpdateDRepCert")
+                .field(credential)
+                .field(anchor),
+        }
+        .build()
+    }
+}
+
+impl FromJson for PoolCert {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let contents = Contents::of(value, "PoolCert")?;
+        Ok(match contents.tag {
+            "StakeRegistration" => PoolCert::StakeRegistration {
+                credential: contents.field()?,
+            },
+            "StakeDeregistration" => PoolCert::StakeDeregistration {
+                credential: contents.field()?,
+            },
+            "StakeDelegation" => {
+                let [credential, pool] = contents.fields()?;
+                PoolCert::StakeDelegation {
+                    credential: from_json(credential)?,
+                    pool: from_json(pool)?,
+                }
+            }
+            "PoolRegistration" => PoolCert::PoolRegistration {
+                params: contents.field()?,
+            },
+            "PoolRetirement" => {
+                let [pool, epoch] = contents.fields()?;
+                PoolCert::PoolRetirement {
+                    pool: from_json(pool)?,
+                    epoch: from_json(epoch)?,
+                }
+            }
+            "RegCert" => {
+                let [credential, coin] = contents.fields()?;
+                PoolCert::RegCert {
+                    credential: from_json(credential)?,
+                    coin: from_json(coin)?,
+                }
+            }
+            "UnregCert" => {
+                let [credential, coin] = contents.fields()?;
+                PoolCert::UnregCert {
+                    credential: from_json(credential)?,
+                    coin: from_json(coin)?,
+                }
+            }
+            "VoteDelegCert" => {
+                let [credential, drep] = contents.fields()?;
+                PoolCert::VoteDelegCert {
+                    credential: from_json(credential)?,
+                    drep: from_json(drep)?,
+                }
+            }
+            "StakeVoteDelegCert" => {
+                let [credential, pool, drep] = contents.fields()?;
+                PoolCert::StakeVoteDelegCert {
+                    credential: from_json(credential)?,
+                    pool: from_json(pool)?,
+                    drep: from_json(drep)?,
+                }
+            }
+            "StakeRegDelegCert" => {
+                let [credential, pool, deposit] = contents.fields()?;
+                PoolCert::StakeRegDelegCert {
+                    credential: from_json(credential)?,
+                    pool: from_json(pool)?,
+                    deposit: from_json(deposit)?,
+                }
+            }
+            "VoteRegDelegCert" => {
+                let [credential, drep, deposit] = contents.fields()?;
+                PoolCert::VoteRegDelegCert {
+                    credential: from_json(credential)?,
+                    drep: from_json(drep)?,
+                    deposit: from_json(deposit)?,
+                }
+            }
+            "StakeVoteRegDelegCert" => {
+                let [credential, pool, drep, deposit] = contents.fields()?;
+                PoolCert::StakeVoteRegDelegCert {
+                    credential: from_json(credential)?,
+                    pool: from_json(pool)?,
+                    drep: from_json(drep)?,
+                    deposit: from_json(deposit)?,
+                }
+            }
+            "AuthCommitteeHotCert" => {
+                let [cold, hot] = contents.fields()?;
+                PoolCert::AuthCommitteeHotCert {
+                    cold: from_json(cold)?,
+                    hot: from_json(hot)?,
+                }
+            }
+            "ResignCommitteeColdCert" => {
+                let [cold, anchor] = contents.fields()?;
+                PoolCert::ResignCommitteeColdCert {
+                    cold: from_json(cold)?,
+                    anchor: from_json(anchor)?,
+                }
+            }
+            "RegDRepCert" => {
+                let [credential, deposit, anchor] = contents.fields()?;
+                PoolCert::RegDRepCert {
+                    credential: from_json(credential)?,
+                    deposit: from_json(deposit)?,
+                    anchor: from_json(anchor)?,
+                }
+            }
+            "UnregDRepCert" => {
+                let [credential, deposit] = contents.fields()?;
+                PoolCert::UnregDRepCert {
+                    credential: from_json(credential)?,
+                    deposit: from_json(deposit)?,
+                }
+            }
+            "UpdateDRepCert" => {
+                let [credential, anchor] = contents.fields()?;
+                PoolCert::UpdateDRepCert {
+                    credential: from_json(credential)?,
+                    anchor: from_json(anchor)?,
+                }
+            }
+            _ => return contents.unknown(),
+        })
+    }
+}
+
+impl ToJson for ShelleyTxCert {
+    fn to_json(&self) -> Value {
+        match self {
+            ShelleyTxCert::Shared(cert) => cert.to_json(),
+            ShelleyTxCert::GenesisDelegation {
+                genesis,
+                delegate,
+                vrf,
+            } => Tagged::new("GenesisDelegation")
+                .field(genesis)
+                .field(delegate)
+                .field(vrf)
+                .build(),
+            ShelleyTxCert::Mir(mir) => Tagged::new("MIR").field(mir).build(),
+        }
+    }
+}
+
+impl FromJson for ShelleyTxCert {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let contents = Contents::of(value, "ShelleyTxCert")?;
+        Ok(match contents.tag {
+            "GenesisDelegation" => {
+                let [genesis, delegate, vrf] = contents.fields()?;
+                ShelleyTxCert::GenesisDelegation {
+                    genesis: from_json(genesis)?,
+                    delegate: from_json(delegate)?,
+                    vrf: from_json(vrf)?,
+                }
+            }
+            "MIR" => ShelleyTxCert::Mir(contents.field()?),
+            _ => match from_json(value)? {
+                cert @ (PoolCert::StakeRegistration { .. }
+                | PoolCert::StakeDeregistration { .. }
+                | PoolCert::StakeDelegation { .. }
+                | PoolCert::PoolRegistration { .. }
+                | PoolCert::PoolRetirement { .. }) => ShelleyTxCert::Shared(cert),
+                _ => return contents.unknown(),
+            },
+        })
+    }
+}
+
+impl ToJson for dijkstra::TxCert {
+    fn to_json(&self) -> Value {
+        self.as_pool_cert().to_json()
+    }
+}
+
+impl FromJson for dijkstra::TxCert {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        dijkstra::TxCert::new(from_json(value)?).ok_or(JsonError::Malformed(
+            "certificates without deposits are no longer supported",
+        ))
+    }
+}
+
+newtype_json!(MultiAsset(BTreeMap<PolicyId, BTreeMap<AssetName, i128>>));
+
+/// A coin-only value is a bare number; a multi-asset value is
+/// `{"lovelace": n, "policies": {policy: {asset: n}}}`.
+impl ToJson for ValueStruct {
+    fn to_json(&self) -> Value {
+        match self {
+            ValueStruct::Coin(coin) => coin.to_json(),
+            ValueStruct::MultiAsset { coin, assets } => json!({
+                "lovelace": coin.to_json(),
+                "policies": assets.to_json(),
+            }),
+        }
+    }
+}
+
+impl FromJson for ValueStruct {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match value {
+            Value::Object(object) => Ok(ValueStruct::MultiAsset {
+                coin: field(object, "lovelace")?,
+                assets: field(object, "policies")?,
+            }),
+            value => Coin::from_json(value).map(ValueStruct::Coin),
+        }
+    }
+}
+
+/// Plutus data in the detailed schema used by `cardano-cli`.  Integers that
+/// do not fit a JSON number are written as the big-endian bytes of their
+/// magnitude under `biguint` or `bignint`.
+impl ToJson for PlutusData {
+    fn to_json(&self) -> Value {
+        match self {
+            PlutusData::Constr { tag, fields } => {
+                json!({ "constructor": tag, "fields": fields.to_json() })
+            }
+            PlutusData::Map(entries) => json!({
+                "map": entries
+                    .iter()
+                    .map(|(k, v)| json!({ "k": k.to_json(), "v": v.to_json() }))
+                    .collect::<Vec<_>>(),
+            }),
+            PlutusData::List(items) => json!({ "list": items.to_json() }),
+            PlutusData::Integer(BigInt::Int(n)) => match (i64::try_from(*n), u64::try_from(*n)) {
+                (Ok(n), _) => json!({ "int": n }),
+                (_, Ok(n)) => json!({ "int": n }),
+                _ if *n < 0 => json!({ "bignint": hex(&(-1 - *n).to_be_bytes()) }),
+                _ => json!({ "biguint": hex(&n.to_be_bytes()) }),
+            },
+            PlutusData::Integer(BigInt::BigUInt(bytes)) => json!({ "biguint": bytes.0.to_json() }),
+            PlutusData::Integer(BigInt::BigNInt(bytes)) => json!({ "bignint": bytes.0.to_json() }),
+            PlutusData::Bytes(bytes) => json!({ "bytes": bytes.to_json() }),
+        }
+    }
+}
+
+impl FromJson for PlutusData {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        if let Some(tag) = object.get("constructor") {
+            Ok(PlutusData::Constr {
+                tag: from_json(tag)?,
+                fields: field(object, "fields")?,
+            })
+        } else if let Some(entries) = object.get("map") {
+            array(entries)?
+                .iter()
+                .map(|entry| {
+                    let entry = self::object(entry)?;
+                    Ok((field(entry, "k")?, field(entry, "v")?))
+                })
+                .collect::<Result<_, _>>()
+                .map(PlutusData::Map)
+        } else if let Some(items) = object.get("list") {
+            Vec::from_json(items).map(PlutusData::List)
+        } else if let Some(n) = object.get("int") {
+            let n = match (n.as_i64(), n.as_u64()) {
+                (Some(n), _) => i128::from(n),
+                (_, Some(n)) => i128::from(n),
+                _ => return Err(JsonError::Malformed("expected an integer")),
+            };
+            Ok(PlutusData::Integer(BigInt::Int(n)))
+        } else if let Some(bytes) = object.get("biguint") {
+            Vec::from_json(bytes)
+                .map(|bytes| PlutusData::Integer(BigInt::BigUInt(BoundedBytes(bytes))))
+        } else if let Some(bytes) = object.get("bignint") {
+            Vec::from_json(bytes)
+                .map(|bytes| PlutusData::Integer(BigInt::BigNInt(BoundedBytes(bytes))))
+        } else if let Some(bytes) = object.get("bytes") {
+            Vec::from_json(bytes).map(PlutusData::Bytes)
+        } else {
+            Err(JsonError::Malformed("unknown Plutus data"))
+        }
+    }
+}
+
+/// Scripts are printed as the hex of their CBOR serialisation, the
+/// `[language, script]` pair used by `script_ref`.  Dijkstra's scripts are a
+/// superset of the earlier ones, so those print through them.
+impl ToJson for Script {
+    fn to_json(&self) -> Value {
+        match self {
+            Script::Native(script) => dijkstra::Script::Native(widen(script)),
+            Script::Plutus(script) => dijkstra::Script::Plutus(script.clone()),
+        }
+        .to_json()
+    }
+}
+
+impl FromJson for Script {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match dijkstra::Script::from_json(value)? {
+            dijkstra::Script::Native(script) => narrow(script).map(Script::Native),
+            dijkstra::Script::Plutus(script) => Ok(Script::Plutus(script)),
+            dijkstra::Script::PlutusV4(_) => Err(JsonError::Malformed(
+                "Plutus V4 scripts need the Dijkstra era",
+            )),
+        }
+    }
+}
+
+impl ToJson for dijkstra::Script {
+    fn to_json(&self) -> Value {
+        use dijkstra::Script;
+        let term = match self {
+            Script::Native(script) => {
+                Term::Array(vec![Term::Unsigned(0), native_script_term(script)])
+            }
+            Script::Plutus(PlutusScript::V1(bytes)) => {
+                Term::Array(vec![Term::Unsigned(1), Term::Bytes(bytes.clone())])
+            }
+            Script::Plutus(PlutusScript::V2(bytes)) => {
+                Term::Array(vec![Term::Unsigned(2), Term::Bytes(bytes.clone())])
+            }
+            Script::Plutus(PlutusScript::V3(bytes)) => {
+                Term::Array(vec![Term::Unsigned(3), Term::Bytes(bytes.clone())])
+            }
+            Script::PlutusV4(bytes) => {
+                Term::Array(vec![Term::Unsigned(4), Term::Bytes(bytes.clone())])
+            }
+        };
+        term.to_bytes().to_json()
+    }
+}
+
+impl FromJson for dijkstra::Script {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        use dijkstra::Script;
+        let bytes = Vec::from_json(value)?;
+        let term = decode_term(bytes.as_slice())
+            .map_err(|_| JsonError::Malformed("invalid script CBOR"))?;
+        match term_items(&term)? {
+            [Term::Unsigned(0), script] => native_script_from_term(script).map(Script::Native),
+            [Term::Unsigned(language), Term::Bytes(bytes)] => match language {
+                1 => Ok(Script::Plutus(PlutusScript::V1(bytes.clone()))),
+                2 => Ok(Script::Plutus(PlutusScript::V2(bytes.clone()))),
+                3 => Ok(Script::Plutus(PlutusScript::V3(bytes.clone()))),
+                4 => Ok(Script::PlutusV4(bytes.clone())),
+                _ => Err(JsonError::Malformed("unknown script language")),
+            },
+            _ => Err(JsonError::Malformed("expected a [language, script] pair")),
+        }
+    }
+}
+
+/// Native scripts on their own, as in the Shelley to Mary eras, are printed as
+/// the hex of their CBOR serialisation without the language tag.
+impl ToJson for NativeScript {
+    fn to_json(&self) -> Value {
+        widen(self).to_json()
+    }
+}
+
+impl FromJson for NativeScript {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        narrow(dijkstra::NativeScript::from_json(value)?)
+    }
+}
+
+impl ToJson for dijkstra::NativeScript {
+    fn to_json(&self) -> Value {
+        native_script_term(self).to_bytes().to_json()
+    }
+}
+
+impl FromJson for dijkstra::NativeScript {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let bytes = Vec::from_json(value)?;
+        let term = decode_term(bytes.as_slice())
+            .map_err(|_| JsonError::Malformed("invalid script CBOR"))?;
+        native_script_from_term(&term)
+    }
+}
+
+fn widen(script: &NativeScript) -> dijkstra::NativeScript {
+    let scripts = |scripts: &[NativeScript]| scripts.iter().map(widen).collect();
+    match script {
+        NativeScript::ScriptPubkey(hash) => dijkstra::NativeScript::ScriptPubkey(hash.clone()),
+        NativeScript::ScriptAll(all) => dijkstra::NativeScript::ScriptAll(scripts(all)),
+        NativeScript::ScriptAny(any) => dijkstra::NativeScript::ScriptAny(scripts(any)),
+        NativeScript::ScriptNOfK {
+            required,
+            scripts: k,
+        } => dijkstra::NativeScript::ScriptNOfK {
+            required: *required,
+            scripts: scripts(k),
+        },
+        NativeScript::InvalidBefore(slot) => dijkstra::NativeScript::InvalidBefore(*slot),
+        NativeScript::InvalidHereafter(slot) => dijkstra::NativeScript::InvalidHereafter(*slot),
+    }
+}
+
+/// Fails on `RequireGuard`, which only Dijkstra scripts can hold.
+fn narrow(script: dijkstra::NativeScript) -> Result<NativeScript, JsonError> {
+    let scripts = |scripts: Vec<dijkstra::NativeScript>| -> Result<Vec<_>, JsonError> {
+        scripts.into_iter().map(narrow).collect()
+    };
+    Ok(match script {
+        dijkstra::NativeScript::ScriptPubkey(hash) => NativeScript::ScriptPubkey(hash),
+        dijkstra::NativeScript::ScriptAll(all) => NativeScript::ScriptAll(scripts(all)?),
+        dijkstra::NativeScript::ScriptAny(any) => NativeScript::ScriptAny(scripts(any)?),
+        dijkstra::NativeScript::ScriptNOfK {
+            required,
+            scripts: k,
+        } => NativeScript::ScriptNOfK {
+            required,
+            scripts: scripts(k)?,
+        },
+        dijkstra::NativeScript::InvalidBefore(slot) => NativeScript::InvalidBefore(slot),
+        dijkstra::NativeScript::InvalidHereafter(slot) => NativeScript::InvalidHereafter(slot),
+        dijkstra::NativeScript::RequireGuard(_) => {
+            return Err(JsonError::Malformed("guard scripts need the Dijkstra era"))
+        }
+    })
+}
+
+fn native_script_term(script: &dijkstra::NativeScript) -> Term {
+    use dijkstra::NativeScript;
+    let scripts =
+        |scripts: &[NativeScript]| Term::Array(scripts.iter().map(native_script_term).collect());
+    let items = match script {
+        NativeScript::ScriptPubkey(hash) => {
+            vec![Term::Unsigned(0), Term::Bytes(hash.0 .0.to_vec())]
+        }
+        NativeScript::ScriptAll(all) => vec![Term::Unsigned(1), scripts(all)],
+        NativeScript::ScriptAny(any) => vec![Term::Unsigned(2), scripts(any)],
+        NativeScript::ScriptNOfK {
+            required,
+            scripts: k,
+        } => {
+            let required = u64::try_from(*required)
+                .map_or(Term::Negative(i128::from(*required)), Term::Unsigned);
+            vec![Term::Unsigned(3), required, scripts(k)]
+        }
+        NativeScript::InvalidBefore(slot) => vec![Term::Unsigned(4), Term::Unsigned(slot.0)],
+        NativeScript::InvalidHereafter(slot) => vec![Term::Unsigned(5), Term::Unsigned(slot.0)],
+        NativeScript::RequireGuard(credential) => {
+            let (tag, hash) = match credential {
+                Credential::KeyHash(hash) => (0, hash.0 .0),
+                Credential::ScriptHash(hash) => (1, hash.0 .0),
+            };
+            let credential = Term::Array(vec![Term::Unsigned(tag), Term::Bytes(hash.to_vec())]);
+            vec![Term::Unsigned(6), credential]
+        }
+    };
+    Term::Array(items)
+}
+
+fn native_script_from_term(term: &Term) -> Result<dijkstra::NativeScript, JsonError> {
+    use dijkstra::NativeScript;
+    let scripts = |term: &Term| -> Result<Vec<NativeScript>, JsonError> {
+        term_items(term)?
+            .iter()
+            .map(native_script_from_term)
+            .collect()
+    };
+    match term_items(term)? {
+        [Term::Unsigned(0), Term::Bytes(hash)] => {
+            let hash = hash
+                .as_slice()
+                .try_into()
+                .map_err(|_| JsonError::Malformed("unexpected hash length"))?;
+            Ok(NativeScript::ScriptPubkey(AddrKeyHash(Hash28(hash))))
+        }
+        [Term::Unsigned(1), all] => scripts(all).map(NativeScript::ScriptAll),
+        [Term::Unsigned(2), any] => scripts(any).map(NativeScript::ScriptAny),
+        [Term::Unsigned(3), required, k] => {
+            let required = match required {
+                Term::Unsigned(n) => i64::try_from(*n).ok(),
+                Term::Negative(n) => i64::try_from(*n).ok(),
+                _ => None,
+            }
+            .ok_or(JsonError::Malformed("invalid required script count"))?;
+            Ok(NativeScript::ScriptNOfK {
+                required,
+                scripts: scripts(k)?,
+            })
+        }
+        [Term::Unsigned(4), Term::Unsigned(slot)] => Ok(NativeScript::InvalidBefore(SlotNo(*slot))),
+        [Term::Unsigned(5), Term::Unsigned(slot)] => {
+            Ok(NativeScript::InvalidHereafter(SlotNo(*slot)))
+        }
+        [Term::Unsigned(6), credential] => match term_items(credential)? {
+            [Term::Unsigned(tag @ (0 | 1)), Term::Bytes(hash)] => {
+                let hash = Hash28(
+                    hash.as_slice()
+                        .try_into()
+                        .map_err(|_| JsonError::Malformed("unexpected hash length"))?,
+                );
+                Ok(NativeScript::RequireGuard(if *tag == 0 {
+                    Credential::KeyHash(AddrKeyHash(hash))
+                } else {
+                    Credential::ScriptHash(ScriptHash(hash))
+                }))
+            }
+            _ => Err(JsonError::Malformed("invalid guard credential")),
+        },
+        _ => Err(JsonError::Malformed("unknown native script")),
+    }
+}
+
+fn term_items(term: &Term) -> Result<&[Term], JsonError> {
+    match term {
+        Term::Array(items) => Ok(items),
+        _ => Err(JsonError::Malformed("expected a CBOR array")),
+    }
+}
+
+impl<S: ToJson> ToJson for ScriptRef<S> {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+}
+
+impl<S: FromJson> FromJson for ScriptRef<S> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        S::from_json(value).map(ScriptRef)
+    }
+}
+
+/// Alonzo outputs add `datahash`; Babbage outputs carry `datum` and
+/// `referenceScript`, whose presence is how the decoder tells them apart.
+///
+/// The Haskell `Datum` instance prints an inline datum as its hash, which
+/// would need a Blake2b implementation here; inline datums are printed as
+/// Plutus data instead.
+impl<E: Era> ToJson for TxOutStruct<E> {
+    fn to_json(&self) -> Value {
+        match self {
+            TxOutStruct::Shelley {
+                address,
+                amount,
+                datum_hash,
+            } => {
+                let mut object = Map::new();
+                object.insert("address".into(), address.to_json());
+                object.insert("value".into(), amount.to_json());
+                insert_optional(&mut object, "datahash", datum_hash);
+                Value::Object(object)
+            }
+            TxOutStruct::Babbage {
+                address,
+                amount,
+                datum_option,
+                script_ref,
+            } => json!({
+                "address": address.to_json(),
+                "value": amount.to_json(),
+                "datum": match datum_option {
+                    None => Value::Null,
+                    Some(DatumOption::Hash(hash)) => hash.to_json(),
+                    Some(DatumOption::Inline(data)) => data.to_json(),
+                },
+                "referenceScript": script_ref.to_json(),
+            }),
+        }
+    }
+}
+
+impl<E: Era> FromJson for TxOutStruct<E> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        if object.contains_key("datahash") && E::INDEX < Alonzo::INDEX {
+            return Err(JsonError::Malformed(
+                "datum hashes on outputs need the Alonzo era",
+            ));
+        }
+        if !object.contains_key("referenceScript") {
+            return Ok(TxOutStruct::Shelley {
+                address: field(object, "address")?,
+                amount: field(object, "value")?,
+                datum_hash: optional(object, "datahash")?,
+            });
+        }
+        let datum_option = match object.get("datum") {
+            None | Some(Value::Null) => None,
+            Some(hash @ Value::String(_)) => Some(DatumOption::Hash(from_json(hash)?)),
+            Some(data) => Some(DatumOption::Inline(from_json(data)?)),
+        };
+        if E::INDEX < Babbage::INDEX {
+            return Err(JsonError::Malformed(
+                "reference scripts on outputs need the Babbage era",
+            ));
+        }
+        Ok(TxOutStruct::Babbage {
+            address: field(object, "address")?,
+            amount: field(object, "value")?,
+            datum_option,
+            script_ref: optional(object, "referenceScript")?,
+        })
+    }
+}
+
+newtype_json!(UTxOStruct(BTreeMap<TxIn, TxOutStruct>));
+
+impl ToJson for VoterEnum {
+    fn to_json(&self) -> Value {
+        match self {
+            VoterEnum::CommitteeKey(hash) => {
+                Tagged::new("CommitteeVoter").field(&Credential::KeyHash(hash.clone()))
+            }
+            VoterEnum::CommitteeScript(hash) => {
+                Tagged::new("CommitteeVoter").field(&Credential::ScriptHash(hash.clone()))
+            }
+            VoterEnum::DRepKey(hash) => {
+                Tagged::new("DRepVoter").field(&Credential::KeyHash(hash.clone()))
+            }
+            VoterEnum::DRepScript(hash) => {
+                Tagged::new("DRepVoter").field(&Credential::ScriptHash(hash.clone()))
+            }
+            VoterEnum::StakePool(hash) => Tagged::new("StakePoolVoter").field(hash),
+        }
+        .build()
+    }
+}
+
+impl FromJson for VoterEnum {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let contents = Contents::of(value, "Voter")?;
+        Ok(match contents.tag {
+            "CommitteeVoter" => voter_from_credential(true, contents.field()?),
+            "DRepVoter" => voter_from_credential(false, contents.field()?),
+            "StakePoolVoter" => VoterEnum::StakePool(contents.field()?),
+            _ => return contents.unknown(),
+        })
+    }
+}
+
+fn voter_from_credential(committee: bool, credential: Credential) -> VoterEnum {
+    match (committee, credential) {
+        (true, Credential::KeyHash(hash)) => VoterEnum::CommitteeKey(hash),
+        (true, Credential::ScriptHash(hash)) => VoterEnum::CommitteeScript(hash),
+        (false, Credential::KeyHash(hash)) => VoterEnum::DRepKey(hash),
+        (false, Credential::ScriptHash(hash)) => VoterEnum::DRepScript(hash),
+    }
+}
+
+/// `committee-`, `drep-` or `stakepool-` followed by the credential text.
+impl ToJsonKey for VoterEnum {
+    fn to_json_key(&self) -> String {
+        match self {
+            VoterEnum::CommitteeKey(hash) => {
+                format!(
+                    "committee-{}",
+                    Credential::KeyHash(hash.clone()).to_json_key()
+                )
+            }
+            VoterEnum::CommitteeScript(hash) => {
+                format!(
+                    "committee-{}",
+                    Credential::ScriptHash(hash.clone()).to_json_key()
+                )
+            }
+            VoterEnum::DRepKey(hash) => {
+                format!("drep-{}", Credential::KeyHash(hash.clone()).to_json_key())
+            }
+            VoterEnum::DRepScript(hash) => {
+                format!(
+                    "drep-{}",
+                    Credential::ScriptHash(hash.clone()).to_json_key()
+                )
+            }
+            VoterEnum::StakePool(hash) => {
+                format!(
+                    "stakepool-{}",
+                    Credential::KeyHash(AddrKeyHash(hash.0)).to_json_key()
+                )
+            }
+        }
+    }
+}
+
+impl FromJsonKey for VoterEnum {
+    fn from_json_key(key: &str) -> Result<Self, JsonError> {
+        if let Some(credential) = key.strip_prefix("committee-") {
+            Credential::from_json_key(credential).map(|c| voter_from_credential(true, c))
+        } else if let Some(credential) = key.strip_prefix("drep-") {
+            Credential::from_json_key(credential).map(|c| voter_from_credential(false, c))
+        } else if let Some(credential) = key.strip_prefix("stakepool-") {
+            match Credential::from_json_key(credential)? {
+                Credential::KeyHash(hash) => Ok(VoterEnum::StakePool(PoolKeyHash(hash.0))),
+                Credential::ScriptHash(_) => {
+                    Err(JsonError::Malformed("stake pool voters are key hashes"))
+                }
+            }
+        } else {
+            Err(JsonError::Malformed("unknown voter prefix"))
+        }
+    }
+}
+
+impl ToJson for GovActionId {
+    fn to_json(&self) -> Value {
+        json!({ "txId": self.tx_id.to_json(), "govActionIx": self.action_index.to_json() })
+    }
+}
+
+impl FromJson for GovActionId {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(GovActionId {
+            tx_id: field(object, "txId")?,
+            action_index: field(object, "govActionIx")?,
+        })
+    }
+}
+
+/// `govActionIdToText`: the transaction id in hex, `#`, and the index.
+impl ToJsonKey for GovActionId {
+    fn to_json_key(&self) -> String {
+        format!("{}#{}", hex(&self.tx_id.0 .0), self.action_index.0)
+    }
+}
+
+impl FromJsonKey for GovActionId {
+    fn from_json_key(key: &str) -> Result<Self, JsonError> {
+        let (tx_id, index) = split_id(key)?;
+        Ok(GovActionId {
+            tx_id,
+            action_index: GovActionIx(index),
+        })
+    }
+}
+
+impl ToJson for VotingProcedure {
+    fn to_json(&self) -> Value {
+        let decision = match self.vote {
+            0 => "VoteNo",
+            1 => "VoteYes",
+            _ => "Abstain",
+        };
+        json!({ "anchor": self.anchor.to_json(), "decision": decision })
+    }
+}
+
+impl FromJson for VotingProcedure {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        let vote = match string(member(object, "decision")?)? {
+            "VoteNo" => 0,
+            "VoteYes" => 1,
+            "Abstain" => 2,
+            _ => return Err(JsonError::Malformed("unknown vote")),
+        };
+        Ok(VotingProcedure {
+            vote,
+            anchor: optional(object, "anchor")?,
+        })
+    }
+}
+
+newtype_json!(VotingProceduresStruct(BTreeMap<VoterEnum, BTreeMap<GovActionId, VotingProcedure>>));
+
+impl ToJson for Constitution {
+    fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        object.insert("anchor".into(), self.anchor.to_json());
+        insert_optional(&mut object, "script", &self.script_hash);
+        Value::Object(object)
+    }
+}
+
+impl FromJson for Constitution {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(Constitution {
+            anchor: field(object, "anchor")?,
+            script_hash: optional(object, "script")?,
+        })
+    }
+}
+
+/// Cost models are keyed by language name; models for languages the ledger
+/// does not know are grouped under `Unknown`, keyed by their number.
+impl ToJson for CostModels {
+    fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        let mut unknown = Map::new();
+        for (language, model) in &self.0 {
+            match language_of(*language) {
+                Some(language) => object.insert(language_name(language).into(), model.to_json()),
+                None => unknown.insert(language.to_string(), model.to_json()),
+            };
+        }
+        if !unknown.is_empty() {
+            object.insert("Unknown".into(), Value::Object(unknown));
+        }
+        Value::Object(object)
+    }
+}
+
+impl FromJson for CostModels {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let mut models = BTreeMap::new();
+        for (key, model) in object(value)? {
+            if key == "Unknown" {
+                for (language, model) in object(model)? {
+                    let language = language
+                        .parse()
+                        .map_err(|_| JsonError::Malformed("invalid cost model language"))?;
+                    models.insert(language, from_json(model)?);
+                }
+            } else {
+                let language = Language::from_json(&Value::String(key.clone()))?;
+                models.insert(language as u8, from_json(model)?);
+            }
+        }
+        Ok(CostModels(models))
+    }
+}
+
+impl ToJson for ExUnitPrices {
+    fn to_json(&self) -> Value {
+        json!({
+            "priceSteps": self.step_price.to_json(),
+            "priceMemory": self.mem_price.to_json(),
+        })
+    }
+}
+
+impl FromJson for ExUnitPrices {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(ExUnitPrices {
+            mem_price: field(object, "priceMemory")?,
+            step_price: field(object, "priceSteps")?,
+        })
+    }
+}
+
+impl ToJson for PoolVotingThresholds {
+    fn to_json(&self) -> Value {
+        json!({
+            "motionNoConfidence": self.motion_no_confidence.to_json(),
+            "committeeNormal": self.committee_normal.to_json(),
+            "committeeNoConfidence": self.committee_no_confidence.to_json(),
+            "hardForkInitiation": self.hard_fork_initiation.to_json(),
+            "ppSecurityGroup": self.security_parameter.to_json(),
+        })
+    }
+}
+
+impl FromJson for PoolVotingThresholds {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(PoolVotingThresholds {
+            motion_no_confidence: field(object, "motionNoConfidence")?,
+            committee_normal: field(object, "committeeNormal")?,
+            committee_no_confidence: field(object, "committeeNoConfidence")?,
+            hard_fork_initiation: field(object, "hardForkInitiation")?,
+            security_parameter: field(object, "ppSecurityGroup")?,
+        })
+    }
+}
+
+impl ToJson for DRepVotingThresholds {
+    fn to_json(&self) -> Value {
+        json!({
+            "motionNoConfidence": self.motion_no_confidence.to_json(),
+            "committeeNormal": self.committee_normal.to_json(),
+            "committeeNoConfidence": self.committee_no_confidence.to_json(),
+            "updateToConstitution": self.update_constitution.to_json(),
+            "hardForkInitiation": self.hard_fork_initiation.to_json(),
+            "ppNetworkGroup": self.pparam_network.to_json(),
+            "ppEconomicGroup": self.pparam_economic.to_json(),
+            "ppTechnicalGroup": self.pparam_technical.to_json(),
+            "ppGovGroup": self.pparam_governance.to_json(),
+            "treasuryWithdrawal": self.treasury_withdrawal.to_json(),
+        })
+    }
+}
+
+impl FromJson for DRepVotingThresholds {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(DRepVotingThresholds {
+            motion_no_confidence: field(object, "motionNoConfidence")?,
+            committee_normal: field(object, "committeeNormal")?,
+            committee_no_confidence: field(object, "committeeNoConfidence")?,
+            update_constitution: field(object, "updateToConstitution")?,
+            hard_fork_initiation: field(object, "hardForkInitiation")?,
+            pparam_network: field(object, "ppNetworkGroup")?,
+            pparam_economic: field(object, "ppEconomicGroup")?,
+            pparam_technical: field(object, "ppTechnicalGroup")?,
+            pparam_governance: field(object, "ppGovGroup")?,
+            treasury_withdrawal: field(object, "treasuryWithdrawal")?,
+        })
+    }
+}
+
+/// Applies `$apply!(json_name, field)` to every protocol parameter, in the
+/// order of the Conway `PParams` table.
+macro_rules! protocol_params {
+    ($apply:ident) => {
+        $apply!(
+            "txFeePerByte" => minfee_a,
+            "txFeeFixed" => minfee_b,
+            "maxBlockBodySize" => max_block_body_size,
+            "maxTxSize" => max_tx_size,
+            "maxBlockHeaderSize" => max_block_header_size,
+            "stakeAddressDeposit" => key_deposit,
+            "stakePoolDeposit" => pool_deposit,
+            "poolRetireMaxEpoch" => max_epoch,
+            "stakePoolTargetNum" => desired_number_of_pools,
+            "poolPledgeInfluence" => pool_pledge_influence,
+            "monetaryExpansion" => expansion_rate,
+            "treasuryCut" => treasury_growth_rate,
+            "minPoolCost" => min_pool_cost,
+            "utxoCostPerByte" => ada_per_utxo_byte,
+            "costModels" => cost_models,
+            "executionUnitPrices" => ex_unit_prices,
+            "maxTxExecutionUnits" => max_tx_ex_units,
+            "maxBlockExecutionUnits" => max_block_ex_units,
+            "maxValueSize" => max_value_size,
+            "collateralPercentage" => collateral_percentage,
+            "maxCollateralInputs" => max_collateral_inputs,
+            "poolVotingThresholds" => pool_voting_thresholds,
+            "dRepVotingThresholds" => drep_voting_thresholds,
+            "committeeMinSize" => min_committee_size,
+            "committeeMaxTermLength" => committee_term_limit,
+            "govActionLifetime" => governance_action_validity_period,
+            "govActionDeposit" => governance_action_deposit,
+            "dRepDeposit" => drep_deposit,
+            "dRepActivity" => drep_inactivity_period,
+            "minFeeRefScriptCostPerByte" => ref_script_coins_per_byte,
+        )
+    };
+}
+
+/// A parameter update lists only the parameters it changes.
+impl ToJson for ProtocolParamUpdate {
+    fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        macro_rules! insert {
+            ($($name:literal => $field:ident),* $(,)?) => {
+                $(insert_optional(&mut object, $name, &self.$field);)*
+            };
+        }
+        protocol_params!(insert);
+        Value::Object(object)
+    }
+}
+
+impl FromJson for ProtocolParamUpdate {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        macro_rules! read {
+            ($($name:literal => $field:ident),* $(,)?) => {
+                ProtocolParamUpdate {
+                    $($field: optional(object, $name)?,)*
+                }
+            };
+        }
+        Ok(protocol_params!(read))
+    }
+}
+
+/// A previous action is printed as its `GovActionId`; its purpose is implied
+/// by the action type.
+fn previous_action(
+    value: &Value,
+    purpose: GovActionPurpose,
+) -> Result<Option<GovPurposeIdStruct>, JsonError> {
+    Ok(Option::<GovActionId>::from_json(value)?.map(|id| GovPurposeIdStruct { purpose, id }))
+}
+
+fn hard_fork_action(value: &Value) -> Result<Option<GovPurposeIdStruct>, JsonError> {
+    previous_action(value, GovActionPurpose::HardFork)
+}
+
+impl ToJson for GovPurposeIdStruct {
+    fn to_json(&self) -> Value {
+        self.id.to_json()
+    }
+}
+
+impl ToJson for GovActionStruct {
+    fn to_json(&self) -> Value {
+        match self {
+            GovActionStruct::ParameterChange {
+                previous,
+                update,
+                policy_hash,
+            } => Tagged::new("ParameterChange")
+                .field(previous)
+                .field(update)
+                .field(policy_hash),
+            GovActionStruct::HardForkInitiation {
+                previous,
+                protocol_version,
+            } => Tagged::new("HardForkInitiation")
+                .field(previous)
+                .field(protocol_version),
+            GovActionStruct::TreasuryWithdrawals {
+                withdrawals,
+                policy_hash,
+            } => Tagged::new("TreasuryWithdrawals")
+                .field(withdrawals)
+                .field(policy_hash),
+            GovActionStruct::NoConfidence { previous } => {
+                Tagged::new("NoConfidence").field(previous)
+            }
+            GovActionStruct::UpdateCommittee {
+                previous,
+                removals,
+                additions,
+                new_quorum,
+            } => Tagged::new("UpdateCommittee")
+                .field(previous)
+                .field(removals)
+                .field(additions)
+                .field(new_quorum),
+            GovActionStruct::NewConstitution {
+                previous,
+                constitution,
+            } => Tagged::new("NewConstitution")
+                .field(previous)
+                .field(constitution),
+            GovActionStruct::InfoAction => Tagged::new("InfoAction"),
+        }
+        .build()
+    }
+}
+
+impl FromJson for GovActionStruct {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let contents = Contents::of(value, "GovAction")?;
+        Ok(match contents.tag {
+            "ParameterChange" => {
+                let [previous, update, policy_hash] = contents.fields()?;
+                GovActionStruct::ParameterChange {
+                    previous: previous_action(previous, GovActionPurpose::ParameterChange)?,
+                    update: from_json(update)?,
+                    policy_hash: from_json(policy_hash)?,
+                }
+            }
+            "HardForkInitiation" => {
+                let [previous, protocol_version] = contents.fields()?;
+                GovActionStruct::HardForkInitiation {
+                    previous: previous_action(previous, GovActionPurpose::HardFork)?,
+                    protocol_version: from_json(protocol_version)?,
+                }
+            }
+            "TreasuryWithdrawals" => {
+                let [withdrawals, policy_hash] = contents.fields()?;
+                GovActionStruct::TreasuryWithdrawals {
+                    withdrawals: from_json(withdrawals)?,
+                    policy_hash: from_json(policy_hash)?,
+                }
+            }
+            "NoConfidence" => GovActionStruct::NoConfidence {
+                previous: previous_action(contents.contents()?, GovActionPurpose::Committee)?,
+            },
+            "UpdateCommittee" => {
+                let [previous, removals, additions, new_quorum] = contents.fields()?;
+                GovActionStruct::UpdateCommittee {
+                    previous: previous_action(previous, GovActionPurpose::Committee)?,
+                    removals: from_json(removals)?,
+                    additions: from_json(additions)?,
+                    new_quorum: from_json(new_quorum)?,
+                }
+            }
+            "NewConstitution" => {
+                let [previous, constitution] = contents.fields()?;
+                GovActionStruct::NewConstitution {
+                    previous: previous_action(previous, GovActionPurpose::Constitution)?,
+                    constitution: from_json(constitution)?,
+                }
+            }
+            "InfoAction" => GovActionStruct::InfoAction,
+            _ => return contents.unknown(),
+        })
+    }
+}
+
+impl ToJson for ProposalProcedureStruct {
+    fn to_json(&self) -> Value {
+        json!({
+            "deposit": self.deposit.to_json(),
+            "returnAddr": self.reward_account.to_json(),
+            "govAction": self.action.to_json(),
+            "anchor": self.anchor.to_json(),
+        })
+    }
+}
+
+impl FromJson for ProposalProcedureStruct {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let object = object(value)?;
+        Ok(ProposalProcedureStruct {
+            deposit: field(object, "deposit")?,
+            reward_account: field(object, "returnAddr")?,
+            action: field(object, "govAction")?,
+            anchor: field(object, "anchor")?,
+        })
+    }
+}
+
+/// Plutus purposes use `kindObject`: `{"kind": "ConwaySpending", "value":
+/// x}`.  The shared stand-in types do not record their era, so the Conway
+/// names are printed; Dijkstra's own purposes print the Dijkstra names.  Any
+/// era's names are accepted when decoding.
+fn purpose_json(era: &str, name: &str, value: Value) -> Value {
+    json!({ "kind": format!("{era}{name}"), "value": value })
+}
+
+/// The constructor name without its era prefix, and the purpose's value.
+fn purpose_from_json(value: &Value) -> Result<(&str, &Value), JsonError> {
+    let object = object(value)?;
+    let kind = string(member(object, "kind")?)?;
+    let name = kind
+        .strip_prefix("Conway")
+        .or(kind.strip_prefix("Alonzo"))
+        .or(kind.strip_prefix("Dijkstra"))
+        .unwrap_or(kind);
+    Ok((name, member(object, "value")?))
+}
+
+fn unknown_purpose<T>(value: &Value) -> Result<T, JsonError> {
+    Err(JsonError::UnknownConstructor {
+        family: "PlutusPurpose",
+        tag: string(member(object(value)?, "kind")?)?.to_owned(),
+    })
+}
+
+impl ToJson for PlutusPurposeStruct {
+    fn to_json(&self) -> Value {
+        let name = match self.tag {
+            RedeemerTag::Spend => "Spending",
+            RedeemerTag::Mint => "Minting",
+            RedeemerTag::Cert => "Certifying",
+            RedeemerTag::Reward => "Rewarding",
+            RedeemerTag::Voting => "Voting",
+            RedeemerTag::Proposing => "Proposing",
+        };
+        purpose_json("Conway", name, self.index.to_json())
+    }
+}
+
+impl FromJson for PlutusPurposeStruct {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let (name, index) = purpose_from_json(value)?;
+        let tag = match name {
+            "Spending" => RedeemerTag::Spend,
+            "Minting" => RedeemerTag::Mint,
+            "Certifying" => RedeemerTag::Cert,
+            "Rewarding" => RedeemerTag::Reward,
+            "Voting" => RedeemerTag::Voting,
+            "Proposing" => RedeemerTag::Proposing,
+            _ => return unknown_purpose(value),
+        };
+        Ok(PlutusPurposeStruct {
+            tag,
+            index: from_json(index)?,
+        })
+    }
+}
+
+impl ToJson for PlutusPurposeItemStruct {
+    fn to_json(&self) -> Value {
+        let (name, item) = match self {
+            PlutusPurposeItemStruct::Spending(input) => ("Spending", input.to_json()),
+            PlutusPurposeItemStruct::Minting(policy) => ("Minting", policy.to_json()),
+            PlutusPurposeItemStruct::Certifying(cert) => ("Certifying", cert.to_json()),
+            PlutusPurposeItemStruct::Rewarding(account) => ("Rewarding", account.to_json()),
+            PlutusPurposeItemStruct::Voting(voter) => ("Voting", voter.to_json()),
+            PlutusPurposeItemStruct::Proposing(proposal) => ("Proposing", proposal.to_json()),
+        };
+        purpose_json("Conway", name, item)
+    }
+}
+
+impl FromJson for PlutusPurposeItemStruct {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let (name, item) = purpose_from_json(value)?;
+        Ok(match name {
+            "Spending" => PlutusPurposeItemStruct::Spending(from_json(item)?),
+            "Minting" => PlutusPurposeItemStruct::Minting(from_json(item)?),
+            "Certifying" => PlutusPurposeItemStruct::Certifying(from_json(item)?),
+            "Rewarding" => PlutusPurposeItemStruct::Rewarding(from_json(item)?),
+            "Voting" => PlutusPurposeItemStruct::Voting(from_json(item)?),
+            "Proposing" => PlutusPurposeItemStruct::Proposing(from_json(item)?),
+            _ => return unknown_purpose(value),
+        })
+    }
+}
+
+impl ToJson for AlonzoPlutusPurpose {
+    fn to_json(&self) -> Value {
+        let name = match self.tag {
+            AlonzoRedeemerTag::Spend => "Spending",
+            AlonzoRedeemerTag::Mint => "Minting",
+            AlonzoRedeemerTag::Cert => "Certifying",
+            AlonzoRedeemerTag::Reward => "Rewarding",
+        };
+        purpose_json("Alonzo", name, self.index.to_json())
+    }
+}
+
+impl FromJson for AlonzoPlutusPurpose {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let (name, index) = purpose_from_json(value)?;
+        let tag = match name {
+            "Spending" => AlonzoRedeemerTag::Spend,
+            "Minting" => AlonzoRedeemerTag::Mint,
+            "Certifying" => AlonzoRedeemerTag::Cert,
+            "Rewarding" => AlonzoRedeemerTag::Reward,
+            _ => return unknown_purpose(value),
+        };
+        Ok(AlonzoPlutusPurpose {
+            tag,
+            index: from_json(index)?,
+        })
+    }
+}
+
+impl ToJson for AlonzoPlutusPurposeItem {
+    fn to_json(&self) -> Value {
+        use AlonzoPlutusPurposeItem::*;
+        let (name, item) = match self {
+            Spending(input) => ("Spending", input.to_json()),
+            Minting(policy) => ("Minting", policy.to_json()),
+            Certifying(cert) => ("Certifying", cert.to_json()),
+            Rewarding(account) => ("Rewarding", account.to_json()),
+        };
+        purpose_json("Alonzo", name, item)
+    }
+}
+
+impl FromJson for AlonzoPlutusPurposeItem {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        use AlonzoPlutusPurposeItem::*;
+        let (name, item) = purpose_from_json(value)?;
+        Ok(match name {
+            "Spending" => Spending(from_json(item)?),
+            "Minting" => Minting(from_json(item)?),
+            "Certifying" => Certifying(from_json(item)?),
+            "Rewarding" => Rewarding(from_json(item)?),
+            _ => return unknown_purpose(value),
+        })
+    }
+}
+
+impl ToJson for dijkstra::PlutusPurpose {
+    fn to_json(&self) -> Value {
+        use dijkstra::RedeemerTag;
+        let name = match self.tag {
+            RedeemerTag::Spend => "Spending",
+            RedeemerTag::Mint => "Minting",
+            RedeemerTag::Cert => "Certifying",
+            RedeemerTag::Reward => "Rewarding",
+            RedeemerTag::Voting => "Voting",
+            RedeemerTag::Proposing => "Proposing",
+            RedeemerTag::Guarding => "Guarding",
+        };
+        purpose_json("Dijkstra", name, self.index.to_json())
+    }
+}
+
+impl FromJson for dijkstra::PlutusPurpose {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        use dijkstra::RedeemerTag;
+        let (name, index) = purpose_from_json(value)?;
+        let tag = match name {
+            "Spending" => RedeemerTag::Spend,
+            "Minting" => RedeemerTag::Mint,
+            "Certifying" => RedeemerTag::Cert,
+            "Rewarding" => RedeemerTag::Reward,
+            "Voting" => RedeemerTag::Voting,
+            "Proposing" => RedeemerTag::Proposing,
+            "Guarding" => RedeemerTag::Guarding,
+            _ => return unknown_purpose(value),
+        };
+        Ok(dijkstra::PlutusPurpose {
+            tag,
+            index: from_json(index)?,
+        })
+    }
+}
+
+impl ToJson for dijkstra::PlutusPurposeItem {
+    fn to_json(&self) -> Value {
+        use dijkstra::PlutusPurposeItem::*;
+        let (name, item) = match self {
+            Spending(input) => ("Spending", input.to_json()),
+            Minting(policy) => ("Minting", policy.to_json()),
+            Certifying(cert) => ("Certifying", cert.to_json()),
+            Rewarding(account) => ("Rewarding", account.to_json()),
+            Voting(voter) => ("Voting", voter.to_json()),
+            Proposing(proposal) => ("Proposing", proposal.to_json()),
+            Guarding(script) => ("Guarding", script.to_json()),
+        };
+        purpose_json("Dijkstra", name, item)
+    }
+}
+
+impl FromJson for dijkstra::PlutusPurposeItem {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        use dijkstra::PlutusPurposeItem::*;
+        let (name, item) = purpose_from_json(value)?;
+        Ok(match name {
+            "Spending" => Spending(from_json(item)?),
+            "Minting" => Minting(from_json(item)?),
+            "Certifying" => Certifying(from_json(item)?),
+            "Rewarding" => Rewarding(from_json(item)?),
+            "Voting" => Voting(from_json(item)?),
+            "Proposing" => Proposing(from_json(item)?),
+            "Guarding" => Guarding(from_json(item)?),
+            _ => return unknown_purpose(value),
+        })
+    }
+}
+
+impl ToJson for TxOutSource {
+    fn to_json(&self) -> Value {
+        match self {
+            TxOutSource::FromInput(input) => Tagged::new("TxOutFromInput").field(input),
+            TxOutSource::FromOutput(index) => Tagged::new("TxOutFromOutput").field(index),
+        }
+        .build()
+    }
+}
+
+impl FromJson for TxOutSource {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let contents = Contents::of(value, "TxOutSource")?;
+        Ok(match contents.tag {
+            "TxOutFromInput" => TxOutSource::FromInput(contents.field()?),
+            "TxOutFromOutput" => TxOutSource::FromOutput(contents.field()?),
+            _ => return contents.unknown(),
+        })
+    }
+}
+
+fn language_of(number: u8) -> Option<Language> {
+    match number {
+        0 => Some(Language::PlutusV1),
+        1 => Some(Language::PlutusV2),
+        2 => Some(Language::PlutusV3),
+        3 => Some(Language::PlutusV4),
+        _ => None,
+    }
+}
+
+fn language_name(language: Language) -> &'static str {
+    match language {
+        Language::PlutusV1 => "PlutusV1",
+        Language::PlutusV2 => "PlutusV2",
+        Language::PlutusV3 => "PlutusV3",
+        Language::PlutusV4 => "PlutusV4",
+    }
+}
+
+impl ToJson for Language {
+    fn to_json(&self) -> Value {
+        language_name(*self).into()
+    }
+}
+
+impl FromJson for Language {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match string(value)? {
+            "PlutusV1" => Ok(Language::PlutusV1),
+            "PlutusV2" => Ok(Language::PlutusV2),
+            "PlutusV3" => Ok(Language::PlutusV3),
+            "PlutusV4" => Ok(Language::PlutusV4),
+            _ => Err(JsonError::Malformed("unknown Plutus language")),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Predicate failures
+// ---------------------------------------------------------------------------
+
+macro_rules! json_arity {
+    () => { 0 };
+    ($head:tt $($rest:tt)*) => { 1 + json_arity!($($rest)*) };
+}
+
+/// Decode the next constructor field, attaching the relation of a
+/// `Mismatch` field.
+macro_rules! json_field {
+    ($values:ident $($inner:ident)?) => {
+        from_json($values.next().expect("arity checked by Contents::values"))?
+    };
+    ($values:ident @ $decode:ident) => {
+        $decode($values.next().expect("arity checked by Contents::values"))?
+    };
+}
+
+/// Both directions of the `TaggedObject` encoding of a predicate failure.
+///
+/// Each constructor lists its Rust fields in order and maps to its Haskell
+/// constructor name.  A field whose type the JSON does not determine
+/// names its decoder instead (`field @ decode`).  Newtype variants wrapping a
+/// nested failure are written `Variant(inner)`.
+macro_rules! failure_json {
+    ($(
+        impl$(<$param:ident: $bound:ident $(+ $bounds:ident)*>)? for $ty:ty as $family:literal {
+            $(
+                $variant:ident
+                $({ $($field:ident $(@ $decode:ident)?),* $(,)? })?
+                $(($inner:ident))?
+                => $tag:literal
+            ),* $(,)?
+        }
+    )*) => {
+        $(
+            impl$(<$param: $bound $(+ $bounds)*>)? ToJson for $ty {
+                fn to_json(&self) -> Value {
+                    match self {
+                        $(
+                            Self::$variant $({ $($field),* })? $(($inner))? => {
+                                Tagged::new($tag)
+                                    $($(.field($field))*)?
+                                    $(.field($inner))?
+                                    .build()
+                            }
+                        )*
+                    }
+                }
+            }
+
+            impl$(<$param: $bound $(+ $bounds)*>)? FromJson for $ty {
+                fn from_json(value: &Value) -> Result<Self, JsonError> {
+                    let contents = Contents::of(value, $family)?;
+                    Ok(match contents.tag {
+                        $(
+                            $tag => {
+                                let arity = json_arity!($($($field)*)? $($inner)?);
+                                #[allow(unused_mut, unused_variables)]
+                                let mut values = contents.values(arity)?.into_iter();
+                                Self::$variant
+                                    $({ $($field: json_field!(values $(@ $decode)?),)* })?
+                                    $((json_field!(values $inner)))?
+                            }
+                        )*
+                        _ => return contents.unknown(),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl ToJson for shelley::VotingPeriod {
+    fn to_json(&self) -> Value {
+        match self {
+            shelley::VotingPeriod::VoteForThisEpoch => "VoteForThisEpoch".into(),
+            shelley::VotingPeriod::VoteForNextEpoch => "VoteForNextEpoch".into(),
+        }
+    }
+}
+
+impl FromJson for shelley::VotingPeriod {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match string(value)? {
+            "VoteForThisEpoch" => Ok(shelley::VotingPeriod::VoteForThisEpoch),
+            "VoteForNextEpoch" => Ok(shelley::VotingPeriod::VoteForNextEpoch),
+            _ => Err(JsonError::Malformed("unknown voting period")),
+        }
+    }
+}
+
+failure_json! {
+    impl for shelley::PpupPredicateFailure as "shelley::PpupPredicateFailure" {
+        NonGenesisUpdatePPUP { offending_keys } => "NonGenesisUpdatePPUP",
+        PPUpdateWrongEpoch { current_epoch, declared_epoch, voting_period } => "PPUpdateWrongEpoch",
+        PVCannotFollowPPUP { proposed_version } => "PVCannotFollowPPUP",
+    }
+
+    impl<E: Era> for shelley::UtxoPredicateFailure<E> as "shelley::UtxoPredicateFailure" {
+        BadInputsUTxO { invalid_inputs } => "BadInputsUTxO",
+        ExpiredUTxO { ttl_mismatch } => "ExpiredUTxO",
+        MaxTxSizeUTxO { size_mismatch } => "MaxTxSizeUTxO",
+        InputSetEmptyUTxO => "InputSetEmptyUTxO",
+        FeeTooSmallUTxO { fee_mismatch } => "FeeTooSmallUTxO",
+        ValueNotConservedUTxO { balance_mismatch } => "ValueNotConservedUTxO",
+        OutputTooSmallUTxO { tiny_outputs } => "OutputTooSmallUTxO",
+        UpdateFailure(inner) => "UpdateFailure",
+        WrongNetwork { expected, offending } => "WrongNetwork",
+        WrongNetworkWithdrawal { expected, offending } => "WrongNetworkWithdrawal",
+        OutputBootAddrAttrsTooBig { oversized_bootstrap_outputs } => "OutputBootAddrAttrsTooBig",
+    }
+
+    impl<Utxo: ToJson + FromJson> for shelley::UtxowPredicateFailure<Utxo> as "shelley::UtxowPredicateFailure" {
+        InvalidWitnessesUTXOW { invalid_witnesses } => "InvalidWitnessesUTXOW",
+        MissingVKeyWitnessesUTXOW { missing_signers } => "MissingVKeyWitnessesUTXOW",
+        MissingScriptWitnessesUTXOW { missing_scripts } => "MissingScriptWitnessesUTXOW",
+        ScriptWitnessNotValidatingUTXOW { failed_scripts } => "ScriptWitnessNotValidatingUTXOW",
+        UtxoFailure(inner) => "UtxoFailure",
+        MIRInsufficientGenesisSigsUTXOW { missing_signatures } => "MIRInsufficientGenesisSigsUTXOW",
+        MissingTxBodyMetadataHash { expected } => "MissingTxBodyMetadataHash",
+        MissingTxMetadata { referenced } => "MissingTxMetadata",
+        ConflictingMetadataHash { mismatch } => "ConflictingMetadataHash",
+        InvalidMetadata => "InvalidMetadata",
+        ExtraneousScriptWitnessesUTXOW { extra_scripts } => "ExtraneousScriptWitnessesUTXOW",
+    }
+
+    impl for shelley::DelegPredicateFailure as "shelley::DelegPredicateFailure" {
+        StakeKeyAlreadyRegistered { credential } => "StakeKeyAlreadyRegisteredDELEG",
+        StakeKeyNotRegistered { credential } => "StakeKeyNotRegisteredDELEG",
+        StakeKeyNonZeroAccountBalance { remaining_balance } => "StakeKeyNonZeroAccountBalanceDELEG",
+        StakeDelegationImpossible { credential } => "StakeDelegationImpossibleDELEG",
+        WrongCertificateType => "WrongCertificateTypeDELEG",
+        GenesisKeyNotInMapping { genesis_key } => "GenesisKeyNotInMappingDELEG",
+        DuplicateGenesisDelegate { delegate } => "DuplicateGenesisDelegateDELEG",
+        InsufficientForInstantaneousRewards { pot, bound } => "InsufficientForInstantaneousRewardsDELEG",
+        MIRCertificateTooLateInEpoch { cutoff } => "MIRCertificateTooLateinEpochDELEG",
+        DuplicateGenesisVRF { vrf } => "DuplicateGenesisVRFDELEG",
+        MIRTransferNotCurrentlyAllowed => "MIRTransferNotCurrentlyAllowed",
+        MIRNegativesNotCurrentlyAllowed => "MIRNegativesNotCurrentlyAllowed",
+        InsufficientForTransfer { pot, bound } => "InsufficientForTransferDELEG",
+        MIRProducesNegativeUpdate => "MIRProducesNegativeUpdate",
+        MIRNegativeTransfer { pot, attempted } => "MIRNegativeTransfer",
+    }
+
+    impl for shelley::PoolPredicateFailure as "shelley::PoolPredicateFailure" {
+        StakePoolNotRegisteredOnKey { pool_id } => "StakePoolNotRegisteredOnKeyPOOL",
+        StakePoolRetirementWrongEpoch {
+            retirement_too_early,
+            retirement_too_late,
+        } => "StakePoolRetirementWrongEpochPOOL",
+        StakePoolCostTooLow { cost_bound } => "StakePoolCostTooLowPOOL",
+        WrongNetwork { network_mismatch, pool_id } => "WrongNetworkPOOL",
+        PoolMetadataHashTooBig { pool_id, hash_size } => "PoolMedataHashTooBig",
+        VRFKeyHashAlreadyRegistered { pool_id, vrf } => "VRFKeyHashAlreadyRegistered",
+    }
+
+    impl for shelley::DelegsPredicateFailure as "shelley::DelegsPredicateFailure" {
+        DelegateeNotRegistered { pool_id } => "DelegateeNotRegisteredDELEG",
+        WithdrawalsNotInRewards { withdrawals } => "WithdrawalsNotInRewardsDELEGS",
+        DelplFailure(inner) => "DelplFailure",
+    }
+
+    impl for shelley::DelplPredicateFailure as "shelley::DelplPredicateFailure" {
+        PoolFailure(inner) => "PoolFailure",
+        DelegFailure(inner) => "DelegFailure",
+    }
+
+    impl<Utxo: ToJson + FromJson> for shelley::LedgerPredicateFailure<Utxo> as "shelley::LedgerPredicateFailure" {
+        UtxowFailure(inner) => "UtxowFailure",
+        DelegsFailure(inner) => "DelegsFailure",
+    }
+
+    impl<E: Era> for shelley::BbodyPredicateFailure<E> as "shelley::BbodyPredicateFailure" {
+        WrongBlockBodySizeBBODY { mismatch } => "WrongBlockBodySizeBBODY",
+        InvalidBodyHashBBODY { mismatch } => "InvalidBodyHashBBODY",
+        LedgersFailure(inner) => "LedgersFailure",
+    }
+
+    impl<E: Era> for allegra::UtxoPredicateFailure<E> as "allegra::UtxoPredicateFailure" {
+        BadInputsUTxO { invalid_inputs } => "BadInputsUTxO",
+        OutsideValidityIntervalUTxO { interval, current_slot } => "OutsideValidityIntervalUTxO",
+        MaxTxSizeUTxO { size_mismatch } => "MaxTxSizeUTxO",
+        InputSetEmptyUTxO => "InputSetEmptyUTxO",
+        FeeTooSmallUTxO { fee_mismatch } => "FeeTooSmallUTxO",
+        ValueNotConservedUTxO { balance_mismatch } => "ValueNotConservedUTxO",
+        OutputTooSmallUTxO { tiny_outputs } => "OutputTooSmallUTxO",
+        UpdateFailure(inner) => "UpdateFailure",
+        WrongNetwork { expected, offending } => "WrongNetwork",
+        WrongNetworkWithdrawal { expected, offending } => "WrongNetworkWithdrawal",
+        OutputBootAddrAttrsTooBig { oversized_bootstrap_outputs } => "OutputBootAddrAttrsTooBig",
+        TriesToForgeADA => "TriesToForgeADA",
+        OutputTooBigUTxO { oversized_outputs } => "OutputTooBigUTxO",
+    }
+
+
+    impl<E: PlutusEra> for alonzo::BbodyPredicateFailure<E> as "alonzo::BbodyPredicateFailure" {
+        ShelleyInAlonzo(inner) => "ShelleyInAlonzoBbodyPredFailure",
+        TooManyExUnits { bound } => "TooManyExUnits",
+    }
+
+    impl<E: PlutusEra> for alonzo::UtxoPredicateFailure<E> as "alonzo::UtxoPredicateFailure" {
+        BadInputsUTxO { invalid_inputs } => "BadInputsUTxO",
+        OutsideValidityIntervalUTxO { interval, current_slot } => "OutsideValidityIntervalUTxO",
+        MaxTxSizeUTxO { bound } => "MaxTxSizeUTxO",
+        InputSetEmptyUTxO => "InputSetEmptyUTxO",
+        FeeTooSmallUTxO { bound } => "FeeTooSmallUTxO",
+        ValueNotConservedUTxO { mismatch } => "ValueNotConservedUTxO",
+        OutputTooSmallUTxO { outputs } => "OutputTooSmallUTxO",
+        UtxosFailure(inner) => "UtxosFailure",
+        WrongNetwork { expected, offending } => "WrongNetwork",
+        WrongNetworkWithdrawal { expected, offending } => "WrongNetworkWithdrawal",
+        OutputBootAddrAttrsTooBig { outputs } => "OutputBootAddrAttrsTooBig",
+        OutputTooBigUTxO { oversized_outputs } => "OutputTooBigUTxO",
+        InsufficientCollateral { computed, required } => "InsufficientCollateral",
+        ScriptsNotPaidUTxO { offending_utxo } => "ScriptsNotPaidUTxO",
+        ExUnitsTooBigUTxO { bound } => "ExUnitsTooBigUTxO",
+        CollateralContainsNonADA { value } => "CollateralContainsNonADA",
+        WrongNetworkInTxBody { mismatch } => "WrongNetworkInTxBody",
+        OutsideForecast { slot } => "OutsideForecast",
+        TooManyCollateralInputs { bound } => "TooManyCollateralInputs",
+        NoCollateralInputs => "NoCollateralInputs",
+    }
+
+    impl for alonzo::FailureDescription as "alonzo::FailureDescription" {
+        PassedUnexpectedly => "PassedUnexpectedly",
+        FailedUnexpectedly(inner) => "FailedUnexpectedly",
+    }
+
+    impl<E: PlutusEra> for alonzo::UtxosPredicateFailure<E> as "alonzo::UtxosPredicateFailure" {
+        ValidationTagMismatch { is_valid, description } => "ValidationTagMismatch",
+        CollectErrors { errors } => "CollectErrors",
+        UpdateFailure(inner) => "UpdateFailure",
+    }
+
+    impl<E: PlutusEra> for alonzo::CollectError<E> as "alonzo::CollectError" {
+        NoRedeemer { purpose } => "NoRedeemer",
+        NoWitness { script_hash } => "NoWitness",
+        NoCostModel { language } => "NoCostModel",
+        BadTranslation(context) => "BadTranslation",
+    }
+
+    impl for alonzo::ContextError as "alonzo::ContextError" {
+        TranslationLogicMissingInput { input } => "TranslationLogicMissingInput",
+        TimeTranslationPastHorizon { message } => "TimeTranslationPastHorizon",
+    }
+
+    impl<E: PlutusEra> for alonzo::UtxowPredicateFailure<E> as "alonzo::UtxowPredicateFailure" {
+        ShelleyInAlonzo(inner) => "ShelleyInAlonzoUtxowPredFailure",
+        MissingRedeemers { missing } => "MissingRedeemers",
+        MissingRequiredDatums { missing_hashes, provided_hashes } => "MissingRequiredDatums",
+        NotAllowedSupplementalDatums { forbidden_hashes, permitted } => "NotAllowedSupplementalDatums",
+        PPViewHashesDontMatch { mismatch } => "PPViewHashesDontMatch",
+        UnspendableUTxONoDatumHash { inputs } => "UnspendableUTxONoDatumHash",
+        ExtraRedeemers { extra } => "ExtraRedeemers",
+        ScriptIntegrityHashMismatch { mismatch, provided } => "ScriptIntegrityHashMismatch",
+    }
+
+    impl<E: PlutusEra> for alonzo::LedgerPredicateFailure<E> as "alonzo::LedgerPredicateFailure" {
+        UtxowFailure(inner) => "UtxowFailure",
+        DelegsFailure(inner) => "DelegsFailure",
+    }
+
+    impl<E: PlutusEra> for babbage::UtxoPredicateFailure<E> as "babbage::UtxoPredicateFailure" {
+        AlonzoInBabbage(inner) => "AlonzoInBabbageUtxoPredFailure",
+        IncorrectTotalCollateralField { provided, declared } => "IncorrectTotalCollateralField",
+        OutputTooSmall { outputs } => "BabbageOutputTooSmallUTxO",
+        NonDisjointReferenceInputs { overlapping } => "BabbageNonDisjointRefInputs",
+    }
+
+    impl<E: PlutusEra> for babbage::UtxowPredicateFailure<E> as "babbage::UtxowPredicateFailure" {
+        AlonzoInBabbage(inner) => "AlonzoInBabbageUtxowPredFailure",
+        UtxoFailure(inner) => "UtxoFailure",
+        MalformedScriptWitnesses { witnesses } => "MalformedScriptWitnesses",
+        MalformedReferenceScripts { scripts } => "MalformedReferenceScripts",
+        ScriptIntegrityHashMismatch { mismatch, provided } => "ScriptIntegrityHashMismatch",
+    }
+
+    impl<E: PlutusEra> for babbage::ContextError<E> as "babbage::ContextError" {
+        ByronTxOutInContext { source } => "ByronTxOutInContext",
+        AlonzoContextError(inner) => "AlonzoContextError",
+        RedeemerPointerPointsToNothing { purpose } => "RedeemerPointerPointsToNothing",
+        InlineDatumsNotSupported { source } => "InlineDatumsNotSupported",
+        ReferenceScriptsNotSupported { source } => "ReferenceScriptsNotSupported",
+        ReferenceInputsNotSupported { inputs } => "ReferenceInputsNotSupported",
+    }
+
+    impl<E: PlutusEra> for babbage::LedgerPredicateFailure<E> as "babbage::LedgerPredicateFailure" {
+        UtxowFailure(inner) => "UtxowFailure",
+        DelegsFailure(inner) => "DelegsFailure",
+    }
+
+    impl<E: PlutusEra> for conway::UtxosPredicateFailure<E> as "conway::UtxosPredicateFailure" {
+        ValidationTagMismatch { tag, description } => "ValidationTagMismatch",
+        CollectErrors { errors } => "CollectErrors",
+    }
+
+    impl<E: PlutusEra> for conway::ContextError<E> as "conway::ContextError" {
+        BabbageContextError(inner) => "BabbageContextError",
+        CertificateNotSupported { certificate } => "CertificateNotSupported",
+        PlutusPurposeNotSupported { purpose } => "PlutusPurposeNotSupported",
+        CurrentTreasuryFieldNotSupported { treasury } => "CurrentTreasuryFieldNotSupported",
+        VotingProceduresFieldNotSupported { procedures } => "VotingProceduresFieldNotSupported",
+        ProposalProceduresFieldNotSupported { procedures } => "ProposalProceduresFieldNotSupported",
+        TreasuryDonationFieldNotSupported { donation } => "TreasuryDonationFieldNotSupported",
+        ReferenceInputsNotDisjointFromInputs { common } => "ReferenceInputsNotDisjointFromInputs",
+    }
+
+    impl<E: PlutusEra> for conway::UtxoPredicateFailure<E> as "conway::UtxoPredicateFailure" {
+        UtxosFailure(inner) => "UtxosFailure",
+        BadInputsUTxO { invalid_inputs } => "BadInputsUTxO",
+        OutsideValidityIntervalUTxO { validity_interval, current_slot } => "OutsideValidityIntervalUTxO",
+        MaxTxSizeUTxO { size_mismatch } => "MaxTxSizeUTxO",
+        InputSetEmptyUTxO => "InputSetEmptyUTxO",
+        FeeTooSmallUTxO { fee_mismatch } => "FeeTooSmallUTxO",
+        ValueNotConservedUTxO { balance_mismatch } => "ValueNotConservedUTxO",
+        WrongNetwork { expected, offending } => "WrongNetwork",
+        WrongNetworkWithdrawal { expected, offending } => "WrongNetworkWithdrawal",
+        OutputTooSmallUTxO { tiny_outputs } => "OutputTooSmallUTxO",
+        OutputBootAddrAttrsTooBig { oversized_bootstrap_outputs } => "OutputBootAddrAttrsTooBig",
+        OutputTooBigUTxO { outputs } => "OutputTooBigUTxO",
+        InsufficientCollateral { provided, required } => "InsufficientCollateral",
+        ScriptsNotPaidUTxO { unpaid } => "ScriptsNotPaidUTxO",
+        ExUnitsTooBigUTxO { limit_mismatch } => "ExUnitsTooBigUTxO",
+        CollateralContainsNonADA { offending_value } => "CollateralContainsNonADA",
+        WrongNetworkInTxBody { mismatch } => "WrongNetworkInTxBody",
+        OutsideForecast { slot } => "OutsideForecast",
+        TooManyCollateralInputs { bound } => "TooManyCollateralInputs",
+        NoCollateralInputs => "NoCollateralInputs",
+        IncorrectTotalCollateralField { provided, declared } => "IncorrectTotalCollateralField",
+        BabbageOutputTooSmallUTxO { outputs } => "BabbageOutputTooSmallUTxO",
+        BabbageNonDisjointRefInputs { overlapping } => "BabbageNonDisjointRefInputs",
+    }
+
+    impl<E: PlutusEra> for conway::UtxowPredicateFailure<E> as "conway::UtxowPredicateFailure" {
+        UtxoFailure(inner) => "UtxoFailure",
+        InvalidWitnessesUTXOW { witnesses } => "InvalidWitnessesUTXOW",
+        MissingVKeyWitnessesUTXOW { missing } => "MissingVKeyWitnessesUTXOW",
+        MissingScriptWitnessesUTXOW { missing } => "MissingScriptWitnessesUTXOW",
+        ScriptWitnessNotValidatingUTXOW { failing } => "ScriptWitnessNotValidatingUTXOW",
+        MissingTxBodyMetadataHash { expected } => "MissingTxBodyMetadataHash",
+        MissingTxMetadata { expected } => "MissingTxMetadata",
+        ConflictingMetadataHash { mismatch } => "ConflictingMetadataHash",
+        InvalidMetadata => "InvalidMetadata",
+        ExtraneousScriptWitnessesUTXOW { extraneous } => "ExtraneousScriptWitnessesUTXOW",
+        MissingRedeemers { missing } => "MissingRedeemers",
+        MissingRequiredDatums { missing_hashes, provided_hashes } => "MissingRequiredDatums",
+        NotAllowedSupplementalDatums { disallowed_hashes, allowed } => "NotAllowedSupplementalDatums",
+        PPViewHashesDontMatch { mismatch } => "PPViewHashesDontMatch",
+        UnspendableUTxONoDatumHash { inputs } => "UnspendableUTxONoDatumHash",
+        ExtraRedeemers { extra } => "ExtraRedeemers",
+        MalformedScriptWitnesses { scripts } => "MalformedScriptWitnesses",
+        MalformedReferenceScripts { scripts } => "MalformedReferenceScripts",
+        ScriptIntegrityHashMismatch { mismatch, provided } => "ScriptIntegrityHashMismatch",
+    }
+
+    impl for conway::DelegPredicateFailure as "conway::DelegPredicateFailure" {
+        IncorrectDepositDELEG { deposit } => "IncorrectDepositDELEG",
+        StakeKeyRegisteredDELEG { stake_credential } => "StakeKeyRegisteredDELEG",
+        StakeKeyNotRegisteredDELEG { stake_credential } => "StakeKeyNotRegisteredDELEG",
+        StakeKeyHasNonZeroRewardAccountBalanceDELEG { balance } => "StakeKeyHasNonZeroRewardAccountBalanceDELEG",
+        DelegateeDRepNotRegisteredDELEG { delegatee } => "DelegateeDRepNotRegisteredDELEG",
+        DelegateeStakePoolNotRegisteredDELEG { delegatee } => "DelegateeStakePoolNotRegisteredDELEG",
+        DepositIncorrectDELEG { mismatch } => "DepositIncorrectDELEG",
+        RefundIncorrectDELEG { mismatch } => "RefundIncorrectDELEG",
+    }
+
+    impl for conway::GovCertPredicateFailure as "conway::GovCertPredicateFailure" {
+        ConwayDRepAlreadyRegistered { credential } => "ConwayDRepAlreadyRegistered",
+        ConwayDRepNotRegistered { credential } => "ConwayDRepNotRegistered",
+        ConwayDRepIncorrectDeposit { mismatch } => "ConwayDRepIncorrectDeposit",
+        ConwayCommitteeHasPreviouslyResigned { cold_credential } => "ConwayCommitteeHasPreviouslyResigned",
+        ConwayDRepIncorrectRefund { mismatch } => "ConwayDRepIncorrectRefund",
+        ConwayCommitteeIsUnknown { cold_credential } => "ConwayCommitteeIsUnknown",
+    }
+
+    impl for conway::CertPredicateFailure as "conway::CertPredicateFailure" {
+        DelegFailure(inner) => "DelegFailure",
+        PoolFailure(inner) => "PoolFailure",
+        GovCertFailure(inner) => "GovCertFailure",
+    }
+
+    impl for conway::CertsPredicateFailure as "conway::CertsPredicateFailure" {
+        WithdrawalsNotInRewardsCERTS { withdrawals } => "WithdrawalsNotInRewardsCERTS",
+        CertFailure(inner) => "CertFailure",
+    }
+
+    impl for conway::GovPredicateFailure as "conway::GovPredicateFailure" {
+        GovActionsDoNotExist { missing } => "GovActionsDoNotExist",
+        MalformedProposal { proposal } => "MalformedProposal",
+        ProposalProcedureNetworkIdMismatch { reward_account, expected_network } => "ProposalProcedureNetworkIdMismatch",
+        TreasuryWithdrawalsNetworkIdMismatch { offending_accounts, expected_network } => "TreasuryWithdrawalsNetworkIdMismatch",
+        ProposalDepositIncorrect { mismatch } => "ProposalDepositIncorrect",
+        DisallowedVoters { voters } => "DisallowedVoters",
+        ConflictingCommitteeUpdate { members } => "ConflictingCommitteeUpdate",
+        ExpirationEpochTooSmall { expired } => "ExpirationEpochTooSmall",
+        InvalidPrevGovActionId { proposal } => "InvalidPrevGovActionId",
+        VotingOnExpiredGovAction { votes } => "VotingOnExpiredGovAction",
+        ProposalCantFollow { previous @ hard_fork_action, version_mismatch } => "ProposalCantFollow",
+        InvalidPolicyHash { provided, expected } => "InvalidPolicyHash",
+        DisallowedProposalDuringBootstrap { proposal } => "DisallowedProposalDuringBootstrap",
+        DisallowedVotesDuringBootstrap { votes } => "DisallowedVotesDuringBootstrap",
+        VotersDoNotExist { voters } => "VotersDoNotExist",
+        ZeroTreasuryWithdrawals { action } => "ZeroTreasuryWithdrawals",
+        ProposalReturnAccountDoesNotExist { reward_account } => "ProposalReturnAccountDoesNotExist",
+        TreasuryWithdrawalReturnAccountsDoNotExist { reward_accounts } => "TreasuryWithdrawalReturnAccountsDoNotExist",
+        UnelectedCommitteeVoters { voters } => "UnelectedCommitteeVoters",
+    }
+
+    impl<E: PlutusEra> for conway::LedgerPredicateFailure<E> as "conway::LedgerPredicateFailure" {
+        ConwayUtxowFailure(inner) => "ConwayUtxowFailure",
+        ConwayCertsFailure(inner) => "ConwayCertsFailure",
+        ConwayGovFailure(inner) => "ConwayGovFailure",
+        ConwayWdrlNotDelegatedToDRep { withdrawals } => "ConwayWdrlNotDelegatedToDRep",
+        ConwayTreasuryValueMismatch { mismatch } => "ConwayTreasuryValueMismatch",
+        ConwayTxRefScriptsSizeTooBig { size_mismatch } => "ConwayTxRefScriptsSizeTooBig",
+        ConwayMempoolFailure { reason } => "ConwayMempoolFailure",
+        ConwayWithdrawalsMissingAccounts { withdrawals } => "ConwayWithdrawalsMissingAccounts",
+        ConwayIncompleteWithdrawals { withdrawals } => "ConwayIncompleteWithdrawals",
+    }
+
+    impl<E: PlutusEra> for conway::BbodyPredicateFailure<E> as "conway::BbodyPredicateFailure" {
+        WrongBlockBodySizeBBODY { mismatch } => "WrongBlockBodySizeBBODY",
+        InvalidBodyHashBBODY { mismatch } => "InvalidBodyHashBBODY",
+        LedgersFailure(inner) => "LedgersFailure",
+        TooManyExUnits { bound } => "TooManyExUnits",
+        BodyRefScriptsSizeTooBig { size_mismatch } => "BodyRefScriptsSizeTooBig",
+    }
+}
+
+impl<Ledger: ToJson> ToJson for shelley::LedgersPredicateFailure<Ledger> {
+    fn to_json(&self) -> Value {
+        match self {
+            shelley::LedgersPredicateFailure::LedgersFailure(failure) => {
+                Tagged::new("LedgerFailure").field(failure).build()
+            }
+        }
+    }
+}
+
+impl<Ledger: FromJson> FromJson for shelley::LedgersPredicateFailure<Ledger> {
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let contents = Contents::of(value, "shelley::LedgersPredicateFailure")?;
+        Ok(match contents.tag {
+            "LedgerFailure" => shelley::LedgersPredicateFailure::LedgersFailure(contents.field()?),
+            _ => return contents.unknown(),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// serde
+// ---------------------------------------------------------------------------
+
+/// `Serialize`/`Deserialize` through the [`ToJson`]/[`FromJson`] encoding,
+/// so the failures can be embedded in any serde document.
+macro_rules! serde_via_json {
+    ($(impl$(<$param:ident: $bound:ident $(+ $bounds:ident)*>)? for $ty:ty;)*) => {
+        $(
+            impl$(<$param: $bound $(+ $bounds)*>)? Serialize for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.to_json().serialize(serializer)
+                }
+            }
+
+            impl<'de, $($param: $bound $(+ $bounds)*)?> Deserialize<'de> for $ty {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let value = Value::deserialize(deserializer)?;
+                    from_json(&value).map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+serde_via_json! {
+    impl for Coin;
+    impl for DeltaCoin;
+    impl for SlotNo;
+    impl for EpochNo;
+    impl for TxIx;
+    impl for EpochInterval;
+    impl for GovActionIx;
+    impl for Address;
+    impl for Hash28;
+    impl for Hash32;
+    impl for VerificationKey;
+    impl for ScriptHash;
+    impl for DataHash;
+    impl for ScriptIntegrityHash;
+    impl for TxAuxDataHash;
+    impl for TxId;
+    impl for AddrKeyHash;
+    impl for KeyHash;
+    impl for PoolKeyHash;
+    impl for VRFKeyHash;
+    impl for AssetName;
+    impl for Withdrawals;
+    impl for MultiAsset;
+    impl for UTxOStruct;
+    impl for VotingProceduresStruct;
+    impl for NetworkId;
+    impl for TxIn;
+    impl for ProtVer;
+    impl for ExUnits;
+    impl for ValidityInterval;
+    impl for MIRPot;
+    impl for UnitInterval;
+    impl for NonNegativeInterval;
+    impl for Credential;
+    impl for DRep;
+    impl for RewardAccount;
+    impl for Anchor;
+    impl for PoolMetadata;
+    impl for Relay;
+    impl for PoolParams;
+    impl for PoolCert;
+    impl for ShelleyTxCert;
+    impl for MIRCert;
+    impl for MIRTarget;
+    impl for dijkstra::TxCert;
+    impl for dijkstra::Script;
+    impl for AlonzoPlutusPurpose;
+    impl for AlonzoPlutusPurposeItem;
+    impl for dijkstra::PlutusPurpose;
+    impl for dijkstra::PlutusPurposeItem;
+    impl for ValueStruct;
+    impl for PlutusData;
+    impl for Script;
+    impl<T: ToJson + FromJson> for ScriptRef<T>;
+    impl<E: Era> for TxOutStruct<E>;
+    impl for VoterEnum;
+    impl for GovActionId;
+    impl for VotingProcedure;
+    impl for Constitution;
+    impl for CostModels;
+    impl for ExUnitPrices;
+    impl for PoolVotingThresholds;
+    impl for DRepVotingThresholds;
+    impl for ProtocolParamUpdate;
+    impl for GovActionStruct;
+    impl for ProposalProcedureStruct;
+    impl for PlutusPurposeStruct;
+    impl for PlutusPurposeItemStruct;
+    impl for TxOutSource;
+    impl for Language;
+
+    impl for shelley::VotingPeriod;
+    impl for shelley::PpupPredicateFailure;
+    impl<E: Era> for shelley::UtxoPredicateFailure<E>;
+    impl<Utxo: ToJson + FromJson> for shelley::UtxowPredicateFailure<Utxo>;
+    impl for shelley::DelegPredicateFailure;
+    impl for shelley::PoolPredicateFailure;
+    impl for shelley::DelegsPredicateFailure;
+    impl for shelley::DelplPredicateFailure;
+    impl<Utxo: ToJson + FromJson> for shelley::LedgerPredicateFailure<Utxo>;
+    impl<E: Era> for shelley::BbodyPredicateFailure<E>;
+    impl<E: Era> for allegra::UtxoPredicateFailure<E>;
+    impl<E: PlutusEra> for alonzo::BbodyPredicateFailure<E>;
+    impl<E: PlutusEra> for alonzo::UtxoPredicateFailure<E>;
+    impl for alonzo::FailureDescription;
+    impl<E: PlutusEra> for alonzo::UtxosPredicateFailure<E>;
+    impl<E: PlutusEra> for alonzo::CollectError<E>;
+    impl for alonzo::ContextError;
+    impl<E: PlutusEra> for alonzo::UtxowPredicateFailure<E>;
+    impl<E: PlutusEra> for alonzo::LedgerPredicateFailure<E>;
+    impl<E: PlutusEra> for babbage::UtxoPredicateFailure<E>;
+    impl<E: PlutusEra> for babbage::UtxowPredicateFailure<E>;
+    impl<E: PlutusEra> for babbage::ContextError<E>;
+    impl<E: PlutusEra> for babbage::LedgerPredicateFailure<E>;
+    impl<E: PlutusEra> for conway::UtxosPredicateFailure<E>;
+    impl<E: PlutusEra> for conway::ContextError<E>;
+    impl<E: PlutusEra> for conway::UtxoPredicateFailure<E>;
+    impl<E: PlutusEra> for conway::UtxowPredicateFailure<E>;
+    impl for conway::DelegPredicateFailure;
+    impl for conway::GovCertPredicateFailure;
+    impl for conway::CertPredicateFailure;
+    impl for conway::CertsPredicateFailure;
+    impl for conway::GovPredicateFailure;
+    impl<E: PlutusEra> for conway::LedgerPredicateFailure<E>;
+    impl<E: PlutusEra> for conway::BbodyPredicateFailure<E>;
+}
+
+impl<Ledger: ToJson> Serialize for shelley::LedgersPredicateFailure<Ledger> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+impl<'de, Ledger: FromJson> Deserialize<'de> for shelley::LedgersPredicateFailure<Ledger> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        from_json(&value).map_err(de::Error::custom)
+    }
+}
+
+impl<R, T: ToJson> Serialize for Mismatch<R, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+impl<'de, R: Relation, T: FromJson> Deserialize<'de> for Mismatch<R, T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        from_json(&value).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_shortfall_matches_node_json() {
+        let failure: conway::LedgerPredicateFailure<Conway> =
+            conway::LedgerPredicateFailure::ConwayUtxowFailure(
+                conway::UtxowPredicateFailure::UtxoFailure(
+                    conway::UtxoPredicateFailure::FeeTooSmallUTxO {
+                        fee_mismatch: Mismatch {
+                            relation: RelGTEQ,
+                            supplied: Coin(170_000),
+                            expected: Coin(172_345),
+                        },
+                    },
+                ),
+            );
+
+        let text = serde_json::to_string(&failure).unwrap();
+        assert_eq!(
+            text,
+            r#"{"tag":"ConwayUtxowFailure","contents":{"tag":"UtxoFailure","contents":{"tag":"FeeTooSmallUTxO","contents":{"supplied":170000,"expected":172345}}}}"#
+        );
+        let decoded: conway::LedgerPredicateFailure<Conway> = serde_json::from_str(&text).unwrap();
+        assert_eq!(decoded, failure);
+    }
+
+    #[test]
+    fn credentials_and_multi_field_constructors() {
+        let credential = Credential::ScriptHash(ScriptHash(Hash28([0xab; 28])));
+        let failure = shelley::PoolPredicateFailure::StakePoolRetirementWrongEpoch {
+            retirement_too_early: Mismatch {
+                relation: RelGT,
+                supplied: EpochNo(10),
+                expected: EpochNo(12),
+            },
+            retirement_too_late: Mismatch {
+                relation: RelLTEQ,
+                supplied: EpochNo(10),
+                expected: EpochNo(30),
+            },
+        };
+
+        assert_eq!(
+            credential.to_json(),
+            json!({ "scriptHash": "ab".repeat(28) })
+        );
+        assert_eq!(
+            failure.to_json(),
+            json!({
+                "tag": "StakePoolRetirementWrongEpochPOOL",
+                "contents": [
+                    { "supplied": 10, "expected": 12 },
+                    { "supplied": 10, "expected": 30 },
+                ],
+            })
+        );
+        assert_eq!(
+            shelley::PoolPredicateFailure::from_json(&failure.to_json()).unwrap(),
+            failure
+        );
+        assert!(matches!(
+            decode_failure_json::<shelley::PoolPredicateFailure>(r#"{"tag":"NoSuchPOOL"}"#),
+            Err(JsonError::UnknownConstructor { .. })
+        ));
+    }
+    /// Decode the node's JSON and check that it is printed back unchanged.
+    fn round_trips<T: FromJson + ToJson>(node: Value) -> T {
+        let decoded = T::from_json(&node).unwrap();
+        assert_eq!(decoded.to_json(), node);
+        decoded
+    }
+
+    #[test]
+    fn era_failures_round_trip_node_json() {
+        let input = format!("{}#1", "cd".repeat(32));
+
+        round_trips::<shelley::UtxoPredicateFailure<Shelley>>(json!({
+            "tag": "ExpiredUTxO",
+            "contents": { "supplied": 1000, "expected": 1200 },
+        }));
+        round_trips::<allegra::UtxoPredicateFailure<Allegra>>(json!({
+            "tag": "OutsideValidityIntervalUTxO",
+            "contents": [{ "invalidBefore": 10, "invalidHereafter": 20 }, 25],
+        }));
+        round_trips::<mary::UtxoPredicateFailure<Mary>>(json!({
+            "tag": "ValueNotConservedUTxO",
+            "contents": {
+                "supplied": {
+                    "lovelace": 2000000,
+                    "policies": { "11".repeat(28): { "746f6b": 5 } },
+                },
+                "expected": 2000000,
+            },
+        }));
+        round_trips::<babbage::UtxoPredicateFailure<Babbage>>(json!({
+            "tag": "BabbageNonDisjointRefInputs",
+            "contents": [input],
+        }));
+        round_trips::<conway::LedgerPredicateFailure<Conway>>(json!({
+            "tag": "ConwayTreasuryValueMismatch",
+            "contents": { "supplied": 500, "expected": 700 },
+        }));
+    }
+
+    #[test]
+    fn nested_utxos_failures_round_trip_node_json() {
+        let failure = round_trips::<alonzo::UtxowPredicateFailure<Alonzo>>(json!({
+            "tag": "ShelleyInAlonzoUtxowPredFailure",
+            "contents": {
+                "tag": "UtxoFailure",
+                "contents": {
+                    "tag": "UtxosFailure",
+                    "contents": {
+                        "tag": "ValidationTagMismatch",
+                        "contents": [true, { "tag": "PassedUnexpectedly" }],
+                    },
+                },
+            },
+        }));
+        assert!(matches!(
+            failure,
+            alonzo::UtxowPredicateFailure::ShelleyInAlonzo(
+                shelley::UtxowPredicateFailure::UtxoFailure(
+                    alonzo::UtxoPredicateFailure::UtxosFailure(
+                        alonzo::UtxosPredicateFailure::ValidationTagMismatch { is_valid: true, .. }
+                    )
+                )
+            )
+        ));
+
+        round_trips::<conway::LedgerPredicateFailure<Conway>>(json!({
+            "tag": "ConwayUtxowFailure",
+            "contents": {
+                "tag": "UtxoFailure",
+                "contents": {
+                    "tag": "UtxosFailure",
+                    "contents": {
+                        "tag": "CollectErrors",
+                        "contents": [{ "tag": "NoCostModel", "contents": "PlutusV3" }],
+                    },
+                },
+            },
+        }));
+    }
+
+    #[test]
+    fn mismatch_shapes() {
+        let short = r#"{"supplied":170000,"expected":172345}"#;
+        let mismatch: Mismatch<RelGTEQ, Coin> = serde_json::from_str(short).unwrap();
+        assert_eq!(mismatch.supplied, Coin(170_000));
+        assert_eq!(mismatch.expected, Coin(172_345));
+        assert_eq!(serde_json::to_string(&mismatch).unwrap(), short);
+
+        // The generically derived instance names the fields in full.
+        let long: Mismatch<RelGTEQ, Coin> =
+            serde_json::from_str(r#"{"mismatchSupplied":170000,"mismatchExpected":172345}"#)
+                .unwrap();
+        assert_eq!(long, mismatch);
+        assert!(serde_json::from_str::<Mismatch<RelGTEQ, Coin>>(r#"{"supplied":1}"#).is_err());
+
+        // Next to other fields, a mismatch is one element of the contents.
+        round_trips::<babbage::UtxowPredicateFailure<Babbage>>(json!({
+            "tag": "ScriptIntegrityHashMismatch",
+            "contents": [{ "supplied": null, "expected": "ab".repeat(32) }, null],
+        }));
+    }
+}
@@ -0,0 +1,482 @@
+//! `ledger-failure-decode`: print why a node rejected a transaction.
+//!
+//! Reads the CBOR of a rejection, either the hard-fork combinator envelope
+//! returned over local transaction submission or a bare LEDGER predicate
+//! failure, and prints it as the typed failure tree, as the node's JSON or
+//! as a one-line diagnostic per failure.
+//!
+//! ```text
+//! ledger-failure-decode [--era ERA] [--input FORMAT] [--output FORMAT] [FILE]
+//! ```
+//!
+//! Without `FILE`, or with `-`, the input is read from stdin.  The input may
+//! be hex, base64 or raw CBOR; by default the encoding is detected.  Without
+//! `--era`, the envelope is tried first and then the eras from newest to
+//! oldest, Allegra ahead of Mary.
+
+#![allow(dead_code)]
+
+mod address;
+mod apply_tx_error;
+mod bech32;
+mod diagnostic;
+mod era;
+mod error_parser;
+mod failure_decoder;
+mod failure_encoder;
+mod failure_json;
+mod rust_rule_errors;
+mod value;
+
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::{env, fmt, fs};
+
+use serde_json::{json, Value};
+
+use crate::apply_tx_error::*;
+use crate::error_parser::{decode_spanned_term, ParseError, SpanTree, Term};
+use crate::failure_json::ToJson;
+
+const USAGE: &str = "\
+usage: ledger-failure-decode [OPTIONS] [FILE]
+
+Decode the CBOR of a rejected transaction (the hard-fork envelope or a bare
+LEDGER predicate failure) read from FILE, or from stdin if FILE is absent or -.
+
+options:
+  -e, --era ERA         shelley, allegra, mary, alonzo, babbage, conway,
+                        dijkstra or auto (default: auto)
+  -i, --input FORMAT    hex, base64, raw or auto (default: auto)
+  -o, --output FORMAT   diagnostic, json or tree (default: diagnostic)
+  -h, --help            print this message
+";
+
+/// Eras tried for a bare failure when none is given, newest first.  Dijkstra
+/// failures also decode as Conway ones, so it is only used when named.
+/// Allegra goes before Mary: every Allegra failure also decodes as a Mary one,
+/// so Mary is left for the failures only it can raise, those holding
+/// multi-asset values or `TriesToForgeADA`.
+const AUTO_ERAS: [u64; 6] = [
+    CONWAY_ERA_INDEX,
+    BABBAGE_ERA_INDEX,
+    ALONZO_ERA_INDEX,
+    ALLEGRA_ERA_INDEX,
+    MARY_ERA_INDEX,
+    SHELLEY_ERA_INDEX,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputFormat {
+    Auto,
+    Hex,
+    Base64,
+    Raw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Diagnostic,
+    Json,
+    Tree,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    era: Option<u64>,
+    input: InputFormat,
+    output: OutputFormat,
+    path: Option<String>,
+}
+
+/// Error reported by the tool, with the exit code it maps to.
+#[derive(Debug)]
+enum CliError {
+    /// Invalid command line; exit code 2.
+    Usage(String),
+    /// The input could not be read; exit code 1.
+    Io(io::Error),
+    /// The input is not hex, base64 or CBOR of the expected shape; exit
+    /// code 1.
+    Input(&'static str),
+    /// The CBOR is not a rejection of the requested era; exit code 1.
+    Decode(ParseError),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{msg}\n\n{USAGE}"),
+            CliError::Io(err) => write!(f, "cannot read input: {err}"),
+            CliError::Input(msg) => write!(f, "invalid input: {msg}"),
+            CliError::Decode(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::Io(err)
+    }
+}
+
+impl From<ParseError> for CliError {
+    fn from(err: ParseError) -> Self {
+        CliError::Decode(err)
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        print!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match run(&args) {
+        Ok(output) => {
+            let mut stdout = io::stdout().lock();
+            // A closed pipe (`| head`) is not worth reporting.
+            let _ = stdout.write_all(output.as_bytes());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("ledger-failure-decode: {err}");
+            match err {
+                CliError::Usage(_) => ExitCode::from(2),
+                _ => ExitCode::FAILURE,
+            }
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<String, CliError> {
+    let options = parse_args(args)?;
+    let input = match options.path.as_deref() {
+        None | Some("-") => {
+            let mut input = Vec::new();
+            io::stdin().lock().read_to_end(&mut input)?;
+            input
+        }
+        Some(path) => fs::read(path)?,
+    };
+    let bytes = decode_input(&input, options.input)?;
+    let rejection = decode_rejection(&bytes, options.era)?;
+    Ok(render(&rejection, options.output))
+}
+
+fn parse_args(args: &[String]) -> Result<Options, CliError> {
+    let mut options = Options {
+        era: None,
+        input: InputFormat::Auto,
+        output: OutputFormat::Diagnostic,
+        path: None,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .map(String::as_str)
+                .ok_or_else(|| CliError::Usage(format!("{name} needs a value")))
+        };
+        match arg.as_str() {
+            "-e" | "--era" => options.era = parse_era(value(arg)?)?,
+            "-i" | "--input" => {
+                options.input = match value(arg)? {
+                    "auto" => InputFormat::Auto,
+                    "hex" => InputFormat::Hex,
+                    "base64" => InputFormat::Base64,
+                    "raw" => InputFormat::Raw,
+                    other => {
+                        return Err(CliError::Usage(format!("unknown input format {other:?}")))
+                    }
+                }
+            }
+            "-o" | "--output" => {
+                options.output = match value(arg)? {
+                    "diagnostic" => OutputFormat::Diagnostic,
+                    "json" => OutputFormat::Json,
+                    "tree" => OutputFormat::Tree,
+                    other => {
+                        return Err(CliError::Usage(format!("unknown output format {other:?}")))
+                    }
+                }
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                return Err(CliError::Usage(format!("unknown option {flag:?}")))
+            }
+            path if options.path.is_none() => options.path = Some(path.to_owned()),
+            _ => return Err(CliError::Usage("more than one input file".into())),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_era(name: &str) -> Result<Option<u64>, CliError> {
+    Ok(Some(match name.to_ascii_lowercase().as_str() {
+        "auto" => return Ok(None),
+        "shelley" => SHELLEY_ERA_INDEX,
+        "allegra" => ALLEGRA_ERA_INDEX,
+        "mary" => MARY_ERA_INDEX,
+        "alonzo" => ALONZO_ERA_INDEX,
+        "babbage" => BABBAGE_ERA_INDEX,
+        "conway" => CONWAY_ERA_INDEX,
+        "dijkstra" => DIJKSTRA_ERA_INDEX,
+        _ => return Err(CliError::Usage(format!("unknown era {name:?}"))),
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// Input
+// ---------------------------------------------------------------------------
+
+/// Turn the input into CBOR bytes.  Detection prefers hex, then base64, and
+/// treats anything else as raw CBOR; text encodings may contain whitespace.
+fn decode_input(input: &[u8], format: InputFormat) -> Result<Vec<u8>, CliError> {
+    let text: Vec<u8> = input
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    match format {
+        InputFormat::Raw => Ok(input.to_vec()),
+        InputFormat::Hex => unhex(&text),
+        InputFormat::Base64 => unbase64(&text),
+        InputFormat::Auto => {
+            if text.is_empty() {
+                Err(CliError::Input("no input"))
+            } else if text.iter().all(u8::is_ascii_hexdigit) {
+                unhex(&text)
+            } else if text
+                .iter()
+                .all(|&byte| base64_digit(byte).is_some() || byte == b'=')
+            {
+                unbase64(&text)
+            } else {
+                Ok(input.to_vec())
+            }
+        }
+    }
+}
+
+fn unhex(text: &[u8]) -> Result<Vec<u8>, CliError> {
+    if !text.len().is_multiple_of(2) {
+        return Err(CliError::Input("odd number of hex digits"));
+    }
+    text.chunks(2)
+        .map(|pair| {
+            let digits = std::str::from_utf8(pair).map_err(|_| CliError::Input("not hex"))?;
+            u8::from_str_radix(digits, 16).map_err(|_| CliError::Input("not hex"))
+        })
+        .collect()
+}
+
+/// Value of a base64 digit, accepting both the standard and the URL-safe
+/// alphabet.
+fn base64_digit(byte: u8) -> Option<u32> {
+    match byte {
+        b'A'..=b'Z' => Some(u32::from(byte - b'A')),
+        b'a'..=b'z' => Some(u32::from(byte - b'a') + 26),
+        b'0'..=b'9' => Some(u32::from(byte - b'0') + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+fn unbase64(text: &[u8]) -> Result<Vec<u8>, CliError> {
+    let digits = text
+        .strip_suffix(b"==")
+        .or_else(|| text.strip_suffix(b"="))
+        .unwrap_or(text);
+    if digits.len() % 4 == 1 {
+        return Err(CliError::Input("truncated base64"));
+    }
+    let mut bytes = Vec::with_capacity(digits.len() / 4 * 3);
+    for chunk in digits.chunks(4) {
+        let mut group = 0u32;
+        for &digit in chunk {
+            group = (group << 6) | base64_digit(digit).ok_or(CliError::Input("not base64"))?;
+        }
+        group <<= 6 * (4 - chunk.len() as u32);
+        bytes.extend_from_slice(&group.to_be_bytes()[1..chunk.len()]);
+    }
+    Ok(bytes)
+}
+
+// ---------------------------------------------------------------------------
+// Decoding
+// ---------------------------------------------------------------------------
+
+/// Decode a rejection, accepting the hard-fork envelope, an era's list of
+/// LEDGER failures or a single LEDGER failure.
+fn decode_rejection(bytes: &[u8], era: Option<u64>) -> Result<HardForkApplyTxErr, CliError> {
+    let (term, spans) = decode_spanned_term(bytes)?;
+    let envelope = || HardForkApplyTxErr::from_spanned_term(&term, Some(&spans));
+    let rejection = match era {
+        Some(era) => match decode_in_era(&term, &spans, era) {
+            Ok(failures) => failures,
+            Err(err) => match envelope() {
+                Ok(HardForkApplyTxErr::FromEra(failures)) if failures.era_index() != era => {
+                    return Err(CliError::Input("the envelope names a different era"))
+                }
+                Ok(rejection) => return Ok(rejection),
+                Err(_) => return Err(err.into()),
+            },
+        },
+        None => match envelope() {
+            Ok(rejection) => return Ok(rejection),
+            Err(err) => AUTO_ERAS
+                .iter()
+                .find_map(|&era| decode_in_era(&term, &spans, era).ok())
+                .ok_or(err)?,
+        },
+    };
+    Ok(HardForkApplyTxErr::FromEra(rejection))
+}
+
+/// Decode bare LEDGER failures of one era by wrapping them, and their spans,
+/// in the `[era_index, [+ ledger_failure]]` layout of the envelope.
+fn decode_in_era(term: &Term, spans: &SpanTree, era: u64) -> Result<EraApplyTxError, ParseError> {
+    let (failures, failures_spans) = match term {
+        Term::Array(items) if matches!(items.first(), Some(Term::Unsigned(_))) => {
+            let list_spans = SpanTree {
+                span: spans.span,
+                children: vec![spans.clone()],
+            };
+            (Term::Array(vec![term.clone()]), list_spans)
+        }
+        failures => (failures.clone(), spans.clone()),
+    };
+    let envelope_spans = SpanTree {
+        span: spans.span,
+        children: vec![SpanTree::default(), failures_spans],
+    };
+    EraApplyTxError::from_spanned_term(
+        &Term::Array(vec![Term::Unsigned(era), failures]),
+        Some(&envelope_spans),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+fn render(rejection: &HardForkApplyTxErr, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Tree => format!("{rejection:#?}\n"),
+        OutputFormat::Json => format!("{:#}\n", rejection_json(rejection)),
+        OutputFormat::Diagnostic => match rejection {
+            HardForkApplyTxErr::FromEra(failures) => {
+                let mut text =
+                    format!("{} ledger rejected the transaction:\n", failures.era_name());
+                for line in era_failures(failures, |failure| failure.to_string()) {
+                    text.push_str(&format!("  - {line}\n"));
+                }
+                text
+            }
+            HardForkApplyTxErr::WrongEra {
+                transaction_era,
+                ledger_era,
+            } => format!(
+                "transaction is for the {} era but the ledger is in the {} era\n",
+                transaction_era.name, ledger_era.name
+            ),
+        },
+    }
+}
+
+fn rejection_json(rejection: &HardForkApplyTxErr) -> Value {
+    match rejection {
+        HardForkApplyTxErr::FromEra(failures) => json!({
+            "era": failures.era_name(),
+            "failures": era_failures(failures, |failure| failure.to_json()),
+        }),
+        HardForkApplyTxErr::WrongEra {
+            transaction_era,
+            ledger_era,
+        } => json!({
+            "transactionEra": { "index": transaction_era.index, "name": transaction_era.name },
+            "ledgerEra": { "index": ledger_era.index, "name": ledger_era.name },
+        }),
+    }
+}
+
+/// Map each failure of an era, whatever its LEDGER failure type.
+fn era_failures<T>(failures: &EraApplyTxError, render: impl Fn(&dyn EraFailure) -> T) -> Vec<T> {
+    fn each<F: EraFailure, T>(failures: &[F], render: impl Fn(&dyn EraFailure) -> T) -> Vec<T> {
+        failures.iter().map(|failure| render(failure)).collect()
+    }
+    match failures {
+        EraApplyTxError::Shelley(list) => each(list.as_slice(), render),
+        EraApplyTxError::Allegra(list) => each(list.as_slice(), render),
+        EraApplyTxError::Mary(list) => each(list.as_slice(), render),
+        EraApplyTxError::Alonzo(list) => each(list.as_slice(), render),
+        EraApplyTxError::Babbage(list) => each(list.as_slice(), render),
+        EraApplyTxError::Conway(list) => each(list.as_slice(), render),
+        EraApplyTxError::Dijkstra(list) => each(list.as_slice(), render),
+    }
+}
+
+/// What the output formats need from an era's LEDGER failure.
+trait EraFailure: fmt::Display {
+    fn to_json(&self) -> Value;
+}
+
+impl<T: fmt::Display + ToJson> EraFailure for T {
+    fn to_json(&self) -> Value {
+        ToJson::to_json(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_input_encodings() {
+        let cbor = [0x82, 0x06, 0x81, 0xff];
+        assert_eq!(
+            decode_input(b"820681ff\n", InputFormat::Auto).unwrap(),
+            cbor
+        );
+        assert_eq!(decode_input(b"ggaB/w==", InputFormat::Auto).unwrap(), cbor);
+        assert_eq!(decode_input(&cbor, InputFormat::Auto).unwrap(), cbor);
+    }
+
+    #[test]
+    fn detects_allegra_and_mary_failures() {
+        let era = |bytes: &[u8]| match decode_rejection(bytes, None).unwrap() {
+            HardForkApplyTxErr::FromEra(failures) => failures.era_index(),
+            other => panic!("unexpected rejection {other:?}"),
+        };
+        // UtxowFailure (UtxoFailure (OutsideValidityIntervalUTxO
+        // (ValidityInterval SNothing (SJust 100)) 250)).
+        let outside = [
+            0x82, 0x00, 0x82, 0x04, 0x83, 0x01, 0x82, 0x80, 0x81, 0x18, 0x64, 0x18, 0xfa,
+        ];
+        assert_eq!(era(&outside), ALLEGRA_ERA_INDEX);
+        // UtxowFailure (UtxoFailure TriesToForgeADA).
+        assert_eq!(era(&[0x82, 0x00, 0x82, 0x04, 0x81, 0x0b]), MARY_ERA_INDEX);
+    }
+
+    #[test]
+    fn parses_options() {
+        let args: Vec<String> = ["--era", "Conway", "-o", "json", "tx.cbor"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            parse_args(&args).unwrap(),
+            Options {
+                era: Some(CONWAY_ERA_INDEX),
+                input: InputFormat::Auto,
+                output: OutputFormat::Json,
+                path: Some("tx.cbor".into()),
+            }
+        );
+        assert!(matches!(
+            parse_args(&["--era".to_string(), "byron".to_string()]),
+            Err(CliError::Usage(_))
+        ));
+    }
+}
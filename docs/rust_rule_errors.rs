@@ -8,6 +8,9 @@
 //! wiring these definitions into real code.
 
 use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::era::{Conway, Era, PlutusEra};
 
 // ---------------------------------------------------------------------------
 // Helper stand-ins
@@ -91,23 +94,196 @@ pub enum MIRPot {
     Treasury,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-pub struct UnitInterval {
-    pub numerator: u64,
-    pub denominator: u64,
+/// `MIRTarget`: rewards moved to stake credentials, or lovelace moved to the
+/// other pot.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MIRTarget {
+    StakeAddressesMIR(BTreeMap<StakeCredential, DeltaCoin>),
+    SendToOppositePotMIR(Coin),
+}
+
+/// `MIRCert`, encoded as `[pot, target]`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MIRCert {
+    pub pot: MIRPot,
+    pub target: MIRTarget,
+}
+
+/// A non-negative rational in lowest terms, the `rational` of the CDDL specs
+/// (`#6.30([uint, uint])`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: u64,
+    denominator: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntervalError {
+    ZeroDenominator,
+    AboveOne,
+}
+
+impl fmt::Display for IntervalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntervalError::ZeroDenominator => f.write_str("zero denominator"),
+            IntervalError::AboveOne => f.write_str("unit interval above one"),
+        }
+    }
+}
+
+impl std::error::Error for IntervalError {}
+
+impl Rational {
+    pub const ZERO: Rational = Rational {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    /// Reduces the fraction, as Haskell's `%` does.
+    pub fn new(numerator: u64, denominator: u64) -> Result<Self, IntervalError> {
+        if denominator == 0 {
+            return Err(IntervalError::ZeroDenominator);
+        }
+        let (mut a, mut b) = (numerator, denominator);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        Ok(Rational {
+            numerator: numerator / a,
+            denominator: denominator / a,
+        })
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.denominator
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Rational::ZERO
+    }
+}
+
+/// Compares the values, not the fractions: 1/2 < 2/3.
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let left = u128::from(self.numerator) * u128::from(other.denominator);
+        let right = u128::from(other.numerator) * u128::from(self.denominator);
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Prints the exact decimal expansion when it terminates, e.g. `0.51`, and
+/// otherwise six decimals followed by an ellipsis, e.g. `0.666666…`.
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const MAX_DIGITS: usize = 6;
+        write!(f, "{}", self.numerator / self.denominator)?;
+        let mut remainder = u128::from(self.numerator % self.denominator);
+        let denominator = u128::from(self.denominator);
+        if remainder == 0 {
+            return Ok(());
+        }
+        f.write_str(".")?;
+        for _ in 0..MAX_DIGITS {
+            remainder *= 10;
+            write!(f, "{}", remainder / denominator)?;
+            remainder %= denominator;
+            if remainder == 0 {
+                return Ok(());
+            }
+        }
+        f.write_str("…")
+    }
 }
 
+/// A rational in `[0, 1]`, Haskell's `UnitInterval` (`BoundedRatio`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct UnitInterval(Rational);
+
 impl UnitInterval {
-    pub const HALF: UnitInterval = UnitInterval {
+    pub const HALF: UnitInterval = UnitInterval(Rational {
         numerator: 1,
         denominator: 2,
-    };
+    });
+
+    pub fn new(numerator: u64, denominator: u64) -> Result<Self, IntervalError> {
+        let ratio = Rational::new(numerator, denominator)?;
+        if ratio.numerator > ratio.denominator {
+            return Err(IntervalError::AboveOne);
+        }
+        Ok(UnitInterval(ratio))
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.0.numerator
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.0.denominator
+    }
+
+    /// Haskell's `unboundRational`.
+    pub fn to_rational(self) -> Rational {
+        self.0
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-pub struct NonNegativeInterval {
-    pub numerator: u64,
-    pub denominator: u64,
+impl fmt::Display for UnitInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A rational in `[0, ∞)`, Haskell's `NonNegativeInterval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct NonNegativeInterval(Rational);
+
+impl NonNegativeInterval {
+    pub fn new(numerator: u64, denominator: u64) -> Result<Self, IntervalError> {
+        Rational::new(numerator, denominator).map(NonNegativeInterval)
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.0.numerator
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.0.denominator
+    }
+
+    /// Haskell's `unboundRational`.
+    pub fn to_rational(self) -> Rational {
+        self.0
+    }
+}
+
+impl fmt::Display for NonNegativeInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<UnitInterval> for NonNegativeInterval {
+    fn from(interval: UnitInterval) -> Self {
+        NonNegativeInterval(interval.0)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -128,10 +304,12 @@ pub struct VRFKeyHash(pub Hash32);
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct VerificationKey(pub [u8; 32]); // `vkey = bytes .size 32`.
 
+// Variant order follows the derived Haskell `Ord`, which fixes the order in
+// which sets and maps of credentials are serialised.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Credential {
-    KeyHash(AddrKeyHash),
     ScriptHash(ScriptHash),
+    KeyHash(AddrKeyHash),
 }
 
 impl Default for Credential {
@@ -144,6 +322,7 @@ pub type StakeCredential = Credential;
 pub type CommitteeColdCredential = Credential;
 pub type CommitteeHotCredential = Credential;
 pub type DRepCredential = Credential;
+pub type GuardCredential = Credential;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum DRep {
@@ -171,7 +350,7 @@ pub struct PoolMetadata {
     pub metadata_hash: Vec<u8>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Relay {
     SingleHostAddr {
         port: Option<u16>,
@@ -187,6 +366,14 @@ pub enum Relay {
     },
 }
 
+impl Default for Relay {
+    fn default() -> Self {
+        Relay::MultiHostName {
+            dns_name: String::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct PoolParams {
     pub operator: PoolKeyHash,
@@ -250,6 +437,23 @@ impl Default for PoolCert {
     }
 }
 
+/// `ShelleyTxCert`, the certificates of Shelley to Babbage: those Conway
+/// kept, plus genesis delegation and MIR, which it dropped.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShelleyTxCert {
+    /// Tags 0 to 4: stake registration and delegation, and pool
+    /// registration and retirement.
+    Shared(PoolCert),
+    /// Tag: 5
+    GenesisDelegation {
+        genesis: KeyHash,
+        delegate: KeyHash,
+        vrf: VRFKeyHash,
+    },
+    /// Tag: 6
+    Mir(MIRCert),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Withdrawals(pub BTreeMap<RewardAccount, Coin>); // `withdrawals = {+ reward_account => coin}`.
 
@@ -257,7 +461,7 @@ pub struct Withdrawals(pub BTreeMap<RewardAccount, Coin>); // `withdrawals = {+
 pub struct AssetName(pub Vec<u8>); // `asset_name = bytes .size (0 .. 32)`.
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-pub struct MultiAsset(pub BTreeMap<PolicyId, BTreeMap<AssetName, u64>>);
+pub struct MultiAsset(pub BTreeMap<PolicyId, BTreeMap<AssetName, i128>>); // `Integer` quantities, negative for burns.
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ValueStruct {
@@ -356,28 +560,30 @@ impl Default for Script {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-pub struct ScriptRef(pub Script);
+pub struct ScriptRef<S = Script>(pub S);
 
+/// Outputs are parameterised by their era, which fixes the values they hold
+/// and the scripts they can reference.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum TxOutStruct {
+pub enum TxOutStruct<E: Era = Conway> {
     Shelley {
         address: Address,
-        amount: ValueStruct,
-        datum_hash: Option<DataHash>,
+        amount: E::Value,
+        datum_hash: Option<DataHash>, // From Alonzo onwards.
     },
     Babbage {
         address: Address,
-        amount: ValueStruct,
+        amount: E::Value,
         datum_option: Option<DatumOption>,
-        script_ref: Option<ScriptRef>,
+        script_ref: Option<ScriptRef<E::Script>>,
     },
 }
 
-impl Default for TxOutStruct {
+impl<E: Era> Default for TxOutStruct<E> {
     fn default() -> Self {
         TxOutStruct::Shelley {
             address: Address::default(),
-            amount: ValueStruct::default(),
+            amount: E::Value::default(),
             datum_hash: None,
         }
     }
@@ -537,6 +743,7 @@ impl Default for GovActionStruct {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum GovActionPurpose {
+    #[default]
     ParameterChange,
     HardFork,
     Committee,
@@ -551,6 +758,7 @@ pub struct GovPurposeIdStruct {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum RedeemerTag {
+    #[default]
     Spend,
     Mint,
     Cert,
@@ -565,6 +773,61 @@ pub struct PlutusPurposeStruct {
     pub index: u32,
 }
 
+/// `PlutusPurpose AsItem`: the item a redeemer is attached to, rather than
+/// its index in the transaction body.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PlutusPurposeItemStruct {
+    Spending(TxIn),
+    Minting(PolicyId),
+    Certifying(PoolCert),
+    Rewarding(RewardAccount),
+    Voting(Voter),
+    Proposing(Box<ProposalProcedureStruct>),
+}
+
+/// `AlonzoPlutusPurpose`'s tags, shared by Babbage: there is nothing to vote
+/// or propose before Conway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum AlonzoRedeemerTag {
+    #[default]
+    Spend,
+    Mint,
+    Cert,
+    Reward,
+}
+
+/// `AlonzoPlutusPurpose AsIx`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct AlonzoPlutusPurpose {
+    pub tag: AlonzoRedeemerTag,
+    pub index: u32,
+}
+
+/// `AlonzoPlutusPurpose AsItem`: certificates are [`ShelleyTxCert`]s.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AlonzoPlutusPurposeItem {
+    Spending(TxIn),
+    Minting(PolicyId),
+    Certifying(ShelleyTxCert),
+    Rewarding(RewardAccount),
+}
+
+/// Where a `TxOut` seen while building the Plutus `TxInfo` came from.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TxOutSource {
+    FromInput(TxIn),
+    FromOutput(TxIx),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Language {
+    #[default]
+    PlutusV1,
+    PlutusV2,
+    PlutusV3,
+    PlutusV4,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct GovEnvStruct;
 
@@ -578,11 +841,18 @@ pub enum RelationKind {
     Subset,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct RelationMismatch<T> {
-    pub relation: RelationKind,
-    pub supplied: T,
-    pub expected: T,
+impl RelationKind {
+    /// The relation as the ledger specs write it.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            RelationKind::Eq => "=",
+            RelationKind::Lteq => "≤",
+            RelationKind::Gteq => "≥",
+            RelationKind::Lt => "<",
+            RelationKind::Gt => ">",
+            RelationKind::Subset => "⊆",
+        }
+    }
 }
 
 pub type StrictMaybe<T> = Option<T>;
@@ -593,32 +863,153 @@ pub type TagMismatchDescription = String;
 pub type IsValid = bool;
 pub type Text = String;
 
+/// Haskell's `NonEmpty`: a list with at least one element, so a failure
+/// listing its offenders always names one.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NonEmpty<T>(Vec<T>);
+
+impl<T> NonEmpty<T> {
+    /// `None` for an empty list.
+    pub fn new(items: Vec<T>) -> Option<Self> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(NonEmpty(items))
+        }
+    }
+
+    pub fn singleton(head: T) -> Self {
+        NonEmpty(vec![head])
+    }
+
+    pub fn head(&self) -> &T {
+        &self.0[0]
+    }
+
+    pub fn tail(&self) -> &[T] {
+        &self.0[1..]
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> IntoIterator for NonEmpty<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a NonEmpty<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
-pub struct NonEmpty<T>(pub Vec<T>); // TODO: enforce the invariant.
+pub struct OSet<T>(pub Vec<T>); // Set that keeps insertion order on the wire.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct GovActionIx(pub u16);
 
-pub type TxOut<Era> = TxOutStruct;
-pub type Value<Era> = ValueStruct;
-pub type UTxO<Era> = UTxOStruct;
-pub type GovAction<Era> = GovActionStruct;
-pub type ProposalProcedure<Era> = ProposalProcedureStruct;
-pub type VotingProcedures<Era> = VotingProceduresStruct;
-pub type GovEnv<Era> = GovEnvStruct;
+pub type TxOut<E> = TxOutStruct<E>;
+pub type Value<E> = <E as Era>::Value;
+pub type UTxO = UTxOStruct;
+pub type GovAction = GovActionStruct;
+pub type ProposalProcedure = ProposalProcedureStruct;
+pub type VotingProcedures = VotingProceduresStruct;
+pub type GovEnv = GovEnvStruct;
 pub type Voter = VoterEnum;
 pub type GovPurposeId<Purpose> = GovPurposeIdStruct;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct HardForkPurpose;
 
-pub type PlutusPurpose<Tag, Era> = PlutusPurposeStruct;
+pub type PlutusPurpose<E> = <E as PlutusEra>::PurposeIx;
+pub type PlutusPurposeItem<E> = <E as PlutusEra>::PurposeItem;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct AsItem;
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct AsIx;
 
-pub type Mismatch<Relation, T> = RelationMismatch<T>;
+/// `Mismatch (r :: Relation) a`: a check `supplied r expected` that failed.
+/// The relation is fixed by the type, as in Haskell, so decoding a field
+/// declared `Mismatch<RelLTEQ, _>` cannot yield any other relation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch<R, T> {
+    pub relation: R,
+    pub supplied: T,
+    pub expected: T,
+}
+
+impl<R: Relation, T> Mismatch<R, T> {
+    pub fn new(supplied: T, expected: T) -> Self {
+        Mismatch {
+            relation: R::default(),
+            supplied,
+            expected,
+        }
+    }
+
+    pub fn kind(&self) -> RelationKind {
+        R::KIND
+    }
+
+    /// Whether `supplied` actually stands in the relation to `expected`.  A
+    /// mismatch reported by the ledger never does.
+    pub fn holds(&self) -> bool
+    where
+        R: Holds<T>,
+    {
+        R::holds(&self.supplied, &self.expected)
+    }
+
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Mismatch<R, U> {
+        Mismatch {
+            relation: self.relation,
+            supplied: f(self.supplied),
+            expected: f(self.expected),
+        }
+    }
+}
+
+/// Renders the violated relation, e.g. "supplied 17000 must be ≤ 16384".
+impl<R: Relation, T: fmt::Display> fmt::Display for Mismatch<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "supplied {} must be {} {}",
+            self.supplied,
+            R::KIND.symbol(),
+            self.expected
+        )
+    }
+}
+
+/// A type-level `Relation`, the first parameter of `Mismatch`.
+pub trait Relation: Copy + Default + fmt::Debug + Eq {
+    const KIND: RelationKind;
+}
+
+/// The check a relation stands for on the values it compares.
+pub trait Holds<T>: Relation {
+    fn holds(supplied: &T, expected: &T) -> bool;
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct RelEQ;
@@ -633,6 +1024,56 @@ pub struct RelGT;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct RelSubset;
 
+impl Relation for RelEQ {
+    const KIND: RelationKind = RelationKind::Eq;
+}
+impl Relation for RelLTEQ {
+    const KIND: RelationKind = RelationKind::Lteq;
+}
+impl Relation for RelGTEQ {
+    const KIND: RelationKind = RelationKind::Gteq;
+}
+impl Relation for RelLT {
+    const KIND: RelationKind = RelationKind::Lt;
+}
+impl Relation for RelGT {
+    const KIND: RelationKind = RelationKind::Gt;
+}
+impl Relation for RelSubset {
+    const KIND: RelationKind = RelationKind::Subset;
+}
+
+impl<T: PartialEq> Holds<T> for RelEQ {
+    fn holds(supplied: &T, expected: &T) -> bool {
+        supplied == expected
+    }
+}
+impl<T: PartialOrd> Holds<T> for RelLTEQ {
+    fn holds(supplied: &T, expected: &T) -> bool {
+        supplied <= expected
+    }
+}
+impl<T: PartialOrd> Holds<T> for RelGTEQ {
+    fn holds(supplied: &T, expected: &T) -> bool {
+        supplied >= expected
+    }
+}
+impl<T: PartialOrd> Holds<T> for RelLT {
+    fn holds(supplied: &T, expected: &T) -> bool {
+        supplied < expected
+    }
+}
+impl<T: PartialOrd> Holds<T> for RelGT {
+    fn holds(supplied: &T, expected: &T) -> bool {
+        supplied > expected
+    }
+}
+impl<T: Ord> Holds<BTreeSet<T>> for RelSubset {
+    fn holds(supplied: &BTreeSet<T>, expected: &BTreeSet<T>) -> bool {
+        supplied.is_subset(expected)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Shelley era predicate failures
 // ---------------------------------------------------------------------------
@@ -667,14 +1108,15 @@ pub mod shelley {
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxoPredicateFailure<Era> {
+    pub enum UtxoPredicateFailure<E: Era> {
         /// Tag: 0
         BadInputsUTxO {
             invalid_inputs: BTreeSet<TxIn>,
         },
         /// Tag: 1
         ExpiredUTxO {
-            current_slot: SlotNo,
+            // Supplied is the transaction's TTL, expected the current slot.
+            ttl_mismatch: Mismatch<RelLTEQ, SlotNo>,
         },
         /// Tag: 2
         MaxTxSizeUTxO {
@@ -688,11 +1130,11 @@ pub mod shelley {
         },
         /// Tag: 5
         ValueNotConservedUTxO {
-            balance_mismatch: Mismatch<RelEQ, Value<Era>>,
+            balance_mismatch: Mismatch<RelEQ, Value<E>>,
         },
         /// Tag: 6
         OutputTooSmallUTxO {
-            tiny_outputs: Vec<TxOut<Era>>,
+            tiny_outputs: Vec<TxOut<E>>,
         },
         /// Tag: 7
         UpdateFailure(super::shelley::PpupPredicateFailure),
@@ -708,12 +1150,13 @@ pub mod shelley {
         },
         /// Tag: 10
         OutputBootAddrAttrsTooBig {
-            oversized_bootstrap_outputs: Vec<TxOut<Era>>,
+            oversized_bootstrap_outputs: Vec<TxOut<E>>,
         },
     }
 
+    /// Allegra and Mary reuse this type with their own UTXO failure.
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxowPredicateFailure<Era> {
+    pub enum UtxowPredicateFailure<Utxo> {
         /// Tag: 0
         InvalidWitnessesUTXOW {
             invalid_witnesses: Vec<Credential>,
@@ -731,7 +1174,7 @@ pub mod shelley {
             failed_scripts: BTreeSet<ScriptHash>,
         },
         /// Tag: 4
-        UtxoFailure(super::shelley::UtxoPredicateFailure<Era>),
+        UtxoFailure(Utxo),
         /// Tag: 5
         MIRInsufficientGenesisSigsUTXOW {
             missing_signatures: BTreeSet<KeyHash>,
@@ -848,7 +1291,7 @@ pub mod shelley {
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum DelegsPredicateFailure<Era> {
+    pub enum DelegsPredicateFailure {
         /// Tag: 0
         DelegateeNotRegistered {
             pool_id: KeyHash,
@@ -858,27 +1301,28 @@ pub mod shelley {
             withdrawals: Withdrawals,
         },
         /// Tag: 2
-        DelplFailure(DelplPredicateFailure<Era>),
+        DelplFailure(DelplPredicateFailure),
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum DelplPredicateFailure<Era> {
+    pub enum DelplPredicateFailure {
         /// Tag: 0
         PoolFailure(PoolPredicateFailure),
         /// Tag: 1
         DelegFailure(DelegPredicateFailure),
     }
 
+    /// Allegra and Mary reuse this type with their own UTXO failure.
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum LedgerPredicateFailure<Era> {
+    pub enum LedgerPredicateFailure<Utxo> {
         /// Tag: 0
-        UtxowFailure(UtxowPredicateFailure<Era>),
+        UtxowFailure(UtxowPredicateFailure<Utxo>),
         /// Tag: 1
-        DelegsFailure(DelegsPredicateFailure<Era>),
+        DelegsFailure(DelegsPredicateFailure),
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum BbodyPredicateFailure<Era> {
+    pub enum BbodyPredicateFailure<E: Era> {
         // No explicit CBOR instance in the source; tags unknown.
         WrongBlockBodySizeBBODY {
             mismatch: Mismatch<RelEQ, usize>,
@@ -886,14 +1330,15 @@ pub mod shelley {
         InvalidBodyHashBBODY {
             mismatch: Mismatch<RelEQ, [u8; 32]>, // TODO: real hash type
         },
-        LedgersFailure(LedgersPredicateFailure<Era>),
+        LedgersFailure(LedgersPredicateFailure<LedgerPredicateFailure<UtxoPredicateFailure<E>>>),
     }
 
+    /// Later eras reuse this type with their own LEDGER failure.
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum LedgersPredicateFailure<Era> {
+    pub enum LedgersPredicateFailure<Ledger> {
         /// Tag 0 relayed through `ShelleyBbodyPredFailure`
-        LedgersFailure(LedgerPredicateFailure<Era>),
-        /// TODO: capture other constructors when serialisation is required.
+        LedgersFailure(Ledger),
+        // TODO: capture other constructors when serialisation is required.
     }
 
     // TODO: Mirror the remaining Shelley-era failures (e.g. MIR, TICK) if
@@ -907,24 +1352,76 @@ pub mod shelley {
 pub mod allegra {
     use super::*;
 
+    /// `AllegraUtxoPredFailure`, the UTXO failure of Allegra and Mary.  It
+    /// follows the Shelley tags, with validity intervals replacing the TTL.
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxoPredicateFailure<Era> {
-        /// Tag: 0 (mirrors Shelley via translation layer)
-        AlonzoWrapped(super::alonzo::UtxoPredicateFailure<Era>),
+    pub enum UtxoPredicateFailure<E: Era> {
+        /// Tag: 0
+        BadInputsUTxO {
+            invalid_inputs: BTreeSet<TxIn>,
+        },
         /// Tag: 1
-        ValidateOutsideValidityInterval {
+        OutsideValidityIntervalUTxO {
             interval: ValidityInterval,
+            current_slot: SlotNo,
+        },
+        /// Tag: 2
+        MaxTxSizeUTxO {
+            size_mismatch: Mismatch<RelLTEQ, u32>,
+        },
+        /// Tag: 3
+        InputSetEmptyUTxO,
+        /// Tag: 4
+        FeeTooSmallUTxO {
+            fee_mismatch: Mismatch<RelGTEQ, Coin>,
+        },
+        /// Tag: 5
+        ValueNotConservedUTxO {
+            balance_mismatch: Mismatch<RelEQ, Value<E>>,
+        },
+        /// Tag: 6
+        OutputTooSmallUTxO {
+            tiny_outputs: Vec<TxOut<E>>,
+        },
+        /// Tag: 7
+        UpdateFailure(super::shelley::PpupPredicateFailure),
+        /// Tag: 8
+        WrongNetwork {
+            expected: NetworkId,
+            offending: BTreeSet<Address>,
+        },
+        /// Tag: 9
+        WrongNetworkWithdrawal {
+            expected: NetworkId,
+            offending: BTreeSet<RewardAccount>,
+        },
+        /// Tag: 10
+        OutputBootAddrAttrsTooBig {
+            oversized_bootstrap_outputs: Vec<TxOut<E>>,
+        },
+        /// Tag: 11.  Dropped from the ledger since, but Mary-era nodes
+        /// rejected transactions minting lovelace with it; other eras never
+        /// produce it.
+        TriesToForgeADA,
+        /// Tag: 12
+        OutputTooBigUTxO {
+            oversized_outputs: Vec<TxOut<E>>,
         },
-        // TODO: complete once allegra-specific constructors are required.
     }
 
-    // Mary reuses Allegra failures; see the `mary` module for explicit aliases.
+    pub type UtxowPredicateFailure<E> =
+        super::shelley::UtxowPredicateFailure<UtxoPredicateFailure<E>>;
+    pub type LedgerPredicateFailure<E> =
+        super::shelley::LedgerPredicateFailure<UtxoPredicateFailure<E>>;
 }
 
+/// Mary keeps the Allegra rules; what changes is `Value`, which is
+/// multi-asset from here on, so `mary::UtxoPredicateFailure<Mary>` decodes
+/// token balances that `allegra::UtxoPredicateFailure<Allegra>` rejects.
 pub mod mary {
-    use super::*;
-
-    pub type UtxoPredicateFailure<Era> = super::allegra::UtxoPredicateFailure<Era>;
+    pub type UtxoPredicateFailure<E> = super::allegra::UtxoPredicateFailure<E>;
+    pub type UtxowPredicateFailure<E> = super::allegra::UtxowPredicateFailure<E>;
+    pub type LedgerPredicateFailure<E> = super::allegra::LedgerPredicateFailure<E>;
 }
 
 // ---------------------------------------------------------------------------
@@ -935,9 +1432,9 @@ pub mod alonzo {
     use super::*;
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum BbodyPredicateFailure<Era> {
+    pub enum BbodyPredicateFailure<E: PlutusEra> {
         /// Tag: 0
-        ShelleyInAlonzo(super::shelley::BbodyPredicateFailure<Era>),
+        ShelleyInAlonzo(super::shelley::BbodyPredicateFailure<E>),
         /// Tag: 1
         TooManyExUnits {
             bound: Mismatch<RelLTEQ, ExUnits>,
@@ -945,7 +1442,7 @@ pub mod alonzo {
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxoPredicateFailure<Era> {
+    pub enum UtxoPredicateFailure<E: PlutusEra> {
         /// Tag: 0
         BadInputsUTxO {
             invalid_inputs: BTreeSet<TxIn>,
@@ -967,14 +1464,14 @@ pub mod alonzo {
         },
         /// Tag: 5
         ValueNotConservedUTxO {
-            mismatch: Mismatch<RelEQ, Value<Era>>,
+            mismatch: Mismatch<RelEQ, Value<E>>,
         },
         /// Tag: 6
         OutputTooSmallUTxO {
-            outputs: Vec<TxOut<Era>>,
+            outputs: Vec<TxOut<E>>,
         },
         /// Tag: 7
-        UtxosFailure(UtxosPredicateFailure<Era>),
+        UtxosFailure(UtxosPredicateFailure<E>),
         /// Tag: 8
         WrongNetwork {
             expected: NetworkId,
@@ -987,11 +1484,11 @@ pub mod alonzo {
         },
         /// Tag: 10
         OutputBootAddrAttrsTooBig {
-            outputs: Vec<TxOut<Era>>,
+            outputs: Vec<TxOut<E>>,
         },
         /// Tag: 12
         OutputTooBigUTxO {
-            oversized_outputs: Vec<(i32, i32, TxOut<Era>)>,
+            oversized_outputs: Vec<(i32, i32, TxOut<E>)>,
         },
         /// Tag: 13
         InsufficientCollateral {
@@ -1000,7 +1497,7 @@ pub mod alonzo {
         },
         /// Tag: 14
         ScriptsNotPaidUTxO {
-            offending_utxo: UTxO<Era>,
+            offending_utxo: UTxO,
         },
         /// Tag: 15
         ExUnitsTooBigUTxO {
@@ -1008,7 +1505,7 @@ pub mod alonzo {
         },
         /// Tag: 16
         CollateralContainsNonADA {
-            value: Value<Era>,
+            value: Value<E>,
         },
         /// Tag: 17
         WrongNetworkInTxBody {
@@ -1034,8 +1531,10 @@ pub mod alonzo {
         FailedUnexpectedly(Vec<FailureDescription>),
     }
 
+    /// Babbage reaches this rule through `AlonzoInBabbage`, so the collect
+    /// errors carry the era's own `ContextError`.
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxosPredicateFailure<Era> {
+    pub enum UtxosPredicateFailure<E: PlutusEra> {
         /// Tag: 0
         ValidationTagMismatch {
             is_valid: bool,
@@ -1043,26 +1542,51 @@ pub mod alonzo {
         },
         /// Tag: 1
         CollectErrors {
-            errors: Vec<CollectError<Era>>,
+            errors: Vec<CollectError<E>>,
         },
         /// Tag: 2
         UpdateFailure(super::shelley::PpupPredicateFailure),
     }
 
+    /// Later eras reuse this type with their own purposes and `ContextError`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum CollectError<E: PlutusEra> {
+        /// Tag: 0
+        NoRedeemer {
+            purpose: PlutusPurposeItem<E>,
+        },
+        /// Tag: 1
+        NoWitness {
+            script_hash: ScriptHash,
+        },
+        /// Tag: 2
+        NoCostModel {
+            language: Language,
+        },
+        /// Tag: 3
+        BadTranslation(E::ContextError),
+    }
+
+    /// `AlonzoContextError`, raised while building the Plutus V1 `TxInfo`.
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum CollectError<Era> {
-        // Placeholder; see `collectTwoPhaseScriptInputs` for full shape.
-        /// TODO: encode exact variants from `CollectError`.
-        Placeholder, // TODO: encode exact variants from `CollectError`.
+    pub enum ContextError {
+        /// Tag: 1
+        TranslationLogicMissingInput {
+            input: TxIn,
+        },
+        /// Tag: 7
+        TimeTranslationPastHorizon {
+            message: Text,
+        },
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxowPredicateFailure<Era> {
+    pub enum UtxowPredicateFailure<E: PlutusEra> {
         /// Tag: 0
-        ShelleyInAlonzo(super::shelley::UtxowPredicateFailure<Era>),
+        ShelleyInAlonzo(super::shelley::UtxowPredicateFailure<UtxoPredicateFailure<E>>),
         /// Tag: 1
         MissingRedeemers {
-            missing: Vec<(PlutusPurpose<AsItem, Era>, ScriptHash)>,
+            missing: Vec<(PlutusPurposeItem<E>, ScriptHash)>,
         },
         /// Tag: 2
         MissingRequiredDatums {
@@ -1084,7 +1608,7 @@ pub mod alonzo {
         },
         /// Tag: 7
         ExtraRedeemers {
-            extra: Vec<PlutusPurpose<AsIx, Era>>,
+            extra: Vec<PlutusPurpose<E>>,
         },
         /// Tag: 8
         ScriptIntegrityHashMismatch {
@@ -1093,6 +1617,14 @@ pub mod alonzo {
         },
     }
 
+    /// `ShelleyLedgerPredFailure` instantiated at Alonzo's UTXOW rule.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum LedgerPredicateFailure<E: PlutusEra> {
+        /// Tag: 0
+        UtxowFailure(UtxowPredicateFailure<E>),
+        /// Tag: 1
+        DelegsFailure(super::shelley::DelegsPredicateFailure),
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1103,9 +1635,9 @@ pub mod babbage {
     use super::*;
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxoPredicateFailure<Era> {
+    pub enum UtxoPredicateFailure<E: PlutusEra> {
         /// Tag: 1
-        AlonzoInBabbage(super::alonzo::UtxoPredicateFailure<Era>),
+        AlonzoInBabbage(super::alonzo::UtxoPredicateFailure<E>),
         /// Tag: 2
         IncorrectTotalCollateralField {
             provided: DeltaCoin,
@@ -1113,7 +1645,7 @@ pub mod babbage {
         },
         /// Tag: 3
         OutputTooSmall {
-            outputs: Vec<(TxOut<Era>, Coin)>,
+            outputs: Vec<(TxOut<E>, Coin)>,
         },
         /// Tag: 4
         NonDisjointReferenceInputs {
@@ -1122,11 +1654,11 @@ pub mod babbage {
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxowPredicateFailure<Era> {
+    pub enum UtxowPredicateFailure<E: PlutusEra> {
         /// Tag: 1
-        AlonzoInBabbage(super::alonzo::UtxowPredicateFailure<Era>),
+        AlonzoInBabbage(super::alonzo::UtxowPredicateFailure<E>),
         /// Tag: 2
-        UtxoFailure(UtxoPredicateFailure<Era>),
+        UtxoFailure(UtxoPredicateFailure<E>),
         /// Tag: 3
         MalformedScriptWitnesses {
             witnesses: BTreeSet<ScriptHash>,
@@ -1142,6 +1674,43 @@ pub mod babbage {
         },
     }
 
+    /// `BabbageContextError`, raised while building the Plutus V1 and V2
+    /// `TxInfo`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ContextError<E: PlutusEra> {
+        /// Tag: 0
+        ByronTxOutInContext {
+            source: TxOutSource,
+        },
+        /// Tags: 1 and 7, flattened into this sum rather than nested.
+        AlonzoContextError(super::alonzo::ContextError),
+        /// Tag: 2
+        RedeemerPointerPointsToNothing {
+            purpose: PlutusPurpose<E>,
+        },
+        /// Tag: 4
+        InlineDatumsNotSupported {
+            source: TxOutSource,
+        },
+        /// Tag: 5
+        ReferenceScriptsNotSupported {
+            source: TxOutSource,
+        },
+        /// Tag: 6
+        ReferenceInputsNotSupported {
+            inputs: BTreeSet<TxIn>,
+        },
+    }
+
+    /// `ShelleyLedgerPredFailure` instantiated at Babbage's UTXOW rule.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum LedgerPredicateFailure<E: PlutusEra> {
+        /// Tag: 0
+        UtxowFailure(UtxowPredicateFailure<E>),
+        /// Tag: 1
+        DelegsFailure(super::shelley::DelegsPredicateFailure),
+    }
+
     // TODO: Extend with Babbage-specific ledgers/bbody predicate failures when
     // serialisation hooks are required.
 }
@@ -1154,7 +1723,7 @@ pub mod conway {
     use super::*;
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxosPredicateFailure<Era> {
+    pub enum UtxosPredicateFailure<E: PlutusEra> {
         /// Tag: 0
         ValidationTagMismatch {
             tag: IsValid,
@@ -1162,14 +1731,50 @@ pub mod conway {
         },
         /// Tag: 1
         CollectErrors {
-            errors: Vec<super::alonzo::CollectError<Era>>,
+            errors: Vec<super::alonzo::CollectError<E>>,
         },
     }
 
+    /// `ConwayContextError`, raised while building the Plutus V1, V2 and V3
+    /// `TxInfo`.  Tags start at 8 to stay clear of the Babbage ones.
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxoPredicateFailure<Era> {
+    pub enum ContextError<E: PlutusEra> {
+        /// Tag: 8
+        BabbageContextError(super::babbage::ContextError<E>),
+        /// Tag: 9
+        CertificateNotSupported {
+            certificate: E::TxCert,
+        },
+        /// Tag: 10
+        PlutusPurposeNotSupported {
+            purpose: PlutusPurposeItem<E>,
+        },
+        /// Tag: 11
+        CurrentTreasuryFieldNotSupported {
+            treasury: Coin,
+        },
+        /// Tag: 12
+        VotingProceduresFieldNotSupported {
+            procedures: VotingProcedures,
+        },
+        /// Tag: 13
+        ProposalProceduresFieldNotSupported {
+            procedures: OSet<ProposalProcedure>,
+        },
+        /// Tag: 14
+        TreasuryDonationFieldNotSupported {
+            donation: Coin,
+        },
+        /// Tag: 15
+        ReferenceInputsNotDisjointFromInputs {
+            common: NonEmpty<TxIn>,
+        },
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum UtxoPredicateFailure<E: PlutusEra> {
         /// Tag: 0
-        UtxosFailure(UtxosPredicateFailure<Era>),
+        UtxosFailure(UtxosPredicateFailure<E>),
         /// Tag: 1
         BadInputsUTxO {
             invalid_inputs: BTreeSet<TxIn>,
@@ -1191,7 +1796,7 @@ pub mod conway {
         },
         /// Tag: 6
         ValueNotConservedUTxO {
-            balance_mismatch: Mismatch<RelEQ, Value<Era>>,
+            balance_mismatch: Mismatch<RelEQ, Value<E>>,
         },
         /// Tag: 7
         WrongNetwork {
@@ -1205,16 +1810,16 @@ pub mod conway {
         },
         /// Tag: 9
         OutputTooSmallUTxO {
-            tiny_outputs: Vec<TxOut<Era>>,
+            tiny_outputs: Vec<TxOut<E>>,
         },
         /// Tag: 10
         OutputBootAddrAttrsTooBig {
-            oversized_bootstrap_outputs: Vec<TxOut<Era>>,
+            oversized_bootstrap_outputs: Vec<TxOut<E>>,
         },
         /// Tag: 11
         OutputTooBigUTxO {
             // (actual_size, max_size, output)
-            outputs: Vec<(i64, i64, TxOut<Era>)>,
+            outputs: Vec<(i64, i64, TxOut<E>)>,
         },
         /// Tag: 12
         InsufficientCollateral {
@@ -1223,7 +1828,7 @@ pub mod conway {
         },
         /// Tag: 13
         ScriptsNotPaidUTxO {
-            unpaid: UTxO<Era>,
+            unpaid: UTxO,
         },
         /// Tag: 14
         ExUnitsTooBigUTxO {
@@ -1231,7 +1836,7 @@ pub mod conway {
         },
         /// Tag: 15
         CollateralContainsNonADA {
-            offending_value: Value<Era>,
+            offending_value: Value<E>,
         },
         /// Tag: 16
         WrongNetworkInTxBody {
@@ -1254,7 +1859,7 @@ pub mod conway {
         },
         /// Tag: 21
         BabbageOutputTooSmallUTxO {
-            outputs: Vec<(TxOut<Era>, Coin)>,
+            outputs: Vec<(TxOut<E>, Coin)>,
         },
         /// Tag: 22
         BabbageNonDisjointRefInputs {
@@ -1263,9 +1868,9 @@ pub mod conway {
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum UtxowPredicateFailure<Era> {
+    pub enum UtxowPredicateFailure<E: PlutusEra> {
         /// Tag: 0
-        UtxoFailure(UtxoPredicateFailure<Era>),
+        UtxoFailure(UtxoPredicateFailure<E>),
         /// Tag: 1
         InvalidWitnessesUTXOW {
             witnesses: Vec<VerificationKey>,
@@ -1302,7 +1907,7 @@ pub mod conway {
         },
         /// Tag: 10
         MissingRedeemers {
-            missing: Vec<(PlutusPurpose<AsItem, Era>, ScriptHash)>,
+            missing: Vec<(PlutusPurposeItem<E>, ScriptHash)>,
         },
         /// Tag: 11
         MissingRequiredDatums {
@@ -1324,7 +1929,7 @@ pub mod conway {
         },
         /// Tag: 15
         ExtraRedeemers {
-            extra: Vec<PlutusPurpose<AsIx, Era>>,
+            extra: Vec<PlutusPurpose<E>>,
         },
         /// Tag: 16
         MalformedScriptWitnesses {
@@ -1406,7 +2011,7 @@ pub mod conway {
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum CertPredicateFailure<Era> {
+    pub enum CertPredicateFailure {
         /// Tag: 1
         DelegFailure(DelegPredicateFailure),
         /// Tag: 2
@@ -1416,24 +2021,24 @@ pub mod conway {
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum CertsPredicateFailure<Era> {
+    pub enum CertsPredicateFailure {
         /// Tag: 0
         WithdrawalsNotInRewardsCERTS {
             withdrawals: Withdrawals,
         },
         /// Tag: 1
-        CertFailure(CertPredicateFailure<Era>),
+        CertFailure(CertPredicateFailure),
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum GovPredicateFailure<Era> {
+    pub enum GovPredicateFailure {
         /// Tag: 0
         GovActionsDoNotExist {
             missing: NonEmpty<GovActionId>,
         },
         /// Tag: 1
         MalformedProposal {
-            proposal: GovAction<Era>,
+            proposal: GovAction,
         },
         /// Tag: 2
         ProposalProcedureNetworkIdMismatch {
@@ -1463,7 +2068,7 @@ pub mod conway {
         },
         /// Tag: 8
         InvalidPrevGovActionId {
-            proposal: ProposalProcedure<Era>,
+            proposal: ProposalProcedure,
         },
         /// Tag: 9
         VotingOnExpiredGovAction {
@@ -1481,7 +2086,7 @@ pub mod conway {
         },
         /// Tag: 12
         DisallowedProposalDuringBootstrap {
-            proposal: ProposalProcedure<Era>,
+            proposal: ProposalProcedure,
         },
         /// Tag: 13
         DisallowedVotesDuringBootstrap {
@@ -1493,7 +2098,7 @@ pub mod conway {
         },
         /// Tag: 15
         ZeroTreasuryWithdrawals {
-            action: GovAction<Era>,
+            action: GovAction,
         },
         /// Tag: 16
         ProposalReturnAccountDoesNotExist {
@@ -1510,13 +2115,13 @@ pub mod conway {
     }
 
     #[derive(Debug, Clone, PartialEq, Eq)]
-    pub enum LedgerPredicateFailure<Era> {
+    pub enum LedgerPredicateFailure<E: PlutusEra> {
         /// Tag: 1
-        ConwayUtxowFailure(UtxowPredicateFailure<Era>),
+        ConwayUtxowFailure(UtxowPredicateFailure<E>),
         /// Tag: 2
-        ConwayCertsFailure(CertsPredicateFailure<Era>),
+        ConwayCertsFailure(CertsPredicateFailure),
         /// Tag: 3
-        ConwayGovFailure(GovPredicateFailure<Era>),
+        ConwayGovFailure(Box<GovPredicateFailure>),
         /// Tag: 4
         ConwayWdrlNotDelegatedToDRep {
             withdrawals: NonEmpty<AddrKeyHash>,
@@ -1543,4 +2148,134 @@ pub mod conway {
             withdrawals: Withdrawals,
         },
     }
-}
\ No newline at end of file
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum BbodyPredicateFailure<E: PlutusEra> {
+        /// Tag: 0
+        WrongBlockBodySizeBBODY {
+            mismatch: Mismatch<RelEQ, i64>,
+        },
+        /// Tag: 1
+        InvalidBodyHashBBODY {
+            mismatch: Mismatch<RelEQ, Hash32>,
+        },
+        /// Tag: 2
+        LedgersFailure(Box<super::shelley::LedgersPredicateFailure<LedgerPredicateFailure<E>>>),
+        /// Tag: 3
+        TooManyExUnits {
+            bound: Mismatch<RelLTEQ, ExUnits>,
+        },
+        /// Tag: 4
+        BodyRefScriptsSizeTooBig {
+            size_mismatch: Mismatch<RelLTEQ, i64>,
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Dijkstra era predicate failures
+// ---------------------------------------------------------------------------
+
+/// Dijkstra keeps the Conway rules' failures (`EraRuleFailure "UTXO"
+/// DijkstraEra = ConwayUtxoPredFailure DijkstraEra` and so on) and the
+/// Shelley POOL failure.  What changes is the shape of the payloads, through
+/// the types below: purposes with `Guarding`, native scripts with
+/// `RequireGuard`, Plutus V4 scripts, and a certificate type without the
+/// deposit-less registrations.  The shared purpose, script and certificate
+/// stand-ins reject all of these.
+pub mod dijkstra {
+    use super::*;
+
+    pub type UtxosPredicateFailure<E> = super::conway::UtxosPredicateFailure<E>;
+    pub type ContextError<E> = super::conway::ContextError<E>;
+    pub type UtxoPredicateFailure<E> = super::conway::UtxoPredicateFailure<E>;
+    pub type UtxowPredicateFailure<E> = super::conway::UtxowPredicateFailure<E>;
+    pub type DelegPredicateFailure = super::conway::DelegPredicateFailure;
+    pub type PoolPredicateFailure = super::shelley::PoolPredicateFailure;
+    pub type GovCertPredicateFailure = super::conway::GovCertPredicateFailure;
+    pub type CertPredicateFailure = super::conway::CertPredicateFailure;
+    pub type CertsPredicateFailure = super::conway::CertsPredicateFailure;
+    pub type GovPredicateFailure = super::conway::GovPredicateFailure;
+    pub type LedgerPredicateFailure<E> = super::conway::LedgerPredicateFailure<E>;
+    pub type BbodyPredicateFailure<E> = super::conway::BbodyPredicateFailure<E>;
+
+    /// `DijkstraTxCert`: the Conway certificates minus `StakeRegistration`
+    /// and `StakeDeregistration` (tags 0 and 1), which carried no deposit.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct TxCert(PoolCert);
+
+    impl TxCert {
+        /// `None` for the certificates Dijkstra no longer accepts.
+        pub fn new(cert: PoolCert) -> Option<Self> {
+            match cert {
+                PoolCert::StakeRegistration { .. } | PoolCert::StakeDeregistration { .. } => None,
+                cert => Some(TxCert(cert)),
+            }
+        }
+
+        pub fn as_pool_cert(&self) -> &PoolCert {
+            &self.0
+        }
+    }
+
+    impl From<TxCert> for PoolCert {
+        fn from(cert: TxCert) -> Self {
+            cert.0
+        }
+    }
+
+    /// `DijkstraNativeScript`: the timelock constructors plus `RequireGuard`.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum NativeScript {
+        ScriptPubkey(AddrKeyHash),
+        ScriptAll(Vec<NativeScript>),
+        ScriptAny(Vec<NativeScript>),
+        ScriptNOfK { required: i64, scripts: Vec<NativeScript> },
+        InvalidBefore(SlotNo),
+        InvalidHereafter(SlotNo),
+        /// Tag: 6.  The credential must be among the transaction's guards.
+        RequireGuard(GuardCredential),
+    }
+
+    /// `DijkstraScript`: guard-aware native scripts, and Plutus up to V4.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum Script {
+        Native(NativeScript),
+        Plutus(PlutusScript),
+        /// Tag: 4
+        PlutusV4(Vec<u8>),
+    }
+
+    /// `DijkstraPlutusPurpose`'s tags: the Conway ones plus `Guarding`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub enum RedeemerTag {
+        #[default]
+        Spend,
+        Mint,
+        Cert,
+        Reward,
+        Voting,
+        Proposing,
+        Guarding,
+    }
+
+    /// `DijkstraPlutusPurpose AsIx`.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub struct PlutusPurpose {
+        pub tag: RedeemerTag,
+        pub index: u32,
+    }
+
+    /// `DijkstraPlutusPurpose AsItem`: certificates are [`TxCert`]s, and
+    /// guard scripts are named by hash.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum PlutusPurposeItem {
+        Spending(TxIn),
+        Minting(PolicyId),
+        Certifying(TxCert),
+        Rewarding(RewardAccount),
+        Voting(Voter),
+        Proposing(Box<ProposalProcedureStruct>),
+        Guarding(ScriptHash),
+    }
+}
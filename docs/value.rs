@@ -0,0 +1,289 @@
+//! Arithmetic on multi-asset values and their asset-by-asset comparison.
+//!
+//! `ValueNotConservedUTxO` only reports the consumed and produced values of a
+//! transaction, which can carry dozens of policies.  The helpers below flatten
+//! a value into one quantity per asset class and list the asset classes whose
+//! quantities differ, so that a diagnostic can point at the tokens that
+//! unbalance the transaction and a wallet can work out what to add or remove.
+//!
+//! Like the Haskell `MultiAsset` arithmetic, sums and differences drop the
+//! quantities that cancel out, along with policies left without assets.
+
+use std::collections::BTreeMap;
+
+use crate::rust_rule_errors::*;
+
+/// One asset class of a value: ada, or a token of a minting policy.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AssetClass {
+    Ada,
+    Token { policy: PolicyId, name: AssetName },
+}
+
+/// An asset class whose quantity differs between two values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetDifference {
+    pub asset: AssetClass,
+    pub supplied: i128,
+    pub expected: i128,
+}
+
+impl AssetDifference {
+    /// How much more of the asset was supplied than expected; negative when
+    /// the supplied side falls short.  Saturates at the bounds of `i128`.
+    pub fn surplus(&self) -> i128 {
+        self.supplied.saturating_sub(self.expected)
+    }
+}
+
+impl MultiAsset {
+    /// Whether every quantity is zero.
+    pub fn is_zero(&self) -> bool {
+        self.0
+            .values()
+            .flat_map(BTreeMap::values)
+            .all(|quantity| *quantity == 0)
+    }
+
+    /// Sum of two multiassets, or `None` if a quantity overflows.
+    pub fn checked_add(&self, other: &MultiAsset) -> Option<MultiAsset> {
+        self.combine(other, i128::checked_add)
+    }
+
+    /// Difference of two multiassets, or `None` if a quantity overflows.
+    pub fn checked_sub(&self, other: &MultiAsset) -> Option<MultiAsset> {
+        self.combine(other, i128::checked_sub)
+    }
+
+    fn combine(
+        &self,
+        other: &MultiAsset,
+        op: fn(i128, i128) -> Option<i128>,
+    ) -> Option<MultiAsset> {
+        let mut policies = self.0.clone();
+        for (policy, names) in &other.0 {
+            let assets = policies.entry(policy.clone()).or_default();
+            for (name, quantity) in names {
+                let total = assets.entry(name.clone()).or_default();
+                *total = op(*total, *quantity)?;
+            }
+        }
+        for assets in policies.values_mut() {
+            assets.retain(|_, quantity| *quantity != 0);
+        }
+        policies.retain(|_, assets| !assets.is_empty());
+        Some(MultiAsset(policies))
+    }
+}
+
+impl ValueStruct {
+    pub fn coin(&self) -> Coin {
+        match self {
+            ValueStruct::Coin(coin) | ValueStruct::MultiAsset { coin, .. } => *coin,
+        }
+    }
+
+    /// The tokens of the value, empty for a coin-only value.
+    pub fn assets(&self) -> MultiAsset {
+        match self {
+            ValueStruct::Coin(_) => MultiAsset::default(),
+            ValueStruct::MultiAsset { assets, .. } => assets.clone(),
+        }
+    }
+
+    /// Build a value, coin-only when there are no tokens.
+    pub fn from_parts(coin: Coin, assets: MultiAsset) -> ValueStruct {
+        if assets.0.is_empty() {
+            ValueStruct::Coin(coin)
+        } else {
+            ValueStruct::MultiAsset { coin, assets }
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coin().0 == 0 && self.assets().is_zero()
+    }
+
+    /// Sum of two values, or `None` if the coin or a token quantity
+    /// overflows.
+    pub fn checked_add(&self, other: &ValueStruct) -> Option<ValueStruct> {
+        let coin = self.coin().0.checked_add(other.coin().0)?;
+        let assets = self.assets().checked_add(&other.assets())?;
+        Some(ValueStruct::from_parts(Coin(coin), assets))
+    }
+
+    /// Difference of two values, or `None` if the coin would go negative or
+    /// a token quantity overflows.  Token quantities may go negative, as they
+    /// do in a mint that burns.
+    pub fn checked_sub(&self, other: &ValueStruct) -> Option<ValueStruct> {
+        let coin = self.coin().0.checked_sub(other.coin().0)?;
+        let assets = self.assets().checked_sub(&other.assets())?;
+        Some(ValueStruct::from_parts(Coin(coin), assets))
+    }
+
+    /// Quantity of every asset class in the value, ada first.  Zero
+    /// quantities are left out, so a coin-only value and the same coin with an
+    /// empty multiasset have the same quantities.
+    pub fn quantities(&self) -> BTreeMap<AssetClass, i128> {
+        let (coin, assets) = match self {
+            ValueStruct::Coin(coin) => (coin, None),
+            ValueStruct::MultiAsset { coin, assets } => (coin, Some(assets)),
+        };
+        let tokens = assets.into_iter().flat_map(|assets| {
+            assets.0.iter().flat_map(|(policy, names)| {
+                names.iter().map(move |(name, quantity)| {
+                    let asset = AssetClass::Token {
+                        policy: policy.clone(),
+                        name: name.clone(),
+                    };
+                    (asset, *quantity)
+                })
+            })
+        });
+        std::iter::once((AssetClass::Ada, i128::from(coin.0)))
+            .chain(tokens)
+            .filter(|(_, quantity)| *quantity != 0)
+            .collect()
+    }
+}
+
+/// Every asset class whose quantity differs between `supplied` and
+/// `expected`, in asset-class order, with its [`AssetDifference::surplus`] or
+/// deficit.  An asset class missing from one side counts as zero there.
+pub fn diff(supplied: &ValueStruct, expected: &ValueStruct) -> Vec<AssetDifference> {
+    let supplied = supplied.quantities();
+    let expected = expected.quantities();
+    let mut assets: Vec<&AssetClass> = supplied.keys().chain(expected.keys()).collect();
+    assets.sort();
+    assets.dedup();
+    assets
+        .into_iter()
+        .filter_map(|asset| {
+            let supplied = supplied.get(asset).copied().unwrap_or(0);
+            let expected = expected.get(asset).copied().unwrap_or(0);
+            (supplied != expected).then(|| AssetDifference {
+                asset: asset.clone(),
+                supplied,
+                expected,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_parser::decode_term;
+    use crate::failure_decoder::FromTerm;
+    use ciborium::{ser::into_writer, value::Value};
+
+    fn policy(byte: u8) -> PolicyId {
+        ScriptHash(Hash28([byte; 28]))
+    }
+
+    fn token(byte: u8, name: &str) -> AssetClass {
+        AssetClass::Token {
+            policy: policy(byte),
+            name: AssetName(name.as_bytes().to_vec()),
+        }
+    }
+
+    #[test]
+    fn decodes_zero_ada_and_burned_tokens() {
+        // [0, {policy: {"burn": -5, "keep": 18446744073709551615}}]
+        let message = Value::Array(vec![
+            Value::Integer(0.into()),
+            Value::Map(vec![(
+                Value::Bytes(vec![1; 28]),
+                Value::Map(vec![
+                    (Value::Bytes(b"burn".to_vec()), Value::Integer((-5).into())),
+                    (
+                        Value::Bytes(b"keep".to_vec()),
+                        Value::Integer(u64::MAX.into()),
+                    ),
+                ]),
+            )]),
+        ]);
+        let mut bytes = Vec::new();
+        into_writer(&message, &mut bytes).expect("encode test value");
+        let value = ValueStruct::from_term(&decode_term(bytes.as_slice()).unwrap()).unwrap();
+
+        assert_eq!(
+            value.quantities(),
+            BTreeMap::from([
+                (token(1, "burn"), -5),
+                (token(1, "keep"), i128::from(u64::MAX)),
+            ])
+        );
+    }
+
+    #[test]
+    fn lists_differing_assets_only() {
+        let supplied = ValueStruct::MultiAsset {
+            coin: Coin(2_000_000),
+            assets: MultiAsset(BTreeMap::from([(
+                policy(1),
+                BTreeMap::from([
+                    (AssetName(b"a".to_vec()), 10),
+                    (AssetName(b"b".to_vec()), 3),
+                ]),
+            )])),
+        };
+        let expected = ValueStruct::MultiAsset {
+            coin: Coin(2_000_000),
+            assets: MultiAsset(BTreeMap::from([
+                (policy(1), BTreeMap::from([(AssetName(b"a".to_vec()), 10)])),
+                (policy(2), BTreeMap::from([(AssetName(b"c".to_vec()), 1)])),
+            ])),
+        };
+
+        assert_eq!(
+            diff(&supplied, &expected),
+            vec![
+                AssetDifference {
+                    asset: token(1, "b"),
+                    supplied: 3,
+                    expected: 0,
+                },
+                AssetDifference {
+                    asset: token(2, "c"),
+                    supplied: 0,
+                    expected: 1,
+                },
+            ]
+        );
+        assert!(diff(&ValueStruct::Coin(Coin(5)), &supplied)
+            .iter()
+            .any(|difference| difference.asset == AssetClass::Ada));
+    }
+
+    #[test]
+    fn adds_and_subtracts_values() {
+        let tokens = |quantity| {
+            MultiAsset(BTreeMap::from([(
+                policy(1),
+                BTreeMap::from([(AssetName(b"a".to_vec()), quantity)]),
+            )]))
+        };
+        let inputs = ValueStruct::from_parts(Coin(5_000_000), tokens(10));
+        let burn = ValueStruct::from_parts(Coin(0), tokens(-10));
+
+        let consumed = inputs.checked_add(&burn).unwrap();
+        assert_eq!(consumed, ValueStruct::Coin(Coin(5_000_000)));
+        assert!(consumed
+            .checked_sub(&ValueStruct::Coin(Coin(5_000_000)))
+            .unwrap()
+            .is_zero());
+        assert_eq!(ValueStruct::Coin(Coin(1)).checked_sub(&inputs), None);
+
+        let difference = &diff(&burn, &inputs)[1];
+        assert_eq!(difference.asset, token(1, "a"));
+        assert_eq!(difference.surplus(), -20);
+
+        // Quantities decoded from JSON can reach the bounds of `i128`.
+        let most = ValueStruct::from_parts(Coin(0), tokens(i128::MAX));
+        assert_eq!(most.checked_add(&inputs), None);
+        assert_eq!(burn.checked_sub(&most), None);
+        assert_eq!(diff(&burn, &most)[0].surplus(), i128::MIN);
+    }
+}